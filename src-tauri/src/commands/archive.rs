@@ -1,32 +1,218 @@
 use std::fs::{self, File};
 use std::io;
 use std::path::{Path, PathBuf};
-use crate::models::{FileEntry, CommandError};
+use crate::models::{ActivityEntry, ActivityKind, ActivityLog, ActivityOutcome, FileEntry, CommandError, ConfigManager, ConflictPolicy, ProgressEvent};
 use crate::utils::path_security::validate_path;
-pub use crate::utils::archive::{ArchiveFormat, is_archive, split_virtual_path};
+pub use crate::utils::archive::{ArchiveFormat, detect_format, is_archive, split_virtual_path};
+use crate::utils::archive::{is_compression_format_supported, SplittingWriter};
+use crate::utils::IgnoreList;
 use log::info;
 use tauri::command;
 use zip::ZipArchive;
 use sevenz_rust as sevenz;
 use tar::Archive as TarArchive;
 use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use xz2::read::XzDecoder;
+use xz2::write::XzEncoder;
 use bzip2::read::BzDecoder;
+use bzip2::write::BzEncoder;
 use zstd::stream::read::Decoder as ZstdDecoder;
 use zstd::stream::write::Encoder as ZstdEncoder;
 use iso9660_core::iso9660entry::{IsISO9660Record, ISO9660Record};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::State;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 pub struct ArchiveState(pub AtomicBool);
 
-pub fn remove_items_from_archive(archive_path: PathBuf, internal_paths: Vec<String>) -> Result<(), CommandError> {
-    let format = ArchiveFormat::from_path(&archive_path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
-    
+/// Deletes entries from an archive by rewriting it. `password` is needed to rewrite an encrypted 7z.
+pub fn remove_items_from_archive(archive_path: PathBuf, internal_paths: Vec<String>, password: Option<&str>) -> Result<(), CommandError> {
+    let format = check_removable(&archive_path)?;
+
     match format {
         ArchiveFormat::Zip => remove_from_zip(&archive_path, &internal_paths),
-        _ => Err(CommandError::ArchiveError("Deleting from this archive format is not supported yet.".to_string())),
+        ArchiveFormat::SevenZip => remove_from_sevenz(&archive_path, &internal_paths, password),
+        _ => remove_from_tar(&archive_path, &internal_paths, format),
+    }
+}
+
+/// Fails with a readable error when entries can't be deleted from this archive,
+/// so callers can refuse a whole batch before modifying anything.
+pub fn check_removable(archive_path: &Path) -> Result<ArchiveFormat, CommandError> {
+    let format = detect_format(archive_path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    let name = archive_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    match format {
+        ArchiveFormat::Iso => Err(CommandError::ArchiveError(format!("Cannot delete from {}: ISO images are read-only.", name))),
+        ArchiveFormat::Rar => Err(CommandError::ArchiveError(format!("Cannot delete from {}: RAR archives are read-only.", name))),
+        _ => Ok(format),
+    }
+}
+
+/// `archive.ext.tmp`, written next to the archive before replacing it.
+fn archive_temp_path(archive_path: &Path) -> PathBuf {
+    let mut name = archive_path.as_os_str().to_os_string();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// Swaps the rewritten archive in, or removes it if the rewrite failed. The rename replaces the
+/// original in one step (MoveFileExW with MOVEFILE_REPLACE_EXISTING on Windows), so a failure
+/// leaves it untouched.
+fn replace_archive(archive_path: &Path, temp_path: &Path, rewrite: Result<(), CommandError>) -> Result<(), CommandError> {
+    if let Err(e) = rewrite.and_then(|_| fs::rename(temp_path, archive_path).map_err(|e| CommandError::IoError(e.to_string()))) {
+        let _ = fs::remove_file(temp_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// 7z has no in-place deletion: every kept entry is decoded and recompressed into a new archive.
+fn remove_from_sevenz(archive_path: &Path, internal_paths: &[String], password: Option<&str>) -> Result<(), CommandError> {
+    let temp_path = archive_temp_path(archive_path);
+    let rewrite = rewrite_sevenz(archive_path, &temp_path, internal_paths, password);
+    replace_archive(archive_path, &temp_path, rewrite)
+}
+
+fn rewrite_sevenz(archive_path: &Path, temp_path: &Path, internal_paths: &[String], password: Option<&str>) -> Result<(), CommandError> {
+    let file = File::open(archive_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let len = file.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len();
+    let mut reader = sevenz::SevenZReader::new(file, len, password.unwrap_or("").into()).map_err(map_sevenz_error)?;
+    // Checked before the temp file exists, so a refused edit leaves nothing behind
+    let methods = sevenz_content_methods(reader.archive(), password)?;
+    let output = File::create(temp_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut writer = sevenz::SevenZWriter::new(output).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    writer.set_content_methods(methods);
+
+    reader.for_each_entries(|entry, entry_reader| {
+        if find_matching_internal_path(entry.name(), internal_paths).is_some() {
+            // Solid blocks are decoded sequentially: removed entries still have to be consumed
+            let _ = io::copy(entry_reader, &mut io::sink());
+        } else if entry.is_directory() || !entry.has_stream {
+            writer.push_archive_entry(entry.clone(), None::<&mut File>)?;
+        } else {
+            writer.push_archive_entry(entry.clone(), Some(entry_reader))?;
+        }
+        Ok(true)
+    }).map_err(map_sevenz_error)?;
+
+    writer.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    Ok(())
+}
+
+/// Writer methods reproducing the coders of `archive`: LZMA2, behind AES when the content is
+/// encrypted, as `compress_seven_zip` writes them. Other coders (LZMA, BZip2, filters...) would be
+/// silently swapped for LZMA2, so the edit is refused instead.
+fn sevenz_content_methods(archive: &sevenz::Archive, password: Option<&str>) -> Result<Vec<sevenz::SevenZMethodConfiguration>, CommandError> {
+    let mut encrypted = false;
+    for coder in archive.folders.iter().flat_map(|folder| folder.coders.iter()) {
+        let id = coder.decompression_method_id();
+        if id == sevenz::SevenZMethod::AES256SHA256.id() {
+            encrypted = true;
+        } else if id != sevenz::SevenZMethod::LZMA2.id() {
+            let method = sevenz::SevenZMethod::by_id(id).map(|m| m.name().to_string()).unwrap_or_else(|| format!("{:02X?}", id));
+            return Err(CommandError::ArchiveError(format!("Cannot delete from this 7z archive: its {} compression can't be written back", method)));
+        }
+    }
+
+    let mut methods = Vec::new();
+    if encrypted {
+        let password = password.ok_or_else(|| CommandError::ArchiveError("password required".to_string()))?;
+        methods.push(sevenz::AesEncoderOptions::new(password.into()).into());
+    }
+    methods.push(sevenz::SevenZMethod::LZMA2.into());
+    Ok(methods)
+}
+
+/// Streams the tar through a new file with the same compression, leaving out removed entries.
+fn remove_from_tar(archive_path: &Path, internal_paths: &[String], format: ArchiveFormat) -> Result<(), CommandError> {
+    let temp_path = archive_temp_path(archive_path);
+    let rewrite = rewrite_tar(archive_path, &temp_path, internal_paths, format);
+    replace_archive(archive_path, &temp_path, rewrite)
+}
+
+fn rewrite_tar(archive_path: &Path, temp_path: &Path, internal_paths: &[String], format: ArchiveFormat) -> Result<(), CommandError> {
+    let file = File::open(archive_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let reader: Box<dyn io::Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
+        _ => Box::new(file),
+    };
+    let mut archive = TarArchive::new(reader);
+    let output = io::BufWriter::with_capacity(128 * 1024, File::create(temp_path).map_err(|e| CommandError::IoError(e.to_string()))?);
+
+    match format {
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Builder::new(GzEncoder::new(output, flate2::Compression::default()));
+            copy_tar_entries(&mut archive, &mut tar, internal_paths)?;
+            let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+        ArchiveFormat::TarXz => {
+            let mut tar = tar::Builder::new(XzEncoder::new(output, 6));
+            copy_tar_entries(&mut archive, &mut tar, internal_paths)?;
+            let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+        ArchiveFormat::TarBz2 => {
+            let mut tar = tar::Builder::new(BzEncoder::new(output, bzip2::Compression::default()));
+            copy_tar_entries(&mut archive, &mut tar, internal_paths)?;
+            let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+        ArchiveFormat::TarZst => {
+            let mut tar = tar::Builder::new(ZstdEncoder::new(output, 3).map_err(|e| CommandError::IoError(e.to_string()))?);
+            copy_tar_entries(&mut archive, &mut tar, internal_paths)?;
+            let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+        _ => {
+            let mut tar = tar::Builder::new(output);
+            copy_tar_entries(&mut archive, &mut tar, internal_paths)?;
+            tar.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+fn copy_tar_entries<R: io::Read, W: io::Write>(archive: &mut TarArchive<R>, tar: &mut tar::Builder<W>, internal_paths: &[String]) -> Result<(), CommandError> {
+    for entry in archive.entries().map_err(|e| CommandError::ArchiveError(e.to_string()))? {
+        let mut entry = entry.map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        let name = entry.path().map_err(|e| CommandError::ArchiveError(e.to_string()))?.to_string_lossy().to_string();
+        if find_matching_internal_path(&name, internal_paths).is_some() {
+            continue;
+        }
+        // append_data rewrites long names, which a cloned header would truncate
+        let mut header = entry.header().clone();
+        tar.append_data(&mut header, &name, &mut entry).map_err(|e| CommandError::IoError(e.to_string()))?;
     }
+    Ok(())
+}
+
+/// Returns the requested internal path that `entry_name` is, or lies beneath.
+/// Both sides are normalized to forward slashes without leading/trailing separators.
+fn find_matching_internal_path<'a>(entry_name: &str, internal_paths: &'a [String]) -> Option<&'a String> {
+    let normalized_archive_entry = entry_name.replace('\\', "/");
+    let normalized_archive_entry = normalized_archive_entry.trim_matches('/');
+
+    internal_paths.iter().find(|p| {
+        let normalized_p = p.replace('\\', "/");
+        let normalized_p = normalized_p.trim_matches('/');
+
+        if normalized_p.is_empty() {
+            return false;
+        }
+
+        if normalized_archive_entry == normalized_p {
+            return true;
+        }
+
+        // Directory match: the entry must start with "<dir>/"
+        normalized_archive_entry.starts_with(&format!("{}/", normalized_p))
+    })
 }
 
 fn remove_from_zip(archive_path: &Path, internal_paths: &[String]) -> Result<(), CommandError> {
@@ -38,28 +224,11 @@ fn remove_from_zip(archive_path: &Path, internal_paths: &[String]) -> Result<(),
     let mut writer = zip::ZipWriter::new(temp_file);
 
     for i in 0..archive.len() {
-        let entry = archive.by_index(i).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        // Raw access: entries are copied as-is, so encrypted ones need no password
+        let entry = archive.by_index_raw(i).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
         let entry_name = entry.name().to_string();
         
-        let should_remove = internal_paths.iter().any(|p| {
-            let normalized_p = p.replace('\\', "/");
-            let normalized_archive_entry = entry_name.replace('\\', "/");
-            
-            if normalized_archive_entry == normalized_p {
-                return true;
-            }
-            
-            // Handle directory removal: if normalized_p is a dir, it might end with / or not.
-            // If it ends with /, check starts_with.
-            // If it doesn't, add / and check starts_with.
-            let dir_prefix = if normalized_p.ends_with('/') {
-                normalized_p.clone()
-            } else {
-                format!("{}/", normalized_p)
-            };
-            
-            normalized_archive_entry.starts_with(&dir_prefix)
-        });
+        let should_remove = find_matching_internal_path(&entry_name, internal_paths).is_some();
 
         if !should_remove {
             writer.raw_copy_file(entry).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
@@ -81,7 +250,7 @@ fn remove_from_zip(archive_path: &Path, internal_paths: &[String]) -> Result<(),
 pub fn list_archive_contents(archive_path: String, internal_path: String) -> Result<Vec<FileEntry>, CommandError> {
     let path_buf = validate_path(&archive_path)?;
     let path = path_buf.as_path();
-    let format = ArchiveFormat::from_path(path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    let format = detect_format(path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
 
     match format {
         ArchiveFormat::Zip => list_zip(path, &internal_path),
@@ -144,6 +313,10 @@ fn list_iso(path: &Path, internal_prefix: &str) -> Result<Vec<FileEntry>, Comman
             is_protected: false,
             original_path: None,
             deleted_time: None,
+            group_key: None,
+            is_favorite: false,
+            match_context: None,
+            match_line: None,
         });
     }
 
@@ -159,7 +332,8 @@ fn list_zip(path: &Path, internal_prefix: &str) -> Result<Vec<FileEntry>, Comman
     let mut seen = std::collections::HashSet::new();
 
     for i in 0..archive.len() {
-        let file = archive.by_index(i).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        // Raw access keeps entry names readable in password-protected archives
+        let file = archive.by_index_raw(i).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
         let name = file.name().replace('\\', "/");
         
         if name.starts_with(&prefix) && name != prefix {
@@ -195,6 +369,10 @@ fn list_zip(path: &Path, internal_prefix: &str) -> Result<Vec<FileEntry>, Comman
                 is_protected: false,
                 original_path: None,
                 deleted_time: None,
+                group_key: None,
+                is_favorite: false,
+                match_context: None,
+                match_line: None,
             });
         }
     }
@@ -234,6 +412,10 @@ fn list_seven_zip(path: &Path, internal_prefix: &str) -> Result<Vec<FileEntry>,
                         is_protected: false,
                         original_path: None,
                         deleted_time: None,
+                        group_key: None,
+                        is_favorite: false,
+                        match_context: None,
+                        match_line: None,
                     });
                  }
              }
@@ -289,18 +471,49 @@ fn list_tar(path: &Path, internal_prefix: &str, format: ArchiveFormat) -> Result
                 is_protected: false,
                 original_path: None,
                 deleted_time: None,
+                group_key: None,
+                is_favorite: false,
+                match_context: None,
+                match_line: None,
             });
         }
     }
     Ok(entries)
 }
 
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct RenamedEntry {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(serde::Serialize, Debug, Default)]
+pub struct ExtractSummary {
+    /// Folder the archive was extracted into
+    pub destination: String,
+    /// Existing files left untouched by the conflict policy
+    pub skipped: Vec<String>,
+    pub renamed: Vec<RenamedEntry>,
+}
+
+/// Extracts the whole archive and reports where it went and which files the conflict policy
+/// skipped or renamed. `extract_mode` is "here" (default, straight into `target_dir`),
+/// "subfolder" (into a folder named after the archive) or "smart" (a subfolder unless the
+/// archive has a single root folder). `conflict_policy` defaults to overwriting.
 #[command]
-pub async fn extract_archive(archive_path: String, target_dir: String, state: State<'_, ArchiveState>) -> Result<(), CommandError> {
+pub async fn extract_archive(
+    app: AppHandle,
+    archive_path: String,
+    target_dir: String,
+    password: Option<String>,
+    extract_mode: Option<String>,
+    conflict_policy: Option<ConflictPolicy>,
+    state: State<'_, ArchiveState>,
+) -> Result<ExtractSummary, CommandError> {
     state.0.store(false, Ordering::Relaxed);
     let path_buf = validate_path(&archive_path)?;
     let path = path_buf.as_path();
-    let format = ArchiveFormat::from_path(path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    let format = detect_format(path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
     let target_buf = validate_path(&target_dir)?;
     let target = target_buf.as_path();
 
@@ -310,52 +523,355 @@ pub async fn extract_archive(archive_path: String, target_dir: String, state: St
         fs::create_dir_all(target).map_err(|e| CommandError::IoError(e.to_string()))?;
     }
 
-    match format {
+    let use_subfolder = match extract_mode.as_deref().unwrap_or("here") {
+        "here" => false,
+        "subfolder" => true,
+        "smart" => !has_single_root_folder(&archive_path)?,
+        other => return Err(CommandError::Other(format!("Unknown extract mode: {}", other))),
+    };
+
+    let cancel = &state.0;
+    let mut log = ExtractionLog { policy: conflict_policy.unwrap_or_default(), ..Default::default() };
+    let destination = if use_subfolder {
+        let dir = target.join(archive_base_name(path));
+        log.create_dir_all(&dir)?;
+        dir
+    } else {
+        target.to_path_buf()
+    };
+    let target = destination.as_path();
+    let target_dir = destination.to_string_lossy().to_string();
+
+    let result = match format {
         ArchiveFormat::Zip => {
             let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
             let mut archive = ZipArchive::new(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-            // zip-rs doesn't have an easy way to check cancellation mid-extract without custom implementation
-            // so we'll just check at the start.
-            archive.extract(target).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-        }
-        ArchiveFormat::SevenZip => {
-            sevenz::decompress_file(path, target).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            extract_zip(&mut archive, target, password.as_deref(), cancel, &mut log)
         }
+        ArchiveFormat::SevenZip => extract_seven_zip(path, target, password.as_deref(), cancel, &mut log),
         ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
-            let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
-            let reader: Box<dyn io::Read> = match format {
-                ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
-                ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
-                ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
-                ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
-                _ => Box::new(file),
-            };
-            let mut archive = TarArchive::new(reader);
-            archive.unpack(target).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-        }
-        ArchiveFormat::Iso => {
-            extract_iso(&archive_path, &target_dir)?;
+            extract_tar(path, target, format, cancel, &mut log)
         }
+        ArchiveFormat::Iso => extract_iso(&archive_path, &target_dir, cancel, &mut log),
         ArchiveFormat::Rar => {
             // Rar extraction is not natively supported by our current crates.
             // We could use a library or call 7z.exe if available.
             return Err(CommandError::ArchiveError("Rar extraction requires external tools (like 7-Zip or WinRAR).".to_string()));
         }
+    };
+
+    if matches!(&result, Err(CommandError::Other(m)) if m == "Cancelled") {
+        info!("Extraction of {:?} cancelled, removing {} extracted files", path, log.files.len());
+        log.rollback();
+    }
+    let archive_size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    record_archive_activity(&app, ActivityKind::Extract, vec![path_buf.to_string_lossy().to_string()], Some(target_dir.clone()), archive_size, &result);
+    result.map(|_| ExtractSummary { destination: target_dir, skipped: log.skipped, renamed: log.renamed })
+}
+
+/// Adds a finished extraction or compression to the activity log.
+fn record_archive_activity<T>(app: &AppHandle, kind: ActivityKind, sources: Vec<String>, destination: Option<String>, total_bytes: u64, result: &Result<T, CommandError>) {
+    let outcome = match result {
+        Ok(_) => ActivityOutcome::Completed,
+        Err(CommandError::Other(m)) if m == "Cancelled" => ActivityOutcome::Cancelled,
+        Err(e) => ActivityOutcome::Failed(e.to_string()),
+    };
+    if let Some(activity) = app.try_state::<ActivityLog>() {
+        activity.record(app, ActivityEntry::new(kind, sources, destination, total_bytes, outcome));
+    }
+}
+
+/// True when every entry of the archive lives under one top-level folder.
+fn has_single_root_folder(archive_path: &str) -> Result<bool, CommandError> {
+    let roots = list_archive_contents(archive_path.to_string(), String::new())?;
+    Ok(roots.len() == 1 && roots[0].is_dir)
+}
+
+/// Archive file name without its archive extension(s), e.g. "backup" for "backup.tar.gz".
+fn archive_base_name(path: &Path) -> String {
+    let mut stem = path.file_stem().map(Path::new).unwrap_or(Path::new(""));
+    if stem.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("tar")) {
+        stem = stem.file_stem().map(Path::new).unwrap_or(stem);
+    }
+    match stem.to_string_lossy().to_string() {
+        name if name.is_empty() => "extracted".to_string(),
+        name => name,
+    }
+}
+
+/// Files and folders created by `extract_archive`, so a cancelled run can be undone.
+/// Pre-existing paths are never recorded, so a rollback only removes what the extraction added.
+/// Also applies the conflict policy and records its outcome.
+#[derive(Default)]
+struct ExtractionLog {
+    files: Vec<PathBuf>,
+    dirs: Vec<PathBuf>,
+    policy: ConflictPolicy,
+    skipped: Vec<String>,
+    renamed: Vec<RenamedEntry>,
+}
+
+impl ExtractionLog {
+    /// Path to write an entry bound for `dest`, or None to leave the existing file alone.
+    /// `entry_modified` (ms since epoch) is needed for KeepNewer; unknown dates keep the existing file.
+    fn resolve_conflict(&mut self, dest: &Path, entry_modified: Option<u64>) -> Option<PathBuf> {
+        if !dest.is_file() {
+            return Some(dest.to_path_buf());
+        }
+
+        match self.policy {
+            ConflictPolicy::Overwrite => Some(dest.to_path_buf()),
+            ConflictPolicy::Skip => {
+                self.skipped.push(dest.to_string_lossy().to_string());
+                None
+            }
+            ConflictPolicy::Rename => {
+                let renamed = numbered_path(dest);
+                self.renamed.push(RenamedEntry {
+                    from: dest.to_string_lossy().to_string(),
+                    to: renamed.to_string_lossy().to_string(),
+                });
+                Some(renamed)
+            }
+            ConflictPolicy::KeepNewer => {
+                let existing = fs::metadata(dest).and_then(|m| m.modified()).ok()
+                    .and_then(|t| t.duration_since(std::time::SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64);
+                match (entry_modified, existing) {
+                    (Some(entry), Some(existing)) if entry > existing => Some(dest.to_path_buf()),
+                    _ => {
+                        self.skipped.push(dest.to_string_lossy().to_string());
+                        None
+                    }
+                }
+            }
+        }
+    }
+
+    fn create_dir_all(&mut self, dir: &Path) -> Result<(), CommandError> {
+        let mut missing: Vec<PathBuf> = dir.ancestors().take_while(|d| !d.exists()).map(Path::to_path_buf).collect();
+        fs::create_dir_all(dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+        missing.reverse();
+        self.dirs.extend(missing);
+        Ok(())
+    }
+
+    /// Creates the parent folders of `dest` and records `dest` if it did not exist yet.
+    fn prepare_file(&mut self, dest: &Path) -> Result<(), CommandError> {
+        if let Some(parent) = dest.parent() {
+            self.create_dir_all(parent)?;
+        }
+        if !dest.exists() {
+            self.files.push(dest.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn create_file(&mut self, dest: &Path) -> Result<File, CommandError> {
+        self.prepare_file(dest)?;
+        File::create(dest).map_err(|e| CommandError::IoError(e.to_string()))
+    }
+
+    fn rollback(&self) {
+        for file in self.files.iter().rev() {
+            let _ = fs::remove_file(file);
+        }
+        // Deepest first; folders that still hold other content are left alone
+        for dir in self.dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// First free `name (N).ext` next to `path`, starting at 2.
+fn numbered_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    let parent = path.parent().unwrap_or(Path::new(""));
+    (2..)
+        .map(|n| parent.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn check_cancelled(cancel: &AtomicBool) -> Result<(), CommandError> {
+    if cancel.load(Ordering::Relaxed) {
+        return Err(CommandError::Other("Cancelled".into()));
+    }
+    Ok(())
+}
+
+/// Maps zip errors so the UI can tell a missing password from a wrong one.
+fn map_zip_error(e: zip::result::ZipError) -> CommandError {
+    match e {
+        zip::result::ZipError::InvalidPassword => CommandError::ArchiveError("wrong password".to_string()),
+        zip::result::ZipError::UnsupportedArchive(msg) if msg == zip::result::ZipError::PASSWORD_REQUIRED => {
+            CommandError::ArchiveError("password required".to_string())
+        }
+        other => CommandError::ArchiveError(other.to_string()),
+    }
+}
+
+fn map_sevenz_error(e: sevenz::Error) -> CommandError {
+    match e {
+        sevenz::Error::PasswordRequired => CommandError::ArchiveError("password required".to_string()),
+        sevenz::Error::MaybeBadPassword(_) => CommandError::ArchiveError("wrong password".to_string()),
+        other => CommandError::ArchiveError(other.to_string()),
+    }
+}
+
+/// Extracts every entry of a ZIP, decrypting with `password` when given.
+fn extract_zip(archive: &mut ZipArchive<File>, target: &Path, password: Option<&str>, cancel: &AtomicBool, log: &mut ExtractionLog) -> Result<(), CommandError> {
+    for i in 0..archive.len() {
+        check_cancelled(cancel)?;
+        let mut entry = match password {
+            Some(pw) if !pw.is_empty() => archive.by_index_decrypt(i, pw.as_bytes()),
+            _ => archive.by_index(i),
+        }.map_err(map_zip_error)?;
+
+        // Skip entries with unsafe names (zip-slip)
+        let Some(relative) = entry.enclosed_name() else { continue; };
+        let dest = target.join(relative);
+
+        if entry.is_dir() {
+            log.create_dir_all(&dest)?;
+        } else {
+            let modified = entry.last_modified()
+                .and_then(|dt| {
+                    let t: Result<time::OffsetDateTime, _> = dt.try_into();
+                    t.ok()
+                })
+                .map(|ts| ts.unix_timestamp() as u64 * 1000);
+            let Some(dest) = log.resolve_conflict(&dest, modified) else { continue; };
+            let mut out = log.create_file(&dest)?;
+            io::copy(&mut entry, &mut out).map_err(|e| match e.kind() {
+                io::ErrorKind::InvalidData if password.is_some() => CommandError::ArchiveError("wrong password".to_string()),
+                _ => CommandError::IoError(e.to_string()),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Extracts every entry of a 7z archive one at a time, so cancellation is honoured between entries.
+fn extract_seven_zip(path: &Path, target: &Path, password: Option<&str>, cancel: &AtomicBool, log: &mut ExtractionLog) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let len = file.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len();
+    let password = match password {
+        Some(pw) if !pw.is_empty() => pw.into(),
+        _ => "".into(),
+    };
+    let mut reader = sevenz::SevenZReader::new(file, len, password).map_err(map_sevenz_error)?;
+    let mut failure: Option<CommandError> = None;
+
+    reader.for_each_entries(|entry, entry_reader| {
+        if let Err(e) = check_cancelled(cancel) {
+            failure = Some(e);
+            return Ok(false);
+        }
+
+        let Some(dest) = safe_target_path(target, &entry.name().replace('\\', "/")) else {
+            let _ = io::copy(entry_reader, &mut io::sink());
+            return Ok(true);
+        };
+
+        let written = if entry.is_directory() {
+            log.create_dir_all(&dest)
+        } else {
+            let modified = entry.has_last_modified_date
+                .then(|| std::time::SystemTime::from(entry.last_modified_date()))
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64);
+            let Some(dest) = log.resolve_conflict(&dest, modified) else {
+                let _ = io::copy(entry_reader, &mut io::sink());
+                return Ok(true);
+            };
+            log.create_file(&dest).and_then(|mut out| {
+                io::copy(entry_reader, &mut out).map(|_| ()).map_err(|e| CommandError::IoError(e.to_string()))
+            })
+        };
+
+        if let Err(e) = written {
+            failure = Some(e);
+            return Ok(false);
+        }
+        Ok(true)
+    }).map_err(map_sevenz_error)?;
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+fn extract_tar(path: &Path, target: &Path, format: ArchiveFormat, cancel: &AtomicBool, log: &mut ExtractionLog) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let reader: Box<dyn io::Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
+        _ => Box::new(file),
+    };
+
+    let mut archive = TarArchive::new(reader);
+    for entry in archive.entries().map_err(|e| CommandError::ArchiveError(e.to_string()))? {
+        check_cancelled(cancel)?;
+        let mut entry = entry.map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        let name = entry.path().map_err(|e| CommandError::ArchiveError(e.to_string()))?.to_string_lossy().replace('\\', "/");
+
+        let Some(dest) = safe_target_path(target, &name) else { continue; };
+        if entry.header().entry_type().is_dir() {
+            log.create_dir_all(&dest)?;
+        } else {
+            let modified = entry.header().mtime().ok().map(|secs| secs * 1000);
+            let Some(dest) = log.resolve_conflict(&dest, modified) else { continue; };
+            log.prepare_file(&dest)?;
+            if !unpack_tar_entry(&mut entry, target, &dest)? {
+                log::warn!("Refused unsafe tar entry {}", name);
+            }
+        }
     }
     Ok(())
 }
 
-fn extract_iso(archive_path: &str, target_dir: &str) -> Result<(), CommandError> {
+fn extract_iso(archive_path: &str, target_dir: &str, cancel: &AtomicBool, log: &mut ExtractionLog) -> Result<(), CommandError> {
     let file = File::open(archive_path).map_err(|e| CommandError::IoError(e.to_string()))?;
     let mut iso = iso9660_core::ISO9660::load(file).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+    // Entry dates for KeepNewer, keyed by their path in the image
+    let times = File::open(archive_path)
+        .map(|mut f| crate::utils::archive::iso_modified_times(&mut f))
+        .unwrap_or_default();
     
-    extract_iso_recursive(&mut iso, "/", target_dir)
+    extract_iso_recursive(&mut iso, "/", "/", target_dir, &mut |_: &str, dest: &Path, is_dir: bool| {
+        check_cancelled(cancel)?;
+        if is_dir {
+            log.create_dir_all(dest)?;
+            return Ok(Some(dest.to_path_buf()));
+        }
+        let modified = dest.strip_prefix(target_dir).ok()
+            .map(|relative| format!("/{}", relative.to_string_lossy().replace('\\', "/").to_lowercase()))
+            .and_then(|key| times.get(&key).copied());
+        match log.resolve_conflict(dest, modified) {
+            Some(dest) => {
+                log.prepare_file(&dest)?;
+                Ok(Some(dest))
+            }
+            None => Ok(None),
+        }
+    })
 }
 
+/// Extracts the ISO directory `internal_path` into `target_base`.
+/// Output paths are relative to `root`. `on_entry` is called with the name, destination and
+/// whether it's a folder before each entry is written. It creates folders itself, and returns the
+/// path to write a file to, or None to skip the entry (a folder's whole content with it).
 fn extract_iso_recursive<T: iso9660_core::block_device::ISORead>(
     iso: &mut iso9660_core::ISO9660<T>,
     internal_path: &str,
-    target_base: &str
+    root: &str,
+    target_base: &str,
+    on_entry: &mut dyn FnMut(&str, &Path, bool) -> Result<Option<PathBuf>, CommandError>,
 ) -> Result<(), CommandError> {
     let mut iter = iso.listdir(internal_path).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
     
@@ -379,31 +895,22 @@ fn extract_iso_recursive<T: iso9660_core::block_device::ISORead>(
             format!("{}/{}", internal_path.trim_end_matches('/'), display_name)
         };
         
-        let relative_path = new_internal.trim_start_matches('/').replace('/', "\\");
+        let relative_path = new_internal
+            .strip_prefix(root.trim_end_matches('/'))
+            .unwrap_or(&new_internal)
+            .trim_start_matches('/')
+            .replace('/', "\\");
         let target_path = Path::new(target_base).join(&relative_path);
         
         match rec {
             ISO9660Record::Directory(_) => {
-                fs::create_dir_all(&target_path).map_err(|e| CommandError::IoError(e.to_string()))?;
-                extract_iso_recursive(iso, &new_internal, target_base)?;
+                if on_entry(display_name, &target_path, true)?.is_some() {
+                    extract_iso_recursive(iso, &new_internal, root, target_base, on_entry)?;
+                }
             }
             ISO9660Record::File(_) => {
-                if let Some(parent) = target_path.parent() {
-                    fs::create_dir_all(parent).map_err(|e| CommandError::IoError(e.to_string()))?;
-                }
-                
-                let size = iso.total_size(&new_internal).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
-                let mut writer = File::create(target_path).map_err(|e| CommandError::IoError(e.to_string()))?;
-                
-                let mut offset = 0;
-                let mut buf = [0u8; 65536];
-                while offset < size {
-                    let to_read = std::cmp::min(buf.len(), size - offset);
-                    let n = iso.read(&new_internal, &mut buf[..to_read], offset).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
-                    if n == 0 { break; }
-                    use std::io::Write;
-                    writer.write_all(&buf[..n]).map_err(|e| CommandError::IoError(e.to_string()))?;
-                    offset += n;
+                if let Some(dest) = on_entry(display_name, &target_path, false)? {
+                    extract_iso_file(iso, &new_internal, &dest)?;
                 }
             }
         }
@@ -411,75 +918,1207 @@ fn extract_iso_recursive<T: iso9660_core::block_device::ISORead>(
     Ok(())
 }
 
-#[command]
-pub async fn cancel_archive_operation(state: State<'_, ArchiveState>) -> Result<(), CommandError> {
-    state.0.store(true, Ordering::Relaxed);
+fn extract_iso_file<T: iso9660_core::block_device::ISORead>(
+    iso: &mut iso9660_core::ISO9660<T>,
+    internal_path: &str,
+    target_path: &Path,
+) -> Result<(), CommandError> {
+    if let Some(parent) = target_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| CommandError::IoError(e.to_string()))?;
+    }
+    
+    let size = iso.total_size(internal_path).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+    let mut writer = File::create(target_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    
+    let mut offset = 0;
+    let mut buf = [0u8; 65536];
+    while offset < size {
+        let to_read = std::cmp::min(buf.len(), size - offset);
+        let n = iso.read(internal_path, &mut buf[..to_read], offset).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+        if n == 0 { break; }
+        use std::io::Write;
+        writer.write_all(&buf[..n]).map_err(|e| CommandError::IoError(e.to_string()))?;
+        offset += n;
+    }
     Ok(())
 }
 
-#[command]
-pub async fn compress_to_archive(paths: Vec<String>, archive_path: String, format: String, quality: String, state: State<'_, ArchiveState>) -> Result<(), CommandError> {
-    state.0.store(false, Ordering::Relaxed);
-    let target_path_buf = validate_path(&archive_path)?;
-    let target_path = target_path_buf.as_path();
-    
-    info!("Compressing {:?} items to {:?}", paths.len(), target_path);
-    
-    let result = match format.to_lowercase().as_str() {
-        "zip" => compress_zip(paths, target_path, &quality, &state),
-        "7z" => compress_seven_zip(paths, target_path, &quality, &state),
-        "tar" => compress_tar(paths, target_path, false, &state),
-        "zst" | "tar.zst" => compress_tar_zst(paths, target_path, &quality, &state),
-        _ => Err(CommandError::ArchiveError("Unsupported format".to_string())),
-    };
+/// Throttled "progress" emitter for archive tasks (counts entries, not bytes).
+struct ArchiveProgress<'a> {
+    app: &'a AppHandle,
+    id: String,
+    task: &'static str,
+    current: u64,
+    total: u64,
+    last_emit: Instant,
+}
 
-    if result.is_err()
-        && target_path.exists() {
-            let _ = fs::remove_file(target_path);
+impl<'a> ArchiveProgress<'a> {
+    fn new(app: &'a AppHandle, task: &'static str, total: u64) -> Self {
+        Self {
+            app,
+            id: format!("{}_op", task),
+            task,
+            current: 0,
+            total,
+            last_emit: Instant::now(),
         }
+    }
 
-    result
+    fn step(&mut self, filename: &str) {
+        self.current += 1;
+        if self.last_emit.elapsed().as_millis() > 100 {
+            self.emit("running", Some(filename.to_string()));
+            self.last_emit = Instant::now();
+        }
+    }
+
+    fn emit(&self, status: &str, filename: Option<String>) {
+        let _ = self.app.emit("progress", ProgressEvent {
+            id: self.id.clone(),
+            task: self.task.to_string(),
+            current: self.current,
+            total: self.total.max(self.current),
+            status: status.to_string(),
+            filename,
+        });
+    }
 }
 
-#[command]
-pub async fn add_to_archive(paths: Vec<String>, archive_path: String, state: State<'_, ArchiveState>) -> Result<(), CommandError> {
-    state.0.store(false, Ordering::Relaxed);
-    let target_path_buf = validate_path(&archive_path)?;
-    let target_path = target_path_buf.as_path();
-    
-    let format = ArchiveFormat::from_path(target_path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
-    
-    match format {
-        ArchiveFormat::Zip => add_to_zip(paths, target_path, &state),
-        _ => Err(CommandError::ArchiveError("Adding to this archive format is not supported yet.".to_string())),
+/// Maps an archive entry to its output path relative to the extraction target:
+/// the parent of the matched internal path is stripped so the selected item lands
+/// directly in the target, with its descendants below it.
+fn extraction_relative_path(entry_name: &str, matched: &str) -> String {
+    let entry = entry_name.replace('\\', "/");
+    let entry = entry.trim_matches('/');
+    let matched = matched.replace('\\', "/");
+    let matched = matched.trim_matches('/');
+
+    match matched.rsplit_once('/') {
+        Some((parent, _)) => entry.strip_prefix(parent).unwrap_or(entry).trim_start_matches('/').to_string(),
+        None => entry.to_string(),
     }
 }
 
-fn add_to_zip(paths: Vec<String>, target: &Path, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
-    let file = fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .open(target)
-        .map_err(|e| CommandError::IoError(e.to_string()))?;
+/// Joins an archive-relative path onto `target`, refusing anything that would escape it.
+fn safe_target_path(target: &Path, relative: &str) -> Option<PathBuf> {
+    let mut out = target.to_path_buf();
+    let mut pushed = false;
+    for part in relative.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        if part == ".." || part.contains(':') {
+            return None;
+        }
+        out.push(part);
+        pushed = true;
+    }
+    if pushed { Some(out) } else { None }
+}
 
-    let mut zip = zip::ZipWriter::new_append(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-    
-    let options = zip::write::SimpleFileOptions::default()
-        .compression_method(zip::CompressionMethod::Deflated)
-        .unix_permissions(0o755);
+/// Writes a tar entry to `dest`, with the guarantees of `Entry::unpack_in(target)`: nothing is
+/// written through an existing link that leads out of `target`, and symlinks pointing outside it
+/// are refused. Hard links are refused too, since their source is not resolved against `target`.
+/// Returns false for a refused entry.
+fn unpack_tar_entry<R: io::Read>(entry: &mut tar::Entry<'_, R>, target: &Path, dest: &Path) -> Result<bool, CommandError> {
+    let entry_type = entry.header().entry_type();
+    if entry_type.is_hard_link() {
+        return Ok(false);
+    }
+    if entry_type.is_symlink() {
+        let link = entry.link_name().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        let base = dest.parent().and_then(|p| p.strip_prefix(target).ok());
+        match (link, base) {
+            (Some(link), Some(base)) if link_stays_inside(base, &link) => {}
+            _ => return Ok(false),
+        }
+    }
 
-    for p in paths {
-        if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
-        let path = Path::new(&p);
-        let parent = path.parent().unwrap_or(path);
-        
-        if path.is_dir() {
-            for entry in walkdir::WalkDir::new(path) {
-                if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
-                let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
-                let entry_path = entry.path();
-                let name = entry_path.strip_prefix(parent).map_err(|e| CommandError::PathError(e.to_string()))?;
-                let name_str = name.to_string_lossy().replace('\\', "/");
+    let (Ok(root), Some(Ok(parent))) = (fs::canonicalize(target), dest.parent().map(fs::canonicalize)) else {
+        return Ok(false);
+    };
+    if !parent.starts_with(&root) {
+        return Ok(false);
+    }
+
+    entry.unpack(dest).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    Ok(true)
+}
+
+/// Whether a relative symlink `link` placed in `base` (relative to the extraction root) resolves
+/// to a path still inside that root.
+fn link_stays_inside(base: &Path, link: &Path) -> bool {
+    let mut depth = base.components().count();
+    for component in link.components() {
+        match component {
+            std::path::Component::Normal(_) => depth += 1,
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir if depth > 0 => depth -= 1,
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[command]
+pub async fn extract_archive_entries(
+    app: AppHandle,
+    archive_path: String,
+    internal_paths: Vec<String>,
+    target_dir: String,
+    state: State<'_, ArchiveState>,
+) -> Result<(), CommandError> {
+    state.0.store(false, Ordering::Relaxed);
+    let path_buf = validate_path(&archive_path)?;
+    let path = path_buf.as_path();
+    let format = detect_format(path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    let target_buf = validate_path(&target_dir)?;
+    let target = target_buf.as_path();
+
+    if internal_paths.is_empty() {
+        return Ok(());
+    }
+
+    info!("Extracting {} entries from {:?} to {:?}", internal_paths.len(), path, target);
+
+    if !target.exists() {
+        fs::create_dir_all(target).map_err(|e| CommandError::IoError(e.to_string()))?;
+    }
+
+    let result = match format {
+        ArchiveFormat::Zip => extract_zip_entries(&app, path, &internal_paths, target, &state),
+        ArchiveFormat::SevenZip => extract_seven_zip_entries(&app, path, &internal_paths, target, &state),
+        ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
+            extract_tar_entries(&app, path, &internal_paths, target, format, &state)
+        }
+        ArchiveFormat::Iso => extract_iso_entries(&app, path, &internal_paths, target, &state),
+        ArchiveFormat::Rar => Err(CommandError::ArchiveError("Rar extraction requires external tools (like 7-Zip or WinRAR).".to_string())),
+    };
+
+    if let Err(e) = &result {
+        let status = if matches!(e, CommandError::Other(m) if m == "Cancelled") { "cancelled" } else { "error" };
+        ArchiveProgress::new(&app, "extract", 0).emit(status, None);
+    }
+
+    result
+}
+
+fn extract_zip_entries(app: &AppHandle, path: &Path, internal_paths: &[String], target: &Path, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+
+    let total = archive.file_names().filter(|n| find_matching_internal_path(n, internal_paths).is_some()).count() as u64;
+    let mut progress = ArchiveProgress::new(app, "extract", total);
+
+    for i in 0..archive.len() {
+        if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let mut entry = archive.by_index(i).map_err(map_zip_error)?;
+        let name = entry.name().to_string();
+
+        let Some(matched) = find_matching_internal_path(&name, internal_paths) else { continue; };
+        // Reject entries with unsafe names (zip-slip)
+        if entry.enclosed_name().is_none() { continue; }
+        let Some(dest) = safe_target_path(target, &extraction_relative_path(&name, matched)) else { continue; };
+
+        if entry.is_dir() {
+            fs::create_dir_all(&dest).map_err(|e| CommandError::IoError(e.to_string()))?;
+        } else {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| CommandError::IoError(e.to_string()))?;
+            }
+            let mut out = File::create(&dest).map_err(|e| CommandError::IoError(e.to_string()))?;
+            io::copy(&mut entry, &mut out).map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+        progress.step(&name);
+    }
+
+    progress.emit("completed", None);
+    Ok(())
+}
+
+fn extract_seven_zip_entries(app: &AppHandle, path: &Path, internal_paths: &[String], target: &Path, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let len = file.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len();
+    let mut reader = sevenz::SevenZReader::new(file, len, "".into()).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+
+    let total = reader.archive().files.iter().filter(|f| find_matching_internal_path(f.name(), internal_paths).is_some()).count() as u64;
+    let mut progress = ArchiveProgress::new(app, "extract", total);
+    let mut failure: Option<CommandError> = None;
+
+    reader.for_each_entries(|entry, entry_reader| {
+        if state.0.load(Ordering::Relaxed) {
+            failure = Some(CommandError::Other("Cancelled".into()));
+            return Ok(false);
+        }
+
+        let name = entry.name().to_string();
+        let dest = find_matching_internal_path(&name, internal_paths)
+            .and_then(|matched| safe_target_path(target, &extraction_relative_path(&name, matched)));
+
+        let Some(dest) = dest else {
+            // Solid blocks are decoded sequentially: skipped entries still have to be consumed
+            let _ = io::copy(entry_reader, &mut io::sink());
+            return Ok(true);
+        };
+
+        let written = if entry.is_directory() {
+            fs::create_dir_all(&dest)
+        } else {
+            dest.parent().map(fs::create_dir_all).unwrap_or(Ok(()))
+                .and_then(|_| File::create(&dest))
+                .and_then(|mut out| io::copy(entry_reader, &mut out).map(|_| ()))
+        };
+
+        if let Err(e) = written {
+            failure = Some(CommandError::IoError(e.to_string()));
+            return Ok(false);
+        }
+
+        progress.step(&name);
+        Ok(true)
+    }).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+
+    if let Some(e) = failure {
+        return Err(e);
+    }
+
+    progress.emit("completed", None);
+    Ok(())
+}
+
+fn extract_tar_entries(app: &AppHandle, path: &Path, internal_paths: &[String], target: &Path, format: ArchiveFormat, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let reader: Box<dyn io::Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
+        _ => Box::new(file),
+    };
+
+    // Tar is a stream: the entry count is unknown until the end
+    let mut progress = ArchiveProgress::new(app, "extract", 0);
+    let mut archive = TarArchive::new(reader);
+
+    for entry in archive.entries().map_err(|e| CommandError::ArchiveError(e.to_string()))? {
+        if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let mut entry = entry.map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        let name = entry.path().map_err(|e| CommandError::ArchiveError(e.to_string()))?.to_string_lossy().to_string();
+
+        let Some(matched) = find_matching_internal_path(&name, internal_paths) else { continue; };
+        let Some(dest) = safe_target_path(target, &extraction_relative_path(&name, matched)) else { continue; };
+
+        if entry.header().entry_type().is_dir() {
+            fs::create_dir_all(&dest).map_err(|e| CommandError::IoError(e.to_string()))?;
+        } else {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|e| CommandError::IoError(e.to_string()))?;
+            }
+            if !unpack_tar_entry(&mut entry, target, &dest)? {
+                log::warn!("Refused unsafe tar entry {}", name);
+                continue;
+            }
+        }
+        progress.step(&name);
+    }
+
+    progress.emit("completed", None);
+    Ok(())
+}
+
+fn extract_iso_entries(app: &AppHandle, path: &Path, internal_paths: &[String], target: &Path, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut iso = iso9660_core::ISO9660::load(file).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+    let target_str = target.to_string_lossy().to_string();
+    let mut progress = ArchiveProgress::new(app, "extract", 0);
+
+    for internal in internal_paths {
+        if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+
+        let normalized = format!("/{}", internal.replace('\\', "/").trim_matches('/'));
+        let (parent, name) = normalized.rsplit_once('/').unwrap_or(("", &normalized));
+        let parent = if parent.is_empty() { "/" } else { parent };
+
+        // Find the record in its parent directory to know whether it is a file or a folder
+        let mut iter = iso.listdir(parent).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+        let mut is_dir = None;
+        while let Some(rec) = iter.next(&mut iso) {
+            let (ident, dir) = match &rec {
+                ISO9660Record::Directory(d) => (d.identifier(), true),
+                ISO9660Record::File(f) => (f.identifier(), false),
+            };
+            if ident.split(';').next().unwrap_or(&ident).eq_ignore_ascii_case(name) {
+                is_dir = Some(dir);
+                break;
+            }
+        }
+
+        let Some(dest) = safe_target_path(target, name) else { continue; };
+        match is_dir {
+            Some(true) => {
+                fs::create_dir_all(&dest).map_err(|e| CommandError::IoError(e.to_string()))?;
+                extract_iso_recursive(&mut iso, &normalized, parent, &target_str, &mut |file_name: &str, dest: &Path, is_dir: bool| {
+                    if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+                    if is_dir {
+                        fs::create_dir_all(dest).map_err(|e| CommandError::IoError(e.to_string()))?;
+                    } else {
+                        progress.step(file_name);
+                    }
+                    Ok(Some(dest.to_path_buf()))
+                })?;
+            }
+            Some(false) => {
+                extract_iso_file(&mut iso, &normalized, &dest)?;
+                progress.step(name);
+            }
+            None => return Err(CommandError::ArchiveError(format!("Entry not found in archive: {}", internal))),
+        }
+    }
+
+    progress.emit("completed", None);
+    Ok(())
+}
+
+/// An archive entry extracted to a temp file so it can be opened with its default program.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct OpenedArchiveEntry {
+    pub archive_path: String,
+    pub internal_path: String,
+    pub temp_path: String,
+    #[serde(skip)]
+    modified: Option<std::time::SystemTime>,
+}
+
+/// Temp copies made by `open_archive_entry`. They are deleted when the app exits.
+#[derive(Default)]
+pub struct ArchiveTempFiles(pub Mutex<Vec<OpenedArchiveEntry>>);
+
+impl ArchiveTempFiles {
+    pub fn cleanup(&self) {
+        let Ok(mut entries) = self.0.lock() else { return; };
+        for entry in entries.drain(..) {
+            // Each entry lives alone in its own folder
+            if let Some(dir) = Path::new(&entry.temp_path).parent() {
+                let _ = fs::remove_dir_all(dir);
+            }
+        }
+    }
+}
+
+fn file_modified(path: &Path) -> Option<std::time::SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Extracts a single file from the archive to a temp folder and opens it like `open_item`.
+/// Returns the temp path so the UI can watch it for edits.
+#[command]
+pub async fn open_archive_entry(
+    app: AppHandle,
+    archive_path: String,
+    internal_path: String,
+    state: State<'_, ArchiveState>,
+    temp_files: State<'_, ArchiveTempFiles>,
+) -> Result<String, CommandError> {
+    state.0.store(false, Ordering::Relaxed);
+    let path_buf = validate_path(&archive_path)?;
+    let path = path_buf.as_path();
+    let format = detect_format(path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    let internal = internal_path.replace('\\', "/").trim_matches('/').to_string();
+    let file_name = internal.rsplit('/').next().unwrap_or_default().to_string();
+    if file_name.is_empty() {
+        return Err(CommandError::ArchiveError("No entry specified".to_string()));
+    }
+
+    let temp_dir = std::env::temp_dir().join("oxyde_archive_entries").join(uuid::Uuid::new_v4().to_string());
+    fs::create_dir_all(&temp_dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+
+    let internal_paths = vec![internal.clone()];
+    let result = match format {
+        ArchiveFormat::Zip => extract_zip_entries(&app, path, &internal_paths, &temp_dir, &state),
+        ArchiveFormat::SevenZip => extract_seven_zip_entries(&app, path, &internal_paths, &temp_dir, &state),
+        ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
+            extract_tar_entries(&app, path, &internal_paths, &temp_dir, format, &state)
+        }
+        ArchiveFormat::Iso => extract_iso_entries(&app, path, &internal_paths, &temp_dir, &state),
+        ArchiveFormat::Rar => Err(CommandError::ArchiveError("Rar extraction requires external tools (like 7-Zip or WinRAR).".to_string())),
+    };
+
+    let temp_path = temp_dir.join(&file_name);
+    if let Err(e) = result.and_then(|_| {
+        if temp_path.is_file() { Ok(()) } else { Err(CommandError::ArchiveError(format!("Entry not found in archive: {}", internal))) }
+    }) {
+        let _ = fs::remove_dir_all(&temp_dir);
+        return Err(e);
+    }
+
+    let temp_str = temp_path.to_string_lossy().to_string();
+    if let Ok(mut entries) = temp_files.0.lock() {
+        entries.push(OpenedArchiveEntry {
+            archive_path: path_buf.to_string_lossy().to_string(),
+            internal_path: internal,
+            temp_path: temp_str.clone(),
+            modified: file_modified(&temp_path),
+        });
+    }
+
+    crate::commands::system::open_item(app.clone(), temp_str.clone()).await?;
+    Ok(temp_str)
+}
+
+/// Opened archive entries whose temp copy was modified since it was extracted or last saved.
+#[command]
+pub fn get_modified_archive_entries(temp_files: State<'_, ArchiveTempFiles>) -> Result<Vec<OpenedArchiveEntry>, CommandError> {
+    let entries = temp_files.0.lock().map_err(|_| CommandError::Other("Archive temp files lock poisoned".to_string()))?;
+    Ok(entries.iter()
+        .filter(|e| file_modified(Path::new(&e.temp_path)) != e.modified)
+        .cloned()
+        .collect())
+}
+
+/// Writes an edited temp copy back into its archive, replacing the original entry.
+#[command]
+pub async fn save_archive_entry(temp_path: String, temp_files: State<'_, ArchiveTempFiles>) -> Result<(), CommandError> {
+    let entry = {
+        let entries = temp_files.0.lock().map_err(|_| CommandError::Other("Archive temp files lock poisoned".to_string()))?;
+        entries.iter().find(|e| e.temp_path == temp_path).cloned()
+            .ok_or(CommandError::Other("Not an opened archive entry".to_string()))?
+    };
+
+    let archive_path = PathBuf::from(&entry.archive_path);
+    let format = detect_format(&archive_path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    match format {
+        ArchiveFormat::Zip => replace_in_zip(&archive_path, &entry.internal_path, Path::new(&entry.temp_path))?,
+        _ => return Err(CommandError::ArchiveError("Updating this archive format is not supported yet.".to_string())),
+    }
+
+    if let Ok(mut entries) = temp_files.0.lock() {
+        if let Some(e) = entries.iter_mut().find(|e| e.temp_path == temp_path) {
+            e.modified = file_modified(Path::new(&temp_path));
+        }
+    }
+    Ok(())
+}
+
+/// Replaces (or adds) `internal_path` in a ZIP with the contents of `source`.
+fn replace_in_zip(archive_path: &Path, internal_path: &str, source: &Path) -> Result<(), CommandError> {
+    remove_from_zip(archive_path, &[internal_path.to_string()])?;
+
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(archive_path)
+        .map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut zip = zip::ZipWriter::new_append(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    zip.start_file(internal_path, options).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    let mut f = File::open(source).map_err(|e| CommandError::IoError(e.to_string()))?;
+    io::copy(&mut f, &mut zip).map_err(|e| CommandError::IoError(e.to_string()))?;
+    zip.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    Ok(())
+}
+
+#[derive(serde::Serialize, Debug, Default)]
+pub struct ArchiveEntryContent {
+    pub data: Vec<u8>,
+    pub size: u64,
+    pub truncated: bool,
+}
+
+/// Reads up to `max_bytes` of a single archive entry in memory, e.g. for previews.
+#[command]
+pub async fn read_archive_entry(archive_path: String, internal_path: String, max_bytes: u64) -> Result<ArchiveEntryContent, CommandError> {
+    let path_buf = validate_path(&archive_path)?;
+    let format = detect_format(&path_buf).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    let internal = internal_path.replace('\\', "/").trim_matches('/').to_string();
+    if internal.is_empty() {
+        return Err(CommandError::ArchiveError("No entry specified".to_string()));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let path = path_buf.as_path();
+        match format {
+            ArchiveFormat::Zip => read_zip_entry(path, &internal, max_bytes),
+            ArchiveFormat::SevenZip => read_seven_zip_entry(path, &internal, max_bytes),
+            ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
+                read_tar_entry(path, &internal, format, max_bytes)
+            }
+            ArchiveFormat::Iso => read_iso_entry(path, &internal, max_bytes),
+            ArchiveFormat::Rar => Err(CommandError::ArchiveError("Rar navigation not supported yet. Please extract it first.".to_string())),
+        }
+    }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
+}
+
+/// Reads at most `max_bytes` from `reader`, reporting whether more data was available.
+fn read_capped<R: io::Read + ?Sized>(reader: &mut R, max_bytes: u64) -> io::Result<(Vec<u8>, bool)> {
+    use std::io::Read;
+    let mut data = Vec::new();
+    reader.take(max_bytes.saturating_add(1)).read_to_end(&mut data)?;
+    let truncated = data.len() as u64 > max_bytes;
+    data.truncate(max_bytes as usize);
+    Ok((data, truncated))
+}
+
+fn same_entry_name(entry_name: &str, internal: &str) -> bool {
+    entry_name.replace('\\', "/").trim_matches('/') == internal
+}
+
+fn read_zip_entry(path: &Path, internal: &str, max_bytes: u64) -> Result<ArchiveEntryContent, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+
+    let index = (0..archive.len())
+        .find(|&i| archive.name_for_index(i).map(|n| same_entry_name(n, internal)).unwrap_or(false))
+        .ok_or(CommandError::ArchiveError(format!("Entry not found in archive: {}", internal)))?;
+
+    let mut entry = archive.by_index(index).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    if entry.is_dir() {
+        return Err(CommandError::ArchiveError("Cannot read a directory entry".to_string()));
+    }
+    let size = entry.size();
+    let (data, truncated) = read_capped(&mut entry, max_bytes).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    Ok(ArchiveEntryContent { data, size, truncated })
+}
+
+fn read_seven_zip_entry(path: &Path, internal: &str, max_bytes: u64) -> Result<ArchiveEntryContent, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let len = file.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len();
+    let mut reader = sevenz::SevenZReader::new(file, len, "".into()).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+
+    let mut found: Option<Result<ArchiveEntryContent, CommandError>> = None;
+    reader.for_each_entries(|entry, entry_reader| {
+        if entry.is_directory() || !same_entry_name(entry.name(), internal) {
+            let _ = io::copy(entry_reader, &mut io::sink());
+            return Ok(true);
+        }
+        found = Some(
+            read_capped(entry_reader, max_bytes)
+                .map(|(data, truncated)| ArchiveEntryContent { data, size: entry.size(), truncated })
+                .map_err(|e| CommandError::ArchiveError(e.to_string()))
+        );
+        Ok(false)
+    }).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+
+    found.unwrap_or_else(|| Err(CommandError::ArchiveError(format!("Entry not found in archive: {}", internal))))
+}
+
+fn read_tar_entry(path: &Path, internal: &str, format: ArchiveFormat, max_bytes: u64) -> Result<ArchiveEntryContent, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let reader: Box<dyn io::Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
+        _ => Box::new(file),
+    };
+
+    let mut archive = TarArchive::new(reader);
+    for entry in archive.entries().map_err(|e| CommandError::ArchiveError(e.to_string()))? {
+        let mut entry = entry.map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        let name = entry.path().map_err(|e| CommandError::ArchiveError(e.to_string()))?.to_string_lossy().to_string();
+        if entry.header().entry_type().is_dir() || !same_entry_name(&name, internal) {
+            continue;
+        }
+        let size = entry.header().size().unwrap_or(0);
+        let (data, truncated) = read_capped(&mut entry, max_bytes).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        return Ok(ArchiveEntryContent { data, size, truncated });
+    }
+
+    Err(CommandError::ArchiveError(format!("Entry not found in archive: {}", internal)))
+}
+
+fn read_iso_entry(path: &Path, internal: &str, max_bytes: u64) -> Result<ArchiveEntryContent, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut iso = iso9660_core::ISO9660::load(file).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+    let internal_path = format!("/{}", internal);
+
+    let size = iso.total_size(&internal_path).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+    let to_read = std::cmp::min(size as u64, max_bytes) as usize;
+    let mut data = vec![0u8; to_read];
+
+    let mut offset = 0;
+    while offset < to_read {
+        let n = iso.read(&internal_path, &mut data[offset..], offset).map_err(|e| CommandError::ArchiveError(format!("{:?}", e)))?;
+        if n == 0 { break; }
+        offset += n;
+    }
+    data.truncate(offset);
+
+    Ok(ArchiveEntryContent { data, size: size as u64, truncated: (size as u64) > max_bytes })
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct ArchiveEntryTest {
+    pub name: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, Default)]
+pub struct ArchiveTestResult {
+    pub ok: bool,
+    pub entries: Vec<ArchiveEntryTest>,
+}
+
+impl ArchiveTestResult {
+    fn record(&mut self, name: String, outcome: Result<(), String>) {
+        self.entries.push(ArchiveEntryTest {
+            name,
+            ok: outcome.is_ok(),
+            error: outcome.err(),
+        });
+    }
+}
+
+/// Decompresses every entry to a sink to detect truncated or corrupt archives.
+/// ZIP entries are also checked against their stored CRC32.
+#[command]
+pub async fn test_archive(app: AppHandle, archive_path: String) -> Result<ArchiveTestResult, CommandError> {
+    app.state::<ArchiveState>().0.store(false, Ordering::Relaxed);
+    let path_buf = validate_path(&archive_path)?;
+    let format = detect_format(&path_buf).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+
+    tokio::task::spawn_blocking(move || {
+        let state = app.state::<ArchiveState>();
+        let path = path_buf.as_path();
+        let mut result = match format {
+            ArchiveFormat::Zip => test_zip(&app, path, &state.0),
+            ArchiveFormat::SevenZip => test_seven_zip(&app, path, &state.0),
+            ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
+                test_tar(&app, path, format, &state.0)
+            }
+            _ => Err(CommandError::ArchiveError("Testing this archive format is not supported yet.".to_string())),
+        };
+
+        let progress = ArchiveProgress::new(&app, "test", 0);
+        match &mut result {
+            Ok(summary) => {
+                summary.ok = summary.entries.iter().all(|e| e.ok);
+                progress.emit("completed", None);
+            }
+            Err(CommandError::Other(m)) if m == "Cancelled" => progress.emit("cancelled", None),
+            Err(_) => progress.emit("error", None),
+        }
+        result
+    }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
+}
+
+fn test_zip(app: &AppHandle, path: &Path, cancel: &AtomicBool) -> Result<ArchiveTestResult, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    let mut summary = ArchiveTestResult::default();
+    let mut progress = ArchiveProgress::new(app, "test", archive.len() as u64);
+
+    for i in 0..archive.len() {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let name = archive.name_for_index(i).unwrap_or_default().to_string();
+
+        // The zip reader validates the CRC32 once the entry is fully read
+        let outcome = match archive.by_index(i) {
+            Ok(mut entry) => io::copy(&mut entry, &mut io::sink()).map(|_| ()).map_err(|e| e.to_string()),
+            Err(e) => Err(map_zip_error(e).to_string()),
+        };
+        summary.record(name.clone(), outcome);
+        progress.step(&name);
+    }
+    Ok(summary)
+}
+
+fn test_seven_zip(app: &AppHandle, path: &Path, cancel: &AtomicBool) -> Result<ArchiveTestResult, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let len = file.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len();
+    let mut reader = sevenz::SevenZReader::new(file, len, "".into()).map_err(map_sevenz_error)?;
+    let mut summary = ArchiveTestResult::default();
+    let mut progress = ArchiveProgress::new(app, "test", reader.archive().files.len() as u64);
+    let mut cancelled = false;
+
+    let outcome = reader.for_each_entries(|entry, entry_reader| {
+        if cancel.load(Ordering::Relaxed) {
+            cancelled = true;
+            return Ok(false);
+        }
+        let name = entry.name().to_string();
+        let read = io::copy(entry_reader, &mut io::sink()).map(|_| ()).map_err(|e| e.to_string());
+        // A broken block also breaks every following entry of a solid archive: stop at the first failure
+        let keep_going = read.is_ok();
+        summary.record(name.clone(), read);
+        progress.step(&name);
+        Ok(keep_going)
+    });
+
+    if cancelled {
+        return Err(CommandError::Other("Cancelled".into()));
+    }
+    if let Err(e) = outcome {
+        summary.record("<archive>".to_string(), Err(map_sevenz_error(e).to_string()));
+    }
+    Ok(summary)
+}
+
+fn test_tar(app: &AppHandle, path: &Path, format: ArchiveFormat, cancel: &AtomicBool) -> Result<ArchiveTestResult, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let reader: Box<dyn io::Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
+        _ => Box::new(file),
+    };
+
+    let mut archive = TarArchive::new(reader);
+    let mut summary = ArchiveTestResult::default();
+    let mut progress = ArchiveProgress::new(app, "test", 0);
+
+    let entries = archive.entries().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    for entry in entries {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        // Tar is a single stream: any error means the rest of the archive is unreadable
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                summary.record("<archive>".to_string(), Err(e.to_string()));
+                break;
+            }
+        };
+        let name = entry.path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+        let outcome = io::copy(&mut entry, &mut io::sink()).map(|_| ()).map_err(|e| e.to_string());
+        let failed = outcome.is_err();
+        summary.record(name.clone(), outcome);
+        progress.step(&name);
+        if failed { break; }
+    }
+    Ok(summary)
+}
+
+#[derive(serde::Serialize, Debug, Default)]
+pub struct ArchiveFolderComparison {
+    /// Files present on both sides with the same size (and CRC for ZIP)
+    pub matching: usize,
+    /// In the archive but not in the folder
+    pub missing: Vec<String>,
+    /// In the folder but not in the archive
+    pub extra: Vec<String>,
+    /// On both sides but with a different size or content
+    pub differing: Vec<String>,
+}
+
+struct ArchivedFile {
+    name: String,
+    size: u64,
+    crc32: Option<u32>,
+}
+
+/// Relative paths compared with forward slashes, without `.` components (tarballs made with
+/// `tar -C dir .` name their entries `./a.txt`), and case-insensitively on Windows.
+fn comparison_key(name: &str) -> String {
+    let normalized = name.replace('\\', "/");
+    let key = normalized.split('/').filter(|part| !part.is_empty() && *part != ".").collect::<Vec<_>>().join("/");
+    if cfg!(target_os = "windows") { key.to_lowercase() } else { key }
+}
+
+/// Checks that `folder` holds exactly the files of the archive: every entry must exist there
+/// with the same size, and for ZIP the same CRC32. Reports missing, extra and differing files.
+#[command]
+pub async fn verify_archive_against_folder(app: AppHandle, archive_path: String, folder: String) -> Result<ArchiveFolderComparison, CommandError> {
+    app.state::<ArchiveState>().0.store(false, Ordering::Relaxed);
+    let path_buf = validate_path(&archive_path)?;
+    let folder_buf = validate_path(&folder)?;
+    let format = detect_format(&path_buf).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    if !folder_buf.is_dir() {
+        return Err(CommandError::PathError(format!("Not a folder: {}", folder_buf.display())));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let state = app.state::<ArchiveState>();
+        let result = compare_archive_to_folder(&app, &path_buf, format, &folder_buf, &state.0);
+        let progress = ArchiveProgress::new(&app, "verify", 0);
+        match &result {
+            Ok(_) => progress.emit("completed", None),
+            Err(CommandError::Other(m)) if m == "Cancelled" => progress.emit("cancelled", None),
+            Err(_) => progress.emit("error", None),
+        }
+        result
+    }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
+}
+
+fn compare_archive_to_folder(app: &AppHandle, path: &Path, format: ArchiveFormat, folder: &Path, cancel: &AtomicBool) -> Result<ArchiveFolderComparison, CommandError> {
+    let archived = match format {
+        ArchiveFormat::Zip => archived_zip_files(path)?,
+        ArchiveFormat::SevenZip => archived_seven_zip_files(path)?,
+        ArchiveFormat::Tar | ArchiveFormat::TarGz | ArchiveFormat::TarXz | ArchiveFormat::TarZst | ArchiveFormat::TarBz2 => {
+            archived_tar_files(path, format, cancel)?
+        }
+        ArchiveFormat::Iso => {
+            let mut files = Vec::new();
+            archived_iso_files(path, "", &mut files)?;
+            files
+        }
+        ArchiveFormat::Rar => return Err(CommandError::ArchiveError("Rar archives are not supported yet.".to_string())),
+    };
+
+    let mut on_disk: std::collections::HashMap<String, (PathBuf, String)> = std::collections::HashMap::new();
+    for entry in crate::utils::walk_for_size(folder, false) {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        if !entry.file_type().is_file() { continue; }
+        let relative = entry.path().strip_prefix(folder).unwrap_or(entry.path()).to_string_lossy().replace('\\', "/");
+        on_disk.insert(comparison_key(&relative), (entry.into_path(), relative));
+    }
+
+    let mut comparison = ArchiveFolderComparison::default();
+    let mut progress = ArchiveProgress::new(app, "verify", archived.len() as u64);
+    for file in archived {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        progress.step(&file.name);
+        let Some((disk_path, _)) = on_disk.remove(&comparison_key(&file.name)) else {
+            comparison.missing.push(file.name);
+            continue;
+        };
+        let size_matches = fs::metadata(&disk_path).map(|m| m.len() == file.size).unwrap_or(false);
+        let content_matches = size_matches && match file.crc32 {
+            Some(expected) => file_crc32(&disk_path, cancel)? == expected,
+            None => true,
+        };
+        if content_matches {
+            comparison.matching += 1;
+        } else {
+            comparison.differing.push(file.name);
+        }
+    }
+    comparison.extra = on_disk.into_values().map(|(_, relative)| relative).collect();
+    comparison.extra.sort();
+    Ok(comparison)
+}
+
+fn file_crc32(path: &Path, cancel: &AtomicBool) -> Result<u32, CommandError> {
+    use io::Read;
+    let mut file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0u8; 256 * 1024];
+    loop {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let n = file.read(&mut buffer).map_err(|e| CommandError::IoError(e.to_string()))?;
+        if n == 0 { break; }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hasher.finalize())
+}
+
+fn archived_zip_files(path: &Path) -> Result<Vec<ArchivedFile>, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        if entry.is_dir() { continue; }
+        files.push(ArchivedFile { name: entry.name().replace('\\', "/"), size: entry.size(), crc32: Some(entry.crc32()) });
+    }
+    Ok(files)
+}
+
+fn archived_seven_zip_files(path: &Path) -> Result<Vec<ArchivedFile>, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let len = file.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len();
+    let reader = sevenz::SevenZReader::new(file, len, "".into()).map_err(map_sevenz_error)?;
+    Ok(reader.archive().files.iter()
+        .filter(|entry| !entry.is_directory())
+        .map(|entry| ArchivedFile { name: entry.name().replace('\\', "/"), size: entry.size(), crc32: None })
+        .collect())
+}
+
+fn archived_tar_files(path: &Path, format: ArchiveFormat, cancel: &AtomicBool) -> Result<Vec<ArchivedFile>, CommandError> {
+    let file = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let reader: Box<dyn io::Read> = match format {
+        ArchiveFormat::TarGz => Box::new(GzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(XzDecoder::new(file)),
+        ArchiveFormat::TarBz2 => Box::new(BzDecoder::new(file)),
+        ArchiveFormat::TarZst => Box::new(ZstdDecoder::new(file).map_err(|e| CommandError::IoError(e.to_string()))?),
+        _ => Box::new(file),
+    };
+
+    let mut archive = TarArchive::new(reader);
+    let mut files = Vec::new();
+    for entry in archive.entries().map_err(|e| CommandError::ArchiveError(e.to_string()))? {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let entry = entry.map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+        if !entry.header().entry_type().is_file() { continue; }
+        let name = entry.path().map_err(|e| CommandError::ArchiveError(e.to_string()))?.to_string_lossy().replace('\\', "/");
+        files.push(ArchivedFile { name, size: entry.header().size().unwrap_or(0), crc32: None });
+    }
+    Ok(files)
+}
+
+fn archived_iso_files(path: &Path, prefix: &str, files: &mut Vec<ArchivedFile>) -> Result<(), CommandError> {
+    for entry in list_iso(path, prefix)? {
+        let name = if prefix.is_empty() { entry.name.clone() } else { format!("{}/{}", prefix, entry.name) };
+        if entry.is_dir {
+            archived_iso_files(path, &name, files)?;
+        } else {
+            files.push(ArchivedFile { name, size: entry.size, crc32: None });
+        }
+    }
+    Ok(())
+}
+
+#[command]
+pub async fn cancel_archive_operation(state: State<'_, ArchiveState>) -> Result<(), CommandError> {
+    state.0.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[command]
+pub async fn compress_to_archive(
+    app: AppHandle,
+    paths: Vec<String>,
+    archive_path: String,
+    format: Option<String>,
+    quality: Option<String>,
+    password: Option<String>,
+    split_size_bytes: Option<u64>,
+    exclude: Option<Vec<String>>,
+    state: State<'_, ArchiveState>,
+    config_state: State<'_, ConfigManager>,
+) -> Result<Vec<String>, CommandError> {
+    state.0.store(false, Ordering::Relaxed);
+
+    // Fall back to the user's preferred format/level when the caller doesn't specify one
+    let (format, quality) = {
+        let config = config_state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+        (
+            format.filter(|f| !f.is_empty()).unwrap_or_else(|| config.default_archive_format.clone()),
+            quality.filter(|q| !q.is_empty()).unwrap_or_else(|| config.default_archive_level.clone()),
+        )
+    };
+    let target_path_buf = validate_path(&archive_path)?;
+    let target_path = target_path_buf.as_path();
+    
+    info!("Compressing {:?} items to {:?}", paths.len(), target_path);
+
+    if !is_compression_format_supported(&format) {
+        return Err(CommandError::ArchiveError("Unsupported format".to_string()));
+    }
+    // Checked before the output is created, so nothing is left on disk
+    if paths.is_empty() {
+        return Err(CommandError::ArchiveError("Nothing to compress".to_string()));
+    }
+    
+    let password = password.filter(|p| !p.is_empty());
+    let split_size = split_size_bytes.filter(|s| *s > 0);
+    let exclude = IgnoreList::new(&exclude.unwrap_or_default());
+    let volumes = Arc::new(Mutex::new(Vec::new()));
+    let output = open_archive_output(target_path, split_size, &volumes)?;
+
+    let sources = paths.clone();
+    let result = match format.to_lowercase().as_str() {
+        "zip" => compress_zip(paths, output, &quality, password.as_deref(), &exclude, &state),
+        "7z" => compress_seven_zip(paths, output, &quality, password.as_deref(), &exclude, &state),
+        "tar" => compress_tar(paths, output, false, &exclude, &state),
+        "zst" | "tar.zst" => compress_tar_zst(paths, output, &quality, &exclude, &state),
+        "gz" | "tar.gz" => compress_tar_gz(paths, output, &quality, &exclude, &state),
+        "bz2" | "tar.bz2" => compress_tar_bz2(paths, output, &quality, &exclude, &state),
+        "xz" | "tar.xz" => compress_tar_xz(paths, output, &quality, &exclude, &state),
+        _ => Err(CommandError::ArchiveError("Unsupported format".to_string())),
+    };
+
+    let created: Vec<PathBuf> = if split_size.is_some() {
+        volumes.lock().map(|v| v.clone()).unwrap_or_default()
+    } else {
+        vec![target_path.to_path_buf()]
+    };
+
+    if result.is_err() {
+        for path in &created {
+            if path.exists() {
+                let _ = fs::remove_file(path);
+            }
+        }
+    }
+
+    let archive_bytes = created.iter().filter_map(|p| fs::metadata(p).ok()).map(|m| m.len()).sum();
+    record_archive_activity(&app, ActivityKind::Compress, sources, Some(target_path.to_string_lossy().to_string()), archive_bytes, &result);
+    result.map(|_| created.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+/// Matches `path`, relative to the folder it is archived from, against the exclude globs.
+fn is_excluded(exclude: &IgnoreList, path: &Path, base: &Path) -> bool {
+    path.strip_prefix(base).is_ok_and(|relative| exclude.matches_path(relative))
+}
+
+/// Compression level used for a format and quality preset ("fast", "normal" or "best").
+fn encoder_level(format: &str, quality: &str) -> u32 {
+    let zstd = matches!(format, "zst" | "tar.zst");
+    match quality {
+        "fast" => 1,
+        "best" if zstd => 19,
+        "best" => 9,
+        _ if zstd => 3,
+        _ => 6,
+    }
+}
+
+/// Bytes read for an estimate when the caller doesn't say, and the most it may ask for.
+const DEFAULT_ESTIMATE_SAMPLE_MB: u64 = 8;
+const MAX_ESTIMATE_SAMPLE_MB: u64 = 64;
+/// Files the sample is spread over, evenly picked across the selection.
+const MAX_ESTIMATE_SAMPLE_FILES: usize = 64;
+
+#[derive(serde::Serialize, Debug)]
+pub struct CompressionEstimate {
+    pub total_bytes: u64,
+    pub sampled_bytes: u64,
+    pub sampled_files: usize,
+    /// Compressed size / original size for the sample
+    pub ratio: f64,
+    pub estimated_size: u64,
+}
+
+/// Compresses a bounded sample of `paths` in memory with the encoder and level `compress_to_archive`
+/// would use, and projects the archive size from it. Cancelled by `cancel_archive_operation`.
+#[command]
+pub async fn estimate_compression(
+    app: AppHandle,
+    paths: Vec<String>,
+    format: String,
+    quality: Option<String>,
+    sample_mb: Option<u64>,
+    config_state: State<'_, ConfigManager>,
+) -> Result<CompressionEstimate, CommandError> {
+    app.state::<ArchiveState>().0.store(false, Ordering::Relaxed);
+    let format = format.to_lowercase();
+    if !is_compression_format_supported(&format) {
+        return Err(CommandError::ArchiveError("Unsupported format".to_string()));
+    }
+    let quality = match quality.filter(|q| !q.is_empty()) {
+        Some(q) => q,
+        None => config_state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?.default_archive_level.clone(),
+    };
+    let budget = sample_mb.unwrap_or(DEFAULT_ESTIMATE_SAMPLE_MB).clamp(1, MAX_ESTIMATE_SAMPLE_MB) * 1024 * 1024;
+    let roots = paths.iter().map(|p| validate_path(p)).collect::<Result<Vec<_>, _>>()?;
+
+    tokio::task::spawn_blocking(move || {
+        let state = app.state::<ArchiveState>();
+        estimate_sample(&roots, &format, &quality, budget, &state.0)
+    }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
+}
+
+fn estimate_sample(roots: &[PathBuf], format: &str, quality: &str, budget: u64, cancel: &AtomicBool) -> Result<CompressionEstimate, CommandError> {
+    let mut files = Vec::new();
+    for root in roots {
+        if let Some(metadata) = fs::metadata(root).ok().filter(|m| m.is_file() && m.len() > 0) {
+            files.push((root.clone(), metadata.len()));
+        }
+        for entry in crate::utils::walk_for_size(root, false) {
+            if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+            if let Ok(metadata) = entry.metadata() {
+                if metadata.is_file() && metadata.len() > 0 {
+                    files.push((entry.into_path(), metadata.len()));
+                }
+            }
+        }
+    }
+    let total_bytes: u64 = files.iter().map(|(_, size)| size).sum();
+
+    // Evenly spaced picks so one large file at the start doesn't make up the whole sample
+    let picks = files.len().min(MAX_ESTIMATE_SAMPLE_FILES);
+    let per_file = if picks == 0 { 0 } else { budget / picks as u64 };
+    let mut sample = Vec::new();
+    for i in 0..picks {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let (path, size) = &files[i * files.len() / picks];
+        if let Ok(file) = File::open(path) {
+            let _ = io::Read::read_to_end(&mut io::Read::take(file, per_file.min(*size)), &mut sample);
+        }
+    }
+
+    let compressed = compressed_len(format, quality, &sample, cancel)?;
+    let ratio = if sample.is_empty() { 1.0 } else { compressed as f64 / sample.len() as f64 };
+    Ok(CompressionEstimate {
+        total_bytes,
+        sampled_bytes: sample.len() as u64,
+        sampled_files: picks,
+        ratio,
+        estimated_size: (total_bytes as f64 * ratio).round() as u64,
+    })
+}
+
+/// Size of `data` once compressed like `compress_to_archive` would (7z is approximated with
+/// XZ, which wraps LZMA2 at the same preset; plain tar stores data as is).
+fn compressed_len(format: &str, quality: &str, data: &[u8], cancel: &AtomicBool) -> Result<u64, CommandError> {
+    let level = encoder_level(format, quality);
+    let output = match format {
+        "zip" => feed_encoder(flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::new(level)), data, cancel)?.finish()?,
+        "7z" => feed_encoder(XzEncoder::new(Vec::new(), level), data, cancel)?.finish()?,
+        "zst" | "tar.zst" => feed_encoder(ZstdEncoder::new(Vec::new(), level as i32)?, data, cancel)?.finish()?,
+        "gz" | "tar.gz" => feed_encoder(GzEncoder::new(Vec::new(), flate2::Compression::new(level)), data, cancel)?.finish()?,
+        "bz2" | "tar.bz2" => feed_encoder(BzEncoder::new(Vec::new(), bzip2::Compression::new(level)), data, cancel)?.finish()?,
+        "xz" | "tar.xz" => feed_encoder(XzEncoder::new(Vec::new(), level), data, cancel)?.finish()?,
+        _ => return Ok(data.len() as u64),
+    };
+    Ok(output.len() as u64)
+}
+
+/// Writes `data` in 1 MB chunks so a slow encoder can still be cancelled.
+fn feed_encoder<W: io::Write>(mut encoder: W, data: &[u8], cancel: &AtomicBool) -> Result<W, CommandError> {
+    for chunk in data.chunks(1024 * 1024) {
+        if cancel.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        encoder.write_all(chunk)?;
+    }
+    Ok(encoder)
+}
+
+trait WriteSeek: io::Write + io::Seek + Send {}
+impl<T: io::Write + io::Seek + Send> WriteSeek for T {}
+
+/// Opens the destination of a new archive: a single file, or `.001`, `.002`, ... volumes
+/// of `split_size` bytes each when splitting is requested.
+fn open_archive_output(target: &Path, split_size: Option<u64>, volumes: &Arc<Mutex<Vec<PathBuf>>>) -> Result<Box<dyn WriteSeek>, CommandError> {
+    match split_size {
+        Some(size) => Ok(Box::new(SplittingWriter::new(target, size, volumes.clone()))),
+        None => {
+            let file = File::create(target).map_err(|e| CommandError::IoError(e.to_string()))?;
+            Ok(Box::new(file))
+        }
+    }
+}
+
+#[command]
+pub async fn add_to_archive(paths: Vec<String>, archive_path: String, state: State<'_, ArchiveState>) -> Result<(), CommandError> {
+    state.0.store(false, Ordering::Relaxed);
+    let target_path_buf = validate_path(&archive_path)?;
+    let target_path = target_path_buf.as_path();
+    
+    let format = detect_format(target_path).ok_or(CommandError::ArchiveError("Unsupported archive format".to_string()))?;
+    
+    match format {
+        ArchiveFormat::Zip => add_to_zip(paths, target_path, &state),
+        _ => Err(CommandError::ArchiveError("Adding to this archive format is not supported yet.".to_string())),
+    }
+}
+
+fn add_to_zip(paths: Vec<String>, target: &Path, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(target)
+        .map_err(|e| CommandError::IoError(e.to_string()))?;
+
+    let mut zip = zip::ZipWriter::new_append(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o755);
+
+    for p in paths {
+        if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+        let path = Path::new(&p);
+        let parent = path.parent().unwrap_or(path);
+        
+        if path.is_dir() {
+            for entry in walkdir::WalkDir::new(path) {
+                if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+                let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
+                let entry_path = entry.path();
+                let name = entry_path.strip_prefix(parent).map_err(|e| CommandError::PathError(e.to_string()))?;
+                let name_str = name.to_string_lossy().replace('\\', "/");
                 
                 if entry.file_type().is_dir() {
                     zip.add_directory(name_str, options).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
@@ -501,9 +2140,8 @@ fn add_to_zip(paths: Vec<String>, target: &Path, state: &State<'_, ArchiveState>
     Ok(())
 }
 
-fn compress_zip(paths: Vec<String>, target: &Path, quality: &str, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
-    let file = File::create(target).map_err(|e| CommandError::IoError(e.to_string()))?;
-    let mut zip = zip::ZipWriter::new(io::BufWriter::with_capacity(128 * 1024, file));
+fn compress_zip(paths: Vec<String>, output: Box<dyn WriteSeek>, quality: &str, password: Option<&str>, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let mut zip = zip::ZipWriter::new(io::BufWriter::with_capacity(128 * 1024, output));
     
     let method = match quality {
         "fast" => zip::CompressionMethod::Deflated,
@@ -511,24 +2149,25 @@ fn compress_zip(paths: Vec<String>, target: &Path, quality: &str, state: &State<
         _ => zip::CompressionMethod::Deflated,
     };
     
-    let level = match quality {
-        "fast" => Some(1),
-        "best" => Some(9),
-        _ => Some(6),
-    };
+    let level = Some(encoder_level("zip", quality) as i64);
 
     let options = zip::write::SimpleFileOptions::default()
         .compression_method(method)
         .compression_level(level)
         .unix_permissions(0o755);
+    let options = match password {
+        Some(pw) => options.with_aes_encryption(zip::AesMode::Aes256, pw),
+        None => options,
+    };
 
     for p in paths {
         if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
         let path = Path::new(&p);
         let parent = path.parent().unwrap_or(path);
+        if is_excluded(exclude, path, parent) { continue; }
         
         if path.is_dir() {
-            for entry in walkdir::WalkDir::new(path) {
+            for entry in walkdir::WalkDir::new(path).into_iter().filter_entry(|e| !is_excluded(exclude, e.path(), parent)) {
                 if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
                 let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
                 let entry_path = entry.path();
@@ -555,19 +2194,24 @@ fn compress_zip(paths: Vec<String>, target: &Path, quality: &str, state: &State<
     Ok(())
 }
 
-fn compress_seven_zip(paths: Vec<String>, target: &Path, _quality: &str, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
-    if paths.is_empty() { return Ok(()); }
-    
-    let file = File::create(target).map_err(|e| CommandError::IoError(e.to_string()))?;
-    let mut writer = sevenz::SevenZWriter::new(file).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+fn compress_seven_zip(paths: Vec<String>, output: Box<dyn WriteSeek>, quality: &str, password: Option<&str>, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let mut writer = sevenz::SevenZWriter::new(output).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    let lzma2: sevenz::SevenZMethodConfiguration = sevenz::lzma::LZMA2Options::with_preset(encoder_level("7z", quality)).into();
+    let methods = match password {
+        Some(pw) => vec![sevenz::AesEncoderOptions::new(pw.into()).into(), lzma2],
+        None => vec![lzma2],
+    };
+    writer.set_content_methods(methods);
     
     for p in paths {
         if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
         let path = Path::new(&p);
+        let parent = path.parent().unwrap_or(path);
+        if is_excluded(exclude, path, parent) { continue; }
         if path.is_dir() {
-            add_dir_to_sevenz(&mut writer, path, path.parent().unwrap_or(path), state)?;
+            add_dir_to_sevenz(&mut writer, path, parent, exclude, state)?;
         } else {
-            add_file_to_sevenz(&mut writer, path, path.parent().unwrap_or(path))?;
+            add_file_to_sevenz(&mut writer, path, parent)?;
         }
     }
     writer.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
@@ -592,6 +2236,7 @@ fn add_dir_to_sevenz<W: io::Write + io::Seek>(
     writer: &mut sevenz::SevenZWriter<W>,
     path: &Path,
     base: &Path,
+    exclude: &IgnoreList,
     state: &State<'_, ArchiveState>
 ) -> Result<(), CommandError> {
     let name = path.strip_prefix(base).map_err(|e| CommandError::PathError(e.to_string()))?.to_string_lossy();
@@ -604,8 +2249,9 @@ fn add_dir_to_sevenz<W: io::Write + io::Seek>(
         if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
         let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
         let p = entry.path();
+        if is_excluded(exclude, &p, base) { continue; }
         if p.is_dir() {
-            add_dir_to_sevenz(writer, &p, base, state)?;
+            add_dir_to_sevenz(writer, &p, base, exclude, state)?;
         } else {
             add_file_to_sevenz(writer, &p, base)?;
         }
@@ -613,54 +2259,74 @@ fn add_dir_to_sevenz<W: io::Write + io::Seek>(
     Ok(())
 }
 
-fn compress_tar(paths: Vec<String>, target: &Path, _gz: bool, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
-    let file = File::create(target).map_err(|e| CommandError::IoError(e.to_string()))?;
-    let mut tar = tar::Builder::new(io::BufWriter::with_capacity(128 * 1024, file));
-
-    for p in paths {
-        if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
-        let path = Path::new(&p);
-        let name = path.file_name().ok_or(CommandError::PathError("Invalid path".to_string()))?;
-        if path.is_dir() {
-            tar.append_dir_all(name, path).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-        } else {
-            let f = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
-            let mut header = tar::Header::new_gnu();
-            header.set_path(name).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-            header.set_size(f.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len());
-            header.set_cksum();
-            tar.append(&header, &mut io::BufReader::with_capacity(128 * 1024, f)).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-        }
-    }
+fn compress_tar(paths: Vec<String>, output: Box<dyn WriteSeek>, _gz: bool, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let mut tar = tar::Builder::new(io::BufWriter::with_capacity(128 * 1024, output));
+    append_paths_to_tar(&mut tar, &paths, exclude, state)?;
     tar.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
     Ok(())
 }
 
-fn compress_tar_zst(paths: Vec<String>, target: &Path, quality: &str, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
-    let file = File::create(target).map_err(|e| CommandError::IoError(e.to_string()))?;
-    let level = match quality {
-        "fast" => 1,
-        "best" => 19,
-        _ => 3,
-    };
-    let zstd = ZstdEncoder::new(file, level).map_err(|e| CommandError::IoError(e.to_string()))?.auto_finish();
+fn compress_tar_zst(paths: Vec<String>, output: Box<dyn WriteSeek>, quality: &str, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let level = encoder_level("zst", quality) as i32;
+    let zstd = ZstdEncoder::new(output, level).map_err(|e| CommandError::IoError(e.to_string()))?.auto_finish();
     let mut tar = tar::Builder::new(zstd);
+    append_paths_to_tar(&mut tar, &paths, exclude, state)?;
+    tar.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    Ok(())
+}
+
+fn compress_tar_gz(paths: Vec<String>, output: Box<dyn WriteSeek>, quality: &str, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let level = encoder_level("gz", quality);
+    let mut tar = tar::Builder::new(GzEncoder::new(output, flate2::Compression::new(level)));
+    append_paths_to_tar(&mut tar, &paths, exclude, state)?;
+    let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+fn compress_tar_bz2(paths: Vec<String>, output: Box<dyn WriteSeek>, quality: &str, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let level = encoder_level("bz2", quality);
+    let mut tar = tar::Builder::new(BzEncoder::new(output, bzip2::Compression::new(level)));
+    append_paths_to_tar(&mut tar, &paths, exclude, state)?;
+    let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+    Ok(())
+}
 
+fn compress_tar_xz(paths: Vec<String>, output: Box<dyn WriteSeek>, quality: &str, exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
+    let level = encoder_level("xz", quality);
+    let mut tar = tar::Builder::new(XzEncoder::new(output, level));
+    append_paths_to_tar(&mut tar, &paths, exclude, state)?;
+    let encoder = tar.into_inner().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+    encoder.finish().map_err(|e| CommandError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+/// Appends files and folders to a tar stream, skipping excluded entries and checking the cancel
+/// flag before each one.
+fn append_paths_to_tar<W: io::Write>(tar: &mut tar::Builder<W>, paths: &[String], exclude: &IgnoreList, state: &State<'_, ArchiveState>) -> Result<(), CommandError> {
     for p in paths {
         if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
-        let path = Path::new(&p);
-        let name = path.file_name().ok_or(CommandError::PathError("Invalid path".to_string()))?;
+        let path = Path::new(p);
+        let parent = path.parent().unwrap_or(path);
+        if is_excluded(exclude, path, parent) { continue; }
+
         if path.is_dir() {
-            tar.append_dir_all(name, path).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            for entry in walkdir::WalkDir::new(path).into_iter().filter_entry(|e| !is_excluded(exclude, e.path(), parent)) {
+                if state.0.load(Ordering::Relaxed) { return Err(CommandError::Other("Cancelled".into())); }
+                let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
+                let name = entry.path().strip_prefix(parent).map_err(|e| CommandError::PathError(e.to_string()))?;
+
+                if entry.file_type().is_dir() {
+                    tar.append_dir(name, entry.path()).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+                } else {
+                    tar.append_path_with_name(entry.path(), name).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+                }
+            }
         } else {
-            let f = File::open(path).map_err(|e| CommandError::IoError(e.to_string()))?;
-            let mut header = tar::Header::new_gnu();
-            header.set_path(name).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
-            header.set_size(f.metadata().map_err(|e| CommandError::IoError(e.to_string()))?.len());
-            header.set_cksum();
-            tar.append(&header, &mut io::BufReader::with_capacity(128 * 1024, f)).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
+            let name = path.file_name().ok_or(CommandError::PathError("Invalid path".to_string()))?;
+            tar.append_path_with_name(path, name).map_err(|e| CommandError::ArchiveError(e.to_string()))?;
         }
     }
-    tar.finish().map_err(|e| CommandError::ArchiveError(e.to_string()))?;
     Ok(())
 }
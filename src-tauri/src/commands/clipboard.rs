@@ -297,88 +297,16 @@ pub fn set_clipboard_from_trash(trash_paths: Vec<String>) -> Result<Vec<String>,
 
     use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
     use windows::Win32::System::Ole::OleSetClipboard;
-    use windows::Win32::UI::Shell::BHID_DataObject;
 
     unsafe {
         // Initialize COM
         let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
 
-        // Create IShellItems from paths using our existing helper
-        let mut shell_items: Vec<IShellItem> = Vec::new();
-
-        for path in &validated_paths {
-            let wide_path: Vec<u16> = OsStr::new(path)
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect();
-
-            if let Ok(item) = SHCreateItemFromParsingName(
-                PCWSTR(wide_path.as_ptr()),
-                None::<&windows::Win32::System::Com::IBindCtx>,
-            ) {
-                shell_items.push(item);
-            }
-        }
-
-        if shell_items.is_empty() {
-            CoUninitialize();
-            return Err(CommandError::SystemError("Failed to create shell items from trash paths".to_string()));
-        }
-
-        // Create IShellItemArray from multiple items using PIDLs
-        // Use SHGetIDListFromObject to get PIDL from each IShellItem
-        use windows::Win32::UI::Shell::Common::ITEMIDLIST;
-        use windows::Win32::UI::Shell::{
-            ILFree, SHCreateShellItemArrayFromIDLists, SHGetIDListFromObject,
-        };
-
-        // Store the PIDLs returned by SHGetIDListFromObject
-        // SHGetIDListFromObject returns PIDLIST_ABSOLUTE
-        let mut pidl_holders = Vec::new();
-
-        for item in &shell_items {
-            if let Ok(pidl) = SHGetIDListFromObject(item) {
-                pidl_holders.push(pidl);
-            }
-        }
-
-        if pidl_holders.is_empty() {
-            CoUninitialize();
-            return Err(CommandError::SystemError("Failed to get PIDLs from shell items".to_string()));
-        }
-
-        // Convert PIDLIST_ABSOLUTE to const ITEMIDLIST pointers for SHCreateShellItemArrayFromIDLists
-        // Use transmute since PIDLIST_ABSOLUTE wraps *mut ITEMIDLIST
-        let pidls: Vec<*const ITEMIDLIST> = pidl_holders
-            .iter()
-            .map(|p| std::mem::transmute::<_, *const ITEMIDLIST>(*p))
-            .collect();
-
-        // Create IShellItemArray from PIDLs
-        let item_array: windows::Win32::UI::Shell::IShellItemArray =
-            SHCreateShellItemArrayFromIDLists(&pidls).map_err(|e| {
-                // Free PIDLs on error
-                for pidl in &pidl_holders {
-                    ILFree(Some(*pidl));
-                }
-                CoUninitialize();
-                CommandError::SystemError(format!("Failed to create shell item array: {:?}", e))
-            })?;
-
-        // Free the PIDLs after use
-        for pidl in &pidl_holders {
-            ILFree(Some(*pidl));
-        }
-
-        // Get IDataObject from the shell item array
-        let data_object: Result<windows::Win32::System::Com::IDataObject, _> =
-            item_array.BindToHandler(None, &BHID_DataObject);
-
-        let data_obj = match data_object {
+        let data_obj = match create_shell_data_object(&validated_paths) {
             Ok(obj) => obj,
             Err(e) => {
                 CoUninitialize();
-                return Err(CommandError::SystemError(format!("Failed to get IDataObject: {:?}", e)));
+                return Err(e);
             }
         };
 
@@ -450,3 +378,174 @@ fn set_drop_effect_on_data_object(
         Ok(())
     }
 }
+
+/// Builds a shell IDataObject (CF_HDROP, shell ID lists, ...) for the given paths.
+/// COM must already be initialized on the calling thread.
+unsafe fn create_shell_data_object(
+    paths: &[String],
+) -> Result<windows::Win32::System::Com::IDataObject, CommandError> {
+    use windows::Win32::UI::Shell::Common::ITEMIDLIST;
+    use windows::Win32::UI::Shell::{
+        ILFree, SHCreateShellItemArrayFromIDLists, SHGetIDListFromObject, BHID_DataObject,
+    };
+
+    // Create IShellItems from paths
+    let mut shell_items: Vec<IShellItem> = Vec::new();
+
+    for path in paths {
+        let wide_path: Vec<u16> = OsStr::new(path)
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect();
+
+        if let Ok(item) = SHCreateItemFromParsingName(
+            PCWSTR(wide_path.as_ptr()),
+            None::<&windows::Win32::System::Com::IBindCtx>,
+        ) {
+            shell_items.push(item);
+        }
+    }
+
+    if shell_items.is_empty() {
+        return Err(CommandError::SystemError("Failed to create shell items from paths".to_string()));
+    }
+
+    // Store the PIDLs returned by SHGetIDListFromObject
+    // SHGetIDListFromObject returns PIDLIST_ABSOLUTE
+    let mut pidl_holders = Vec::new();
+
+    for item in &shell_items {
+        if let Ok(pidl) = SHGetIDListFromObject(item) {
+            pidl_holders.push(pidl);
+        }
+    }
+
+    if pidl_holders.is_empty() {
+        return Err(CommandError::SystemError("Failed to get PIDLs from shell items".to_string()));
+    }
+
+    // Convert PIDLIST_ABSOLUTE to const ITEMIDLIST pointers for SHCreateShellItemArrayFromIDLists
+    // Use transmute since PIDLIST_ABSOLUTE wraps *mut ITEMIDLIST
+    let pidls: Vec<*const ITEMIDLIST> = pidl_holders
+        .iter()
+        .map(|p| std::mem::transmute::<_, *const ITEMIDLIST>(*p))
+        .collect();
+
+    // Create IShellItemArray from PIDLs
+    let item_array = SHCreateShellItemArrayFromIDLists(&pidls);
+
+    // Free the PIDLs after use
+    for pidl in &pidl_holders {
+        ILFree(Some(*pidl));
+    }
+
+    let item_array: windows::Win32::UI::Shell::IShellItemArray = item_array
+        .map_err(|e| CommandError::SystemError(format!("Failed to create shell item array: {:?}", e)))?;
+
+    // Get IDataObject from the shell item array
+    item_array
+        .BindToHandler(None, &BHID_DataObject)
+        .map_err(|e| CommandError::SystemError(format!("Failed to get IDataObject: {:?}", e)))
+}
+
+/// Minimal IDropSource: drop on left button release, cancel on Escape.
+#[windows::core::implement(windows::Win32::System::Ole::IDropSource)]
+struct DropSource;
+
+impl windows::Win32::System::Ole::IDropSource_Impl for DropSource_Impl {
+    fn QueryContinueDrag(
+        &self,
+        fescapepressed: windows::core::BOOL,
+        grfkeystate: windows::Win32::System::SystemServices::MODIFIERKEYS_FLAGS,
+    ) -> windows::core::HRESULT {
+        use windows::Win32::Foundation::{DRAGDROP_S_CANCEL, DRAGDROP_S_DROP, S_OK};
+        use windows::Win32::System::SystemServices::MK_LBUTTON;
+
+        if fescapepressed.as_bool() {
+            DRAGDROP_S_CANCEL
+        } else if (grfkeystate & MK_LBUTTON).0 == 0 {
+            DRAGDROP_S_DROP
+        } else {
+            S_OK
+        }
+    }
+
+    fn GiveFeedback(&self, _dweffect: windows::Win32::System::Ole::DROPEFFECT) -> windows::core::HRESULT {
+        windows::Win32::Foundation::DRAGDROP_S_USEDEFAULTCURSORS
+    }
+}
+
+/// Starts a native OLE drag of the given files so they can be dropped into
+/// Explorer or any other application. Returns the effect chosen by the drop
+/// target: "copy", "move", "link" or "none".
+#[command(rename_all = "snake_case")]
+pub async fn begin_drag(app: tauri::AppHandle, paths: Vec<String>, effect: String) -> Result<String, CommandError> {
+    let validated_paths: Vec<String> = paths.iter()
+        .map(|p| validate_path(p).map(|pb: std::path::PathBuf| pb.to_string_lossy().to_string()))
+        .collect::<Result<Vec<String>, CommandError>>()?;
+
+    if validated_paths.is_empty() {
+        return Ok("none".to_string());
+    }
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+
+    // DoDragDrop runs a modal loop and must be called from the UI (STA) thread
+    app.run_on_main_thread(move || {
+        let _ = tx.send(do_drag_drop(&validated_paths, &effect));
+    })
+    .map_err(|e| CommandError::SystemError(format!("Failed to dispatch drag to UI thread: {}", e)))?;
+
+    rx.await
+        .map_err(|e| CommandError::Other(format!("Drag operation aborted: {}", e)))?
+}
+
+fn do_drag_drop(paths: &[String], effect: &str) -> Result<String, CommandError> {
+    use windows::Win32::Foundation::DRAGDROP_S_DROP;
+    use windows::Win32::System::Ole::{
+        DoDragDrop, IDropSource, OleInitialize, OleUninitialize, DROPEFFECT, DROPEFFECT_COPY,
+        DROPEFFECT_LINK, DROPEFFECT_MOVE, DROPEFFECT_NONE,
+    };
+
+    let allowed = match effect {
+        "copy" => DROPEFFECT_COPY,
+        "move" => DROPEFFECT_MOVE,
+        "link" => DROPEFFECT_LINK,
+        _ => DROPEFFECT_COPY | DROPEFFECT_MOVE | DROPEFFECT_LINK,
+    };
+
+    unsafe {
+        // The UI thread is normally already OLE-initialized; this just bumps the refcount
+        let ole_init = OleInitialize(None).is_ok();
+
+        let result = (|| {
+            let data_obj = create_shell_data_object(paths)?;
+            let drop_source: IDropSource = DropSource.into();
+
+            let mut performed = DROPEFFECT_NONE;
+            let hr = DoDragDrop(&data_obj, &drop_source, allowed, &mut performed);
+            if hr.is_err() {
+                return Err(CommandError::SystemError(format!("DoDragDrop failed: {:?}", hr)));
+            }
+
+            let performed: DROPEFFECT = if hr == DRAGDROP_S_DROP { performed } else { DROPEFFECT_NONE };
+            let name = if performed.0 & DROPEFFECT_MOVE.0 != 0 {
+                "move"
+            } else if performed.0 & DROPEFFECT_COPY.0 != 0 {
+                "copy"
+            } else if performed.0 & DROPEFFECT_LINK.0 != 0 {
+                "link"
+            } else {
+                "none"
+            };
+            info!("Drag finished with effect: {}", name);
+            Ok(name.to_string())
+        })();
+
+        if ole_init {
+            OleUninitialize();
+        }
+
+        result
+    }
+}
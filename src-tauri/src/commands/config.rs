@@ -1,5 +1,8 @@
-use tauri::{AppHandle, State};
-use crate::models::{AppConfig, ConfigManager, CommandError};
+use tauri::{AppHandle, Manager, State};
+use crate::models::{AppConfig, Bookmark, ConfigManager, ConfigImportReport, ConfigKeySchema, CommandError, Favorite, HistoryManager, SavedSearch, SearchPreset, SessionManager, Workspace, WorkspacePanel};
+use crate::models::config::{config_schema, folder_view_key, MAX_BOOKMARK_SLOT};
+use crate::models::session::PanelState;
+use crate::utils::path_security::validate_path;
 
 #[tauri::command]
 pub fn get_config(state: State<'_, ConfigManager>) -> Result<AppConfig, CommandError> {
@@ -7,6 +10,8 @@ pub fn get_config(state: State<'_, ConfigManager>) -> Result<AppConfig, CommandE
     Ok(config.clone())
 }
 
+/// Sets one config key from its string form. Unknown keys and values outside the schema
+/// (see `get_config_schema`) are rejected and leave the config untouched.
 #[tauri::command]
 pub fn set_config_value(
     app: AppHandle,
@@ -15,35 +20,28 @@ pub fn set_config_value(
     value: String,
 ) -> Result<(), CommandError> {
     let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
-    
-    match key.as_str() {
-        "theme" => config.theme = value,
-        "language" => config.language = value,
-        "layout" => config.layout = value,
-        "show_hidden" => config.show_hidden = value.parse().unwrap_or(false),
-        "show_system" => config.show_system = value.parse().unwrap_or(false),
-        "use_system_icons" => config.use_system_icons = value.parse().unwrap_or(true),
-        "date_format" => config.date_format = value,
-        "show_previews" => config.show_previews = value.parse().unwrap_or(true),
-        "zip_quality" => config.zip_quality = value,
-        "seven_zip_quality" => config.seven_zip_quality = value,
-        "zstd_quality" => config.zstd_quality = value,
-        "font_size" => config.font_size = value.parse().unwrap_or(16),
-        "search_limit" => config.search_limit = value.parse().unwrap_or(3000),
-        "default_turbo_mode" => config.default_turbo_mode = value.parse().unwrap_or(false),
-        "show_grid_thumbnails" => config.show_grid_thumbnails = value.parse().unwrap_or(true),
-        "show_checkboxes" => config.show_checkboxes = value.parse().unwrap_or(false),
-        "show_network" => config.show_network = value.parse().unwrap_or(true),
-        _ => return Err(CommandError::Other(format!("Unknown config key: {}", key))),
+    config.set_value(&key, &serde_json::Value::String(value))?;
+
+    if key == "max_undo_depth" {
+        if let Some(history) = app.try_state::<HistoryManager>() {
+            history.set_max_depth(config.max_undo_depth);
+        }
     }
 
     // Save using the internal helper without re-locking, 
     // OR we could drop lock and call save(). 
     // Since we hold the lock and have the updated data, we can just call save_config with it.
+    // This also emits `config_changed` for the other windows.
     state.save_config(&app, &config)?;
     Ok(())
 }
 
+/// Keys accepted by `set_config_value`, with their type, range or choices and default value.
+#[tauri::command]
+pub fn get_config_schema() -> Vec<ConfigKeySchema> {
+    config_schema()
+}
+
 #[tauri::command]
 pub fn reset_config_to_default(
     app: AppHandle,
@@ -52,6 +50,292 @@ pub fn reset_config_to_default(
     let default_config = AppConfig::default();
     let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
     *config = default_config;
+    if let Some(history) = app.try_state::<HistoryManager>() {
+        history.set_max_depth(config.max_undo_depth);
+    }
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+/// Writes the current config to `path` as JSON.
+#[tauri::command]
+pub fn export_config(state: State<'_, ConfigManager>, path: String) -> Result<(), CommandError> {
+    let path = validate_path(&path)?;
+    let config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    let json = serde_json::to_string_pretty(&*config).map_err(|e| CommandError::Other(e.to_string()))?;
+    std::fs::write(path, json).map_err(|e| CommandError::IoError(e.to_string()))?;
+    Ok(())
+}
+
+/// Replaces the config with one exported by `export_config`, applies and saves it (which emits
+/// `config_changed`). Unknown keys are skipped and invalid ones reset to default (both reported).
+#[tauri::command]
+pub fn import_config(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    path: String,
+) -> Result<ConfigImportReport, CommandError> {
+    let path = validate_path(&path)?;
+    let content = std::fs::read_to_string(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let json: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| CommandError::InvalidConfig(format!("Not a valid config file: {}", e)))?;
+    let (imported, report) = AppConfig::from_import(&json)?;
+
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    *config = imported;
+    if let Some(history) = app.try_state::<HistoryManager>() {
+        history.set_max_depth(config.max_undo_depth);
+    }
+    state.save_config(&app, &config)?;
+    Ok(report)
+}
+
+/// Saves a named search preset, replacing any existing preset with the same name.
+#[tauri::command]
+pub fn save_search(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    name: String,
+    params: SearchPreset,
+) -> Result<(), CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::Other("Saved search name cannot be empty".to_string()));
+    }
+
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    match config.saved_searches.iter_mut().find(|s| s.name == name) {
+        Some(existing) => existing.params = params,
+        None => config.saved_searches.push(SavedSearch { name, params }),
+    }
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_saved_searches(state: State<'_, ConfigManager>) -> Result<Vec<SavedSearch>, CommandError> {
+    let config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    Ok(config.saved_searches.clone())
+}
+
+#[tauri::command]
+pub fn delete_saved_search(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    name: String,
+) -> Result<(), CommandError> {
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    config.saved_searches.retain(|s| s.name != name);
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+fn same_favorite_path(a: &str, b: &str) -> bool {
+    if cfg!(target_os = "windows") { a.eq_ignore_ascii_case(b) } else { a == b }
+}
+
+/// Pins a folder to the favorites list. Re-adding an existing path only updates its name.
+#[tauri::command]
+pub fn add_favorite(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    path: String,
+    name: Option<String>,
+) -> Result<(), CommandError> {
+    let pb = validate_path(&path)?;
+    let path = pb.to_string_lossy().to_string();
+    let name = name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .or_else(|| pb.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| path.clone());
+
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    match config.favorites.iter_mut().find(|f| same_favorite_path(&f.path, &path)) {
+        Some(existing) => existing.name = name,
+        None => config.favorites.push(Favorite { path, name }),
+    }
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_favorite(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    path: String,
+) -> Result<(), CommandError> {
+    let path = validate_path(&path)?.to_string_lossy().to_string();
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    config.favorites.retain(|f| !same_favorite_path(&f.path, &path));
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+/// Reorders favorites to follow `order` (a list of paths). Favorites missing from `order`
+/// keep their relative order after the listed ones.
+#[tauri::command]
+pub fn reorder_favorites(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    order: Vec<String>,
+) -> Result<(), CommandError> {
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    let mut remaining = std::mem::take(&mut config.favorites);
+    let mut reordered = Vec::with_capacity(remaining.len());
+    for path in &order {
+        if let Some(pos) = remaining.iter().position(|f| same_favorite_path(&f.path, path)) {
+            reordered.push(remaining.remove(pos));
+        }
+    }
+    reordered.extend(remaining);
+    config.favorites = reordered;
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_favorites(state: State<'_, ConfigManager>) -> Result<Vec<Favorite>, CommandError> {
+    let config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    Ok(config.favorites.clone())
+}
+
+fn check_bookmark_slot(slot: u8) -> Result<(), CommandError> {
+    if (1..=MAX_BOOKMARK_SLOT).contains(&slot) {
+        Ok(())
+    } else {
+        Err(CommandError::Other(format!("Bookmark slot must be between 1 and {}", MAX_BOOKMARK_SLOT)))
+    }
+}
+
+/// Binds `path` (an existing folder) to a numbered bookmark slot, replacing what was there.
+#[tauri::command]
+pub fn set_bookmark(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    slot: u8,
+    path: String,
+) -> Result<(), CommandError> {
+    check_bookmark_slot(slot)?;
+    let pb = validate_path(&path)?;
+    if !pb.is_dir() {
+        return Err(CommandError::PathError(format!("Not a folder: {}", pb.display())));
+    }
+
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    config.bookmarks.insert(slot, pb.to_string_lossy().to_string());
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_bookmark(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    slot: u8,
+) -> Result<(), CommandError> {
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    if config.bookmarks.remove(&slot).is_some() {
+        state.save_config(&app, &config)?;
+    }
+    Ok(())
+}
+
+/// Bookmarks by slot. Folders that no longer exist are kept but flagged with `exists: false`.
+#[tauri::command]
+pub fn get_bookmarks(state: State<'_, ConfigManager>) -> Result<Vec<Bookmark>, CommandError> {
+    let config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    Ok(config.bookmarks.iter()
+        .map(|(slot, path)| Bookmark { slot: *slot, path: path.clone(), exists: std::path::Path::new(path).is_dir() })
+        .collect())
+}
+
+/// Remembers the panel's current sort as the default for `path`. `list_dir` applies it
+/// whenever that folder is opened without an explicit sort.
+#[tauri::command]
+pub fn save_folder_view(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    session: State<'_, SessionManager>,
+    panel_id: String,
+    path: String,
+) -> Result<(), CommandError> {
+    let sort_config = {
+        let session = session.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session".to_string()))?;
+        if panel_id == "right" { session.right_panel.sort_config.clone() } else { session.left_panel.sort_config.clone() }
+    };
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    config.folder_view_overrides.insert(folder_view_key(&path), sort_config);
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn clear_folder_view(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    path: String,
+) -> Result<(), CommandError> {
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    if config.folder_view_overrides.remove(&folder_view_key(&path)).is_some() {
+        state.save_config(&app, &config)?;
+    }
+    Ok(())
+}
+
+fn workspace_panel(panel: &PanelState) -> WorkspacePanel {
+    WorkspacePanel {
+        tabs: panel.tabs.iter().map(|t| t.path.to_string_lossy().to_string()).collect(),
+        active_tab: panel.tabs.iter().position(|t| t.id == panel.active_tab_id).unwrap_or(0),
+    }
+}
+
+/// Saves the tabs of both panels (and which one is active) under `name`, replacing any
+/// workspace with the same name.
+#[tauri::command]
+pub fn save_workspace(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    session: State<'_, SessionManager>,
+    name: String,
+) -> Result<(), CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::Other("Workspace name cannot be empty".to_string()));
+    }
+
+    let workspace = {
+        let session = session.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session".to_string()))?;
+        Workspace {
+            name: name.clone(),
+            left: workspace_panel(&session.left_panel),
+            right: workspace_panel(&session.right_panel),
+            active_panel: session.active_panel.clone(),
+        }
+    };
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    match config.workspaces.iter_mut().find(|w| w.name == name) {
+        Some(existing) => *existing = workspace,
+        None => config.workspaces.push(workspace),
+    }
+    state.save_config(&app, &config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_workspaces(state: State<'_, ConfigManager>) -> Result<Vec<Workspace>, CommandError> {
+    let config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    Ok(config.workspaces.clone())
+}
+
+#[tauri::command]
+pub fn delete_workspace(
+    app: AppHandle,
+    state: State<'_, ConfigManager>,
+    name: String,
+) -> Result<(), CommandError> {
+    let mut config = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+    config.workspaces.retain(|w| w.name != name);
     state.save_config(&app, &config)?;
     Ok(())
 }
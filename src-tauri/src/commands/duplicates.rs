@@ -1,4 +1,4 @@
-use crate::models::{CommandError, FileEntry};
+use crate::models::{CommandError, ConfigManager, FileEntry};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,6 +12,7 @@ use tauri::{State, AppHandle, Emitter};
 use dashmap::DashMap;
 
 use crate::utils::hardware::get_physical_disk_id;
+use crate::utils::IgnoreList;
 
 #[derive(Clone, Serialize)]
 pub struct DuplicatesProgress {
@@ -76,11 +77,16 @@ fn calculate_hash<P: AsRef<Path>>(path: P, limit: Option<usize>, from_end: bool)
 pub async fn find_duplicates(
     app: AppHandle,
     state: State<'_, DuplicateSearchState>,
+    config_state: State<'_, ConfigManager>,
     paths: Vec<String>,
     options: DuplicateSearchOptions,
 ) -> Result<Vec<DuplicateGroup>, CommandError> {
     state.0.store(false, Ordering::Relaxed);
     let cancel_flag = state.0.clone();
+    let ignore = {
+        let config = config_state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+        IgnoreList::new(&config.ignore_patterns)
+    };
     
     tokio::task::spawn_blocking(move || {
         let emit_progress = |stage: &str, current: usize, total: usize, message: &str| {
@@ -114,7 +120,10 @@ pub async fn find_duplicates(
 
             if !root_path.exists() { return; }
             
-            for entry in WalkDir::new(root_path).into_iter().filter_map(|e| e.ok()) {
+            let walker = WalkDir::new(root_path).into_iter().filter_entry(|e| {
+                e.depth() == 0 || !(e.file_type().is_dir() && ignore.matches(&e.file_name().to_string_lossy()))
+            });
+            for entry in walker.filter_map(|e| e.ok()) {
                 if cancel_flag.load(Ordering::Relaxed) { break; }
                 if entry.file_type().is_file() {
                     if let Ok(metadata) = entry.metadata() {
@@ -312,7 +321,215 @@ pub async fn find_duplicates(
     }).await.map_err(|e| CommandError::IoError(format!("Task execution failed: {}", e)))?
 }
 
+/// One image of a `SimilarImageGroup`, with its Hamming distance to the group's first image.
+#[derive(Serialize)]
+pub struct SimilarImage {
+    pub file: FileEntry,
+    pub distance: u32,
+}
+
+#[derive(Serialize)]
+pub struct SimilarImageGroup {
+    pub files: Vec<SimilarImage>,
+}
+
+/// Difference hash: the image shrunk to 9x8 grayscale, one bit per horizontally adjacent pixel
+/// pair telling whether brightness increases. Survives re-encoding, resizing and small edits.
+fn dhash(path: &Path) -> Option<u64> {
+    let img = image::ImageReader::open(path).ok()?.with_guessed_format().ok()?.decode().ok()?;
+    let small = img
+        .thumbnail(64, 64)
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            if small.get_pixel(x + 1, y)[0] > small.get_pixel(x, y)[0] {
+                hash |= 1;
+            }
+        }
+    }
+    Some(hash)
+}
+
+/// Groups the images under `paths` whose perceptual hashes differ by at most `threshold` bits
+/// (0-64; around 10 catches resized and re-encoded copies). Each image joins the first group
+/// whose first image is close enough. Shares cancellation with `find_duplicates`.
+#[tauri::command]
+pub async fn find_similar_images(
+    app: AppHandle,
+    state: State<'_, DuplicateSearchState>,
+    config_state: State<'_, ConfigManager>,
+    paths: Vec<String>,
+    threshold: u32,
+) -> Result<Vec<SimilarImageGroup>, CommandError> {
+    state.0.store(false, Ordering::Relaxed);
+    let cancel_flag = state.0.clone();
+    let threshold = threshold.min(64);
+    let ignore = {
+        let config = config_state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+        IgnoreList::new(&config.ignore_patterns)
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let emit_progress = |stage: &str, current: usize, total: usize, message: &str| {
+            let _ = app.emit("duplicates_progress", DuplicatesProgress {
+                stage: stage.to_string(),
+                current,
+                total,
+                message: message.to_string(),
+            });
+        };
+
+        emit_progress("Scanning", 0, 0, "Initializing...");
+        let mut images = Vec::new();
+        for path_str in &paths {
+            let root = PathBuf::from(crate::utils::path_security::normalize_drive_root(path_str));
+            let walker = WalkDir::new(root).into_iter().filter_entry(|e| {
+                e.depth() == 0 || !(e.file_type().is_dir() && ignore.matches(&e.file_name().to_string_lossy()))
+            });
+            for entry in walker.filter_map(|e| e.ok()) {
+                if cancel_flag.load(Ordering::Relaxed) { return Ok(vec![]); }
+                if entry.file_type().is_file() && crate::utils::thumbnails::is_preview_image(entry.path()) {
+                    images.push(entry.into_path());
+                }
+            }
+        }
+
+        let total = images.len();
+        emit_progress("Hashing Images", 0, total, "Computing perceptual hashes...");
+        let processed = AtomicUsize::new(0);
+        let hashes: Vec<(PathBuf, u64)> = images
+            .into_par_iter()
+            .filter_map(|path| {
+                if cancel_flag.load(Ordering::Relaxed) { return None; }
+                let hash = dhash(&path);
+                let p = processed.fetch_add(1, Ordering::Relaxed) + 1;
+                if p % 100 == 0 || p == total {
+                    emit_progress("Hashing Images", p, total, &path.file_name().unwrap_or_default().to_string_lossy());
+                }
+                hash.map(|h| (path, h))
+            })
+            .collect();
+
+        if cancel_flag.load(Ordering::Relaxed) { return Ok(vec![]); }
+
+        emit_progress("Grouping", 0, hashes.len(), "Comparing images...");
+        let mut groups: Vec<(u64, Vec<(PathBuf, u32)>)> = Vec::new();
+        for (path, hash) in hashes {
+            match groups.iter_mut().find(|(reference, _)| (reference ^ hash).count_ones() <= threshold) {
+                Some((reference, members)) => {
+                    let distance = (*reference ^ hash).count_ones();
+                    members.push((path, distance));
+                }
+                None => groups.push((hash, vec![(path, 0)])),
+            }
+        }
+
+        let mut result_groups: Vec<SimilarImageGroup> = groups
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .map(|(_, members)| SimilarImageGroup {
+                files: members
+                    .into_iter()
+                    .filter_map(|(p, distance)| {
+                        crate::models::file_entry::get_file_entry_from_path(&p).ok().map(|file| SimilarImage { file, distance })
+                    })
+                    .collect(),
+            })
+            .filter(|g| g.files.len() > 1)
+            .collect();
+
+        result_groups.sort_by(|a, b| b.files.len().cmp(&a.files.len()));
+        Ok(result_groups)
+    }).await.map_err(|e| CommandError::IoError(format!("Task execution failed: {}", e)))?
+}
+
 #[tauri::command]
 pub fn cancel_find_duplicates(state: State<'_, DuplicateSearchState>) {
     state.0.store(true, Ordering::Relaxed);
 }
+
+#[derive(Serialize)]
+pub struct FileComparison {
+    pub equal: bool,
+    /// Offset of the first differing byte (the shorter length when only sizes differ)
+    pub first_difference: Option<u64>,
+}
+
+const COMPARE_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Reads `path` chunk by chunk on its own thread so both files are read concurrently.
+/// The reader stops as soon as the receiving side is dropped.
+fn spawn_chunk_reader(path: PathBuf) -> std::sync::mpsc::Receiver<std::io::Result<Vec<u8>>> {
+    let (tx, rx) = std::sync::mpsc::sync_channel(4);
+    std::thread::spawn(move || {
+        let mut file = match File::open(&path) {
+            Ok(f) => f,
+            Err(e) => { let _ = tx.send(Err(e)); return; }
+        };
+        loop {
+            let mut buf = vec![0u8; COMPARE_CHUNK_SIZE];
+            let mut filled = 0;
+            // Fill the whole chunk so both sides stay aligned on chunk boundaries
+            while filled < buf.len() {
+                match file.read(&mut buf[filled..]) {
+                    Ok(0) => break,
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(e) => { let _ = tx.send(Err(e)); return; }
+                }
+            }
+            buf.truncate(filled);
+            let done = filled == 0;
+            if tx.send(Ok(buf)).is_err() || done { return; }
+        }
+    });
+    rx
+}
+
+fn compare_files(a: PathBuf, b: PathBuf) -> Result<FileComparison, CommandError> {
+    let size_a = std::fs::metadata(&a)?.len();
+    let size_b = std::fs::metadata(&b)?.len();
+    if size_a != size_b {
+        return Ok(FileComparison { equal: false, first_difference: Some(size_a.min(size_b)) });
+    }
+
+    let rx_a = spawn_chunk_reader(a);
+    let rx_b = spawn_chunk_reader(b);
+    let mut offset = 0u64;
+    loop {
+        let (chunk_a, chunk_b) = match (rx_a.recv(), rx_b.recv()) {
+            (Ok(ca), Ok(cb)) => (ca?, cb?),
+            _ => return Err(CommandError::IoError("File reader stopped unexpectedly".to_string())),
+        };
+        if let Some(pos) = chunk_a.iter().zip(chunk_b.iter()).position(|(x, y)| x != y) {
+            return Ok(FileComparison { equal: false, first_difference: Some(offset + pos as u64) });
+        }
+        if chunk_a.len() != chunk_b.len() {
+            // File changed size while reading
+            return Ok(FileComparison { equal: false, first_difference: Some(offset + chunk_a.len().min(chunk_b.len()) as u64) });
+        }
+        if chunk_a.is_empty() {
+            return Ok(FileComparison { equal: true, first_difference: None });
+        }
+        offset += chunk_a.len() as u64;
+    }
+}
+
+/// Byte-compares two files, stopping at the first difference. Cheaper than hashing both.
+#[tauri::command]
+pub async fn files_equal(a: String, b: String) -> Result<FileComparison, CommandError> {
+    let path_a = crate::utils::path_security::validate_path(&a)?;
+    let path_b = crate::utils::path_security::validate_path(&b)?;
+    for p in [&path_a, &path_b] {
+        if p.is_dir() {
+            return Err(CommandError::PathError(format!("{} is a directory", p.display())));
+        }
+    }
+
+    tokio::task::spawn_blocking(move || compare_files(path_a, path_b))
+        .await
+        .map_err(|e| CommandError::IoError(format!("Task execution failed: {}", e)))?
+}
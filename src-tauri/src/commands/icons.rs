@@ -3,25 +3,116 @@ use std::io::Cursor;
 use std::os::windows::ffi::OsStrExt;
 use windows::core::PCWSTR;
 use windows::Win32::UI::Shell::{
-    SHGetFileInfoW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_SMALLICON, SHGFI_SYSICONINDEX,
-    SHGetImageList, SHIL_JUMBO,
+    SHGetFileInfoW, SHFILEINFOW, SHGFI_ADDOVERLAYS, SHGFI_ICON, SHGFI_LARGEICON, SHGFI_OVERLAYINDEX,
+    SHGFI_SMALLICON, SHGFI_SYSICONINDEX, SHGetImageList, SHIL_EXTRALARGE, SHIL_JUMBO, SHIL_LARGE, SHIL_SMALL,
+    AssocQueryStringW, ASSOCF_INIT_IGNOREUNKNOWN, ASSOCSTR, ASSOCSTR_EXECUTABLE, ASSOCSTR_FRIENDLYAPPNAME,
 };
-use windows::Win32::UI::Controls::IImageList;
+use windows::Win32::UI::Controls::{IImageList, ILD_TRANSPARENT};
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
 use windows::Win32::UI::WindowsAndMessaging::{DestroyIcon, HICON, GetIconInfo, DrawIconEx, DI_NORMAL};
 use windows::Win32::Graphics::Gdi::{
     GetDC, ReleaseDC, DeleteObject, CreateCompatibleDC, DeleteDC, HGDIOBJ, 
     CreateDIBSection, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
     GetObjectW, BITMAP
 };
-use crate::models::CommandError;
+use crate::models::{CommandError, ConfigManager};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Mutex;
 use lazy_static::lazy_static;
+use tauri::State;
 
 lazy_static! {
     static ref ICON_CACHE: Mutex<HashMap<String, Vec<u8>>> = Mutex::new(HashMap::new());
 }
 
+/// Extensions whose icon can differ from file to file (embedded resources, shortcut targets, previews).
+const PER_FILE_ICON_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "lnk", "url", "ico", "cur", "scr", "cpl", "msi",
+    "png", "jpg", "jpeg", "gif", "bmp", "webp", "tif", "tiff",
+];
+
+/// How a requested icon size is rendered: which system image list to read and the PNG edge length.
+pub(crate) struct IconSize {
+    key: &'static str,
+    image_list: u32,
+    target: i32,
+    small: bool,
+}
+
+impl IconSize {
+    /// Accepts a pixel size ("16", "32", "48", "256") or the legacy "small"/"large" names.
+    fn parse(size: &str) -> Self {
+        match size {
+            "16" => IconSize { key: "16", image_list: SHIL_SMALL, target: 16, small: true },
+            "32" => IconSize { key: "32", image_list: SHIL_LARGE, target: 32, small: false },
+            "48" => IconSize { key: "48", image_list: SHIL_EXTRALARGE, target: 48, small: false },
+            "256" => IconSize { key: "256", image_list: SHIL_JUMBO, target: 256, small: false },
+            "small" => IconSize { key: "small", image_list: SHIL_EXTRALARGE, target: 32, small: true },
+            // Target size for display (Retina/High DPI friendly)
+            _ => IconSize { key: "large", image_list: SHIL_JUMBO, target: 96, small: false },
+        }
+    }
+
+    /// Smallest supported size that covers `pixels`.
+    pub(crate) fn for_pixels(pixels: u32) -> &'static str {
+        match pixels {
+            0..=16 => "16",
+            17..=32 => "32",
+            33..=48 => "48",
+            _ => "256",
+        }
+    }
+}
+
+fn cache_icon(key: String, data: &[u8]) {
+    let mut cache = ICON_CACHE.lock().unwrap();
+    if cache.len() > 1000 {
+        if let Some(key) = cache.keys().next().cloned() {
+            cache.remove(&key);
+        }
+    }
+    cache.insert(key, data.to_vec());
+}
+
+/// Attributes that make the shell draw an overlay (link arrow, cloud status, compressed, encrypted):
+/// offline, reparse point, compressed, encrypted, pinned, unpinned and the two recall flags.
+const OVERLAY_ATTRIBUTES: u32 = 0x1000 | 0x400 | 0x800 | 0x4000 | 0x80000 | 0x100000 | 0x40000 | 0x400000;
+
+/// Extension whose generic icon can stand in for `path`, or None when the file needs its own
+/// extraction, including files whose attributes call for an overlay the generic icon lacks.
+fn shared_icon_extension(path: &str) -> Option<String> {
+    use std::os::windows::fs::MetadataExt;
+
+    let path = Path::new(path);
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if PER_FILE_ICON_EXTENSIONS.contains(&ext.as_str()) {
+        return None;
+    }
+    // Paths that can't be read, such as entries inside an archive, still get the generic icon
+    match std::fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() || m.file_attributes() & OVERLAY_ATTRIBUTES != 0 => None,
+        _ => Some(ext),
+    }
+}
+
+/// Icon for `path`, reusing one icon per extension for ordinary files unless `exact` is set.
+pub fn file_icon_png(path: &str, size: &str, exact: bool) -> Result<Vec<u8>, String> {
+    if !exact {
+        if let Some(ext) = shared_icon_extension(path) {
+            let cache_key = format!("ext_{}_{}", ext, IconSize::parse(size).key);
+            if let Some(data) = ICON_CACHE.lock().unwrap().get(&cache_key) {
+                return Ok(data.clone());
+            }
+            // SHGFI_USEFILEATTRIBUTES: resolved from the extension alone, without touching the disk
+            let data = extract_icon_png(&format!("file.{}", ext), size, true)?;
+            cache_icon(cache_key, &data);
+            return Ok(data);
+        }
+    }
+    extract_icon_png(path, size, false)
+}
+
 #[tauri::command]
 pub fn purge_icon_cache() {
     let mut cache = ICON_CACHE.lock().unwrap();
@@ -29,25 +120,64 @@ pub fn purge_icon_cache() {
     cache.shrink_to_fit();
 }
 
+/// `size` is "16", "32", "48" or "256" (jumbo); "small"/"large" are still accepted.
 #[tauri::command]
-pub fn get_file_icon(path: String, size: String) -> Result<Vec<u8>, CommandError> {
-    extract_icon_png(&path, &size, false)
+pub fn get_file_icon(config: State<'_, ConfigManager>, path: String, size: String) -> Result<Vec<u8>, CommandError> {
+    let exact = config.0.lock().map(|c| c.exact_file_icons).unwrap_or(false);
+    file_icon_png(&path, &size, exact)
         .map_err(|e| CommandError::SystemError(format!("Failed to extract icon: {}", e)))
 }
 
+/// Batch variant of `get_file_icon` for a screenful of rows. `size` is the display size in pixels
+/// (rounded up to 16/32/48/256).
+/// Files sharing an extension are extracted once; paths whose icon fails are left out of the map.
+#[tauri::command]
+pub async fn get_file_icons(
+    config: State<'_, ConfigManager>,
+    paths: Vec<String>,
+    size: u32,
+) -> Result<HashMap<String, Vec<u8>>, CommandError> {
+    let exact = config.0.lock().map(|c| c.exact_file_icons).unwrap_or(false);
+    let size = IconSize::for_pixels(size);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        // Shell icon lookups need COM on this worker thread
+        let com_initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+
+        let mut icons = HashMap::with_capacity(paths.len());
+        for path in paths {
+            if let Ok(data) = file_icon_png(&path, size, exact) {
+                icons.insert(path, data);
+            }
+        }
+
+        if com_initialized {
+            unsafe { CoUninitialize(); }
+        }
+        icons
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(e.to_string()))
+}
+
 pub fn extract_icon_png(path: &str, size: &str, use_attributes: bool) -> Result<Vec<u8>, String> {
+    let icon_size = IconSize::parse(size);
     let wide_path: Vec<u16> = std::ffi::OsStr::new(path)
         .encode_wide()
         .chain(std::iter::once(0))
         .collect();
 
     let mut shfileinfo = SHFILEINFOW::default();
-    let mut flags = SHGFI_SYSICONINDEX | if size == "small" { SHGFI_SMALLICON } else { SHGFI_LARGEICON };
+    let mut flags = SHGFI_SYSICONINDEX | if icon_size.small { SHGFI_SMALLICON } else { SHGFI_LARGEICON };
     
     let mut attributes = windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES(0);
     if use_attributes {
         flags |= windows::Win32::UI::Shell::SHGFI_USEFILEATTRIBUTES;
         attributes = windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+    } else {
+        // Shortcut arrow, sharing and cloud sync overlays only exist for real paths.
+        // SHGFI_OVERLAYINDEX requires SHGFI_ICON; that handle is released right away.
+        flags |= SHGFI_ICON | SHGFI_OVERLAYINDEX;
     }
 
     unsafe {
@@ -63,9 +193,15 @@ pub fn extract_icon_png(path: &str, size: &str, use_attributes: bool) -> Result<
             return Err("Failed to get file icon info".to_string());
         }
 
-        let icon_index = shfileinfo.iIcon;
-        // Bump version key to v8
-        let cache_key = format!("v8_{}_{}", icon_index, size);
+        if !shfileinfo.hIcon.is_invalid() {
+            let _ = DestroyIcon(shfileinfo.hIcon);
+        }
+
+        // With SHGFI_OVERLAYINDEX the overlay index (1-based, 0 = none) is in the high byte
+        let icon_index = shfileinfo.iIcon & 0x00FF_FFFF;
+        let overlay_index = ((shfileinfo.iIcon as u32) >> 24) & 0xFF;
+        // Bump version key to v9 (overlay state is part of the key)
+        let cache_key = format!("v9_{}_{}_{}", icon_index, overlay_index, icon_size.key);
 
         {
             let cache = ICON_CACHE.lock().unwrap();
@@ -74,18 +210,32 @@ pub fn extract_icon_png(path: &str, size: &str, use_attributes: bool) -> Result<
             }
         }
 
-        // Try to get high quality icon (JUMBO = 256, EXTRALARGE = 48)
-        let list_id = if size == "small" { 2 } else { SHIL_JUMBO as i32 }; 
-        let image_list: windows::core::Result<IImageList> = SHGetImageList(list_id);
-        
+        // Try to get high quality icon (JUMBO = 256, EXTRALARGE = 48). Jumbo requests fall back
+        // to the 48px list before the low-res SHGFI_ICON path below.
+        let mut list_ids = vec![icon_size.image_list];
+        if icon_size.image_list == SHIL_JUMBO {
+            list_ids.push(SHIL_EXTRALARGE);
+        }
+
         let mut hicon = HICON::default();
-        if let Ok(list) = image_list {
-            hicon = list.GetIcon(icon_index, 0).unwrap_or_default();
+        for list_id in list_ids {
+            let image_list: windows::core::Result<IImageList> = SHGetImageList(list_id as i32);
+            if let Ok(list) = image_list {
+                // INDEXTOOVERLAYMASK: the image list composites the overlay onto the icon
+                let draw_flags = ILD_TRANSPARENT.0 | (overlay_index << 8);
+                hicon = list.GetIcon(icon_index, draw_flags).unwrap_or_default();
+            }
+            if !hicon.is_invalid() {
+                break;
+            }
         }
 
         if hicon.is_invalid() {
             let mut shfileinfo_fallback = SHFILEINFOW::default();
-            let fallback_flags = SHGFI_ICON | if size == "small" { SHGFI_SMALLICON } else { SHGFI_LARGEICON };
+            let mut fallback_flags = SHGFI_ICON | if icon_size.small { SHGFI_SMALLICON } else { SHGFI_LARGEICON };
+            if overlay_index != 0 {
+                fallback_flags |= SHGFI_ADDOVERLAYS;
+            }
             SHGetFileInfoW(
                 PCWSTR(wide_path.as_ptr()),
                 windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES(0),
@@ -100,10 +250,7 @@ pub fn extract_icon_png(path: &str, size: &str, use_attributes: bool) -> Result<
             return Err("Invalid icon handle".to_string());
         }
 
-        // Target size for display (Retina/High DPI friendly)
-        let target_size = if size == "small" { 32 } else { 96 }; 
-        
-        let icon_bitmap = match icon_to_bitmap(hicon, target_size) {
+        let icon_bitmap = match icon_to_bitmap(hicon, icon_size.target) {
             Ok(b) => b,
             Err(e) => {
                 let _ = DestroyIcon(hicon);
@@ -121,15 +268,7 @@ pub fn extract_icon_png(path: &str, size: &str, use_attributes: bool) -> Result<
              return Err(format!("Failed to encode icon to PNG: {}", e));
         }
 
-        {
-            let mut cache = ICON_CACHE.lock().unwrap();
-            if cache.len() > 1000 {
-                if let Some(key) = cache.keys().next().cloned() {
-                    cache.remove(&key);
-                }
-            }
-            cache.insert(cache_key, png_buffer.clone());
-        }
+        cache_icon(cache_key, &png_buffer);
 
         Ok(png_buffer)
     }
@@ -311,3 +450,59 @@ fn icon_to_bitmap(hicon: HICON, target_size: i32) -> Result<RgbaImage, String> {
         Ok(image::imageops::resize(&canvas_2x, target_size as u32, target_size as u32, image::imageops::FilterType::Lanczos3))
     }
 }
+
+#[derive(serde::Serialize)]
+pub struct DefaultApp {
+    /// Display name, e.g. "Notepad"
+    pub name: String,
+    pub executable: String,
+    /// 32px PNG of the application's icon, when it could be extracted
+    pub icon: Option<Vec<u8>>,
+}
+
+/// Application that opens `path` when double-clicked, from its extension's association.
+/// None when the extension has no associated program.
+#[tauri::command]
+pub async fn get_default_app(path: String) -> Result<Option<DefaultApp>, CommandError> {
+    let Some(ext) = Path::new(&path).extension().map(|e| format!(".{}", e.to_string_lossy())) else {
+        return Ok(None);
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let com_initialized = unsafe { CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok() };
+
+        let app = assoc_string(&ext, ASSOCSTR_EXECUTABLE).map(|executable| {
+            let name = assoc_string(&ext, ASSOCSTR_FRIENDLYAPPNAME).unwrap_or_else(|| {
+                Path::new(&executable).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+            });
+            let icon = extract_icon_png(&executable, "32", false).ok();
+            DefaultApp { name, executable, icon }
+        });
+
+        if com_initialized {
+            unsafe { CoUninitialize(); }
+        }
+        app
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))
+}
+
+fn assoc_string(ext: &str, kind: ASSOCSTR) -> Option<String> {
+    let wide_ext: Vec<u16> = std::ffi::OsStr::new(ext).encode_wide().chain(std::iter::once(0)).collect();
+    let mut buffer = [0u16; 1024];
+    let mut len = buffer.len() as u32;
+    unsafe {
+        AssocQueryStringW(
+            ASSOCF_INIT_IGNOREUNKNOWN,
+            kind,
+            PCWSTR(wide_ext.as_ptr()),
+            PCWSTR::null(),
+            Some(windows::core::PWSTR(buffer.as_mut_ptr())),
+            &mut len,
+        ).ok().ok()?;
+    }
+    // `len` includes the terminating null
+    let value = String::from_utf16_lossy(&buffer[..(len as usize).saturating_sub(1)]);
+    (!value.is_empty()).then_some(value)
+}
@@ -0,0 +1,321 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use log::info;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::models::{CommandError, HistoryManager, ImageConvertOptions, ImageOutputFormat, ImageSize, ImageTransform, ProgressEvent, Transaction, TransactionDetails, TransactionType};
+use crate::utils::path_security::validate_path;
+
+/// Quality used when a transformed JPEG is written back.
+const JPEG_QUALITY: u8 = 92;
+
+/// Where the previous content of edited files is kept for undo. Emptied when the app exits,
+/// since the history isn't persisted either.
+fn edit_backup_dir() -> PathBuf {
+    std::env::temp_dir().join("oxyde_edit_backups")
+}
+
+pub fn clear_edit_backups() {
+    let _ = fs::remove_dir_all(edit_backup_dir());
+}
+
+/// Rotates or flips an image in place, keeping its format, and returns the new size.
+/// The EXIF orientation is applied first; a JPEG keeps its EXIF block with the orientation reset.
+/// The previous content is backed up so the change can be undone.
+#[tauri::command]
+pub async fn transform_image(app: AppHandle, path: String, op: ImageTransform) -> Result<ImageSize, CommandError> {
+    let source = validate_path(&path)?;
+    if !source.is_file() {
+        return Err(CommandError::PathError(format!("Not a file: {}", source.display())));
+    }
+
+    let (size, backup) = tauri::async_runtime::spawn_blocking(move || rewrite_transformed(&source, op))
+        .await
+        .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))??;
+
+    info!("Applied {:?} to {:?}", op, path);
+    let tx_details = TransactionDetails {
+        paths: vec![path],
+        target_dir: None,
+        old_path: Some(backup.to_string_lossy().to_string()),
+        new_path: None,
+        created_files: None,
+    };
+    app.state::<HistoryManager>().push(Transaction::new(TransactionType::Edit, tx_details));
+    Ok(size)
+}
+
+fn image_error(e: image::ImageError) -> CommandError {
+    CommandError::Other(format!("Failed to process image: {}", e))
+}
+
+/// An image decoded upright, with what's needed to write it back.
+struct OrientedImage {
+    img: DynamicImage,
+    format: Option<ImageFormat>,
+    /// Raw EXIF (TIFF) data of the source, if any
+    exif: Option<Vec<u8>>,
+}
+
+/// Decodes `source` upright, with its EXIF orientation applied, along with its detected format.
+fn open_oriented(source: &Path) -> Result<OrientedImage, CommandError> {
+    let reader = ImageReader::open(source)?.with_guessed_format()?;
+    let format = reader.format();
+    let mut decoder = reader.into_decoder().map_err(image_error)?;
+    let exif = decoder.exif_metadata().ok().flatten();
+    let orientation = decoder.orientation().map_err(image_error)?;
+    let mut img = DynamicImage::from_decoder(decoder).map_err(image_error)?;
+    img.apply_orientation(orientation);
+    Ok(OrientedImage { img, format, exif })
+}
+
+fn rewrite_transformed(source: &Path, op: ImageTransform) -> Result<(ImageSize, PathBuf), CommandError> {
+    let OrientedImage { img, format, exif } = open_oriented(source)?;
+    let format = format
+        .filter(|f| f.writing_enabled())
+        .ok_or_else(|| CommandError::Other("This image format can't be written back".to_string()))?;
+
+    let img = match op {
+        ImageTransform::Rotate90 => img.rotate90(),
+        ImageTransform::Rotate180 => img.rotate180(),
+        ImageTransform::Rotate270 => img.rotate270(),
+        ImageTransform::FlipH => img.fliph(),
+        ImageTransform::FlipV => img.flipv(),
+    };
+
+    // Written next to the original, then renamed over it, so a failure never leaves a half-written image
+    let file_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let temp = source.with_file_name(format!(".{}.oxyde-tmp", file_name));
+    // The pixels are now upright, so the copied metadata must not rotate them again
+    let exif = exif.map(|mut data| {
+        reset_exif_orientation(&mut data);
+        data
+    });
+    if let Err(e) = encode(&img, format, JPEG_QUALITY, exif.as_deref(), &temp) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    let backup_dir = edit_backup_dir();
+    fs::create_dir_all(&backup_dir)?;
+    let backup = backup_dir.join(format!("{}_{}", uuid::Uuid::new_v4(), file_name));
+    let replaced = fs::copy(source, &backup).and_then(|_| fs::rename(&temp, source));
+    if let Err(e) = replaced {
+        let _ = fs::remove_file(&temp);
+        let _ = fs::remove_file(&backup);
+        return Err(e.into());
+    }
+
+    Ok((ImageSize { width: img.width(), height: img.height() }, backup))
+}
+
+/// Writes `img` to `target`. `exif` is only kept for JPEG output.
+fn encode(img: &DynamicImage, format: ImageFormat, quality: u8, exif: Option<&[u8]>, target: &Path) -> Result<(), CommandError> {
+    let save_error = |e: image::ImageError| CommandError::Other(format!("Failed to save image: {}", e));
+    let mut writer = BufWriter::new(fs::File::create(target)?);
+    if format == ImageFormat::Jpeg {
+        // JPEG has no alpha channel
+        let mut encoded = Vec::new();
+        img.to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut encoded, quality))
+            .map_err(save_error)?;
+        match exif.and_then(exif_segment) {
+            // Right after the SOI marker, where readers expect APP1
+            Some(segment) if encoded.starts_with(&[0xFF, 0xD8]) => {
+                writer.write_all(&encoded[..2])?;
+                writer.write_all(&segment)?;
+                writer.write_all(&encoded[2..])?;
+            }
+            _ => writer.write_all(&encoded)?,
+        }
+    } else {
+        img.write_to(&mut writer, format).map_err(save_error)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// JPEG APP1 segment carrying `exif`, or `None` if it doesn't fit in one segment.
+fn exif_segment(exif: &[u8]) -> Option<Vec<u8>> {
+    const HEADER: &[u8] = b"Exif\0\0";
+    let payload = exif.strip_prefix(HEADER).unwrap_or(exif);
+    let len = u16::try_from(2 + HEADER.len() + payload.len()).ok()?;
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&len.to_be_bytes());
+    segment.extend_from_slice(HEADER);
+    segment.extend_from_slice(payload);
+    Some(segment)
+}
+
+/// Sets the orientation tag (0x0112) of raw EXIF data to 1 (upright), if present in IFD0.
+fn reset_exif_orientation(exif: &mut [u8]) {
+    let offset = if exif.starts_with(b"Exif\0\0") { 6 } else { 0 };
+    let tiff = &mut exif[offset..];
+    let big_endian = match tiff.get(..2) {
+        Some(b"MM") => true,
+        Some(b"II") => false,
+        _ => return,
+    };
+    let read_u16 = |b: &[u8], at: usize| {
+        b.get(at..at + 2).map(|v| if big_endian { u16::from_be_bytes([v[0], v[1]]) } else { u16::from_le_bytes([v[0], v[1]]) })
+    };
+    let ifd = match tiff.get(4..8) {
+        Some(v) if big_endian => u32::from_be_bytes([v[0], v[1], v[2], v[3]]) as usize,
+        Some(v) => u32::from_le_bytes([v[0], v[1], v[2], v[3]]) as usize,
+        None => return,
+    };
+    let Some(count) = read_u16(tiff, ifd) else { return };
+    for i in 0..count as usize {
+        let entry = ifd + 2 + i * 12;
+        if read_u16(tiff, entry) == Some(0x0112) {
+            // A SHORT value sits in the first two bytes of the entry's value field
+            let one = if big_endian { 1u16.to_be_bytes() } else { 1u16.to_le_bytes() };
+            if let Some(value) = tiff.get_mut(entry + 8..entry + 10) {
+                value.copy_from_slice(&one);
+            }
+            return;
+        }
+    }
+}
+
+/// Cancellation tokens of running `batch_convert_images` jobs, keyed by op id.
+#[derive(Default)]
+pub struct ImageBatchState(pub std::sync::Mutex<std::collections::HashMap<String, Arc<AtomicBool>>>);
+
+/// An image `batch_convert_images` couldn't convert.
+#[derive(Serialize, Clone, Debug)]
+pub struct ImageConvertFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImageBatchResult {
+    /// Files written, in the order of the input paths
+    pub outputs: Vec<String>,
+    pub failures: Vec<ImageConvertFailure>,
+    pub cancelled: bool,
+}
+
+impl ImageOutputFormat {
+    fn format(self) -> ImageFormat {
+        match self {
+            ImageOutputFormat::Jpeg => ImageFormat::Jpeg,
+            ImageOutputFormat::Png => ImageFormat::Png,
+            ImageOutputFormat::Webp => ImageFormat::WebP,
+            ImageOutputFormat::Bmp => ImageFormat::Bmp,
+            ImageOutputFormat::Tiff => ImageFormat::Tiff,
+        }
+    }
+}
+
+/// Resizes and re-encodes `paths` into `output_dir`, named after the source with the target
+/// format's extension (numbered when taken, so nothing is overwritten). Reports `progress`
+/// per image and carries on past images that fail. Cancelled with `cancel_image_batch` and the
+/// same `op_id`.
+#[tauri::command]
+pub async fn batch_convert_images(
+    app: AppHandle,
+    state: State<'_, ImageBatchState>,
+    paths: Vec<String>,
+    options: ImageConvertOptions,
+    output_dir: String,
+    op_id: String,
+) -> Result<ImageBatchResult, CommandError> {
+    let sources = paths.iter().map(|p| validate_path(p)).collect::<Result<Vec<PathBuf>, CommandError>>()?;
+    let output_dir = validate_path(&output_dir)?;
+    fs::create_dir_all(&output_dir)?;
+    if options.max_width == Some(0) || options.max_height == Some(0) {
+        return Err(CommandError::Other("Maximum size must be greater than zero".to_string()));
+    }
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    state.0.lock()
+        .map_err(|_| CommandError::SystemError("Failed to lock image batch state".to_string()))?
+        .insert(op_id.clone(), cancel_flag.clone());
+
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let total = sources.len() as u64;
+        let emit_progress = |current: u64, status: &str, filename: Option<String>| {
+            let _ = app.emit("progress", ProgressEvent {
+                id: task_op_id.clone(),
+                task: "image_batch".to_string(),
+                current,
+                total,
+                status: status.to_string(),
+                filename,
+            });
+        };
+
+        let mut outputs = Vec::new();
+        let mut failures = Vec::new();
+        for (index, source) in sources.iter().enumerate() {
+            if cancel_flag.load(Ordering::Relaxed) { break; }
+            emit_progress(index as u64, "running", source.file_name().map(|n| n.to_string_lossy().to_string()));
+            match convert_image(source, &options, &output_dir) {
+                Ok(output) => outputs.push(output.to_string_lossy().to_string()),
+                Err(e) => failures.push(ImageConvertFailure {
+                    path: source.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        emit_progress(total, if cancelled { "cancelled" } else { "completed" }, None);
+        info!("Converted {} image(s) to {:?}, {} failure(s)", outputs.len(), options.target_format, failures.len());
+
+        Ok(ImageBatchResult { outputs, failures, cancelled })
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    if let Ok(mut tokens) = state.0.lock() {
+        tokens.remove(&op_id);
+    }
+    result?
+}
+
+fn convert_image(source: &Path, options: &ImageConvertOptions, output_dir: &Path) -> Result<PathBuf, CommandError> {
+    let mut img = open_oriented(source)?.img;
+    let max_width = options.max_width.unwrap_or(u32::MAX);
+    let max_height = options.max_height.unwrap_or(u32::MAX);
+    if img.width() > max_width || img.height() > max_height {
+        img = img.resize(max_width.min(img.width()), max_height.min(img.height()), FilterType::Lanczos3);
+    }
+
+    let format = options.target_format.format();
+    let stem = source.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "image".to_string());
+    let extension = format.extensions_str().first().copied().unwrap_or("img");
+    let target = unused_path(output_dir, &stem, extension);
+    let quality = options.quality.unwrap_or(JPEG_QUALITY).clamp(1, 100);
+    if let Err(e) = encode(&img, format, quality, None, &target) {
+        let _ = fs::remove_file(&target);
+        return Err(e);
+    }
+    Ok(target)
+}
+
+/// `dir/stem.extension`, or `dir/stem (2).extension` and so on if that already exists.
+fn unused_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut candidate = dir.join(format!("{}.{}", stem, extension));
+    let mut n = 2;
+    while candidate.exists() {
+        candidate = dir.join(format!("{} ({}).{}", stem, n, extension));
+        n += 1;
+    }
+    candidate
+}
+
+#[tauri::command]
+pub fn cancel_image_batch(state: State<'_, ImageBatchState>, op_id: String) {
+    if let Ok(tokens) = state.0.lock() {
+        if let Some(flag) = tokens.get(&op_id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use crate::models::{
-    FileEntry, FileProperties, FileSummary, FolderSizeResult, CommandError, Transaction, TransactionType, TransactionDetails, HistoryManager
+    FileEntry, GroupKey, FileProperties, FileAttributes, FileAttributeChanges, FileTimestamps, FileTimestampChanges, FileSummary, TypeSummary, FolderSizeResult, CommandError, ProgressEvent, Transaction, TransactionType, TransactionDetails, HistoryManager
 };
 use tauri::Manager;
 use crate::utils::path_security::validate_path;
+use crate::commands::search::SearchPattern;
 use std::fs;
 use std::path::PathBuf;
 use std::time::SystemTime;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, State};
 use log::info;
 use serde::Serialize;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(target_os = "windows")]
 use windows::Win32::UI::Shell::PropertiesSystem::{SHGetPropertyStoreFromParsingName, IPropertyStore, GPS_DEFAULT};
@@ -22,6 +25,9 @@ pub struct DirResponse {
     pub entries: Vec<FileEntry>,
     pub summary: FileSummary,
     pub is_complete: bool,
+    /// Group headers in display order, when the listing was requested with `group_by`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Vec<GroupKey>>,
 }
 
 #[derive(Serialize, Clone)]
@@ -61,6 +67,10 @@ pub fn get_file_entry_from_metadata(metadata: &fs::Metadata, name: &str, path: &
         is_calculated: false,
         original_path: None,
         deleted_time: None,
+        group_key: None,
+        is_favorite: false,
+        match_context: None,
+        match_line: None,
     }
 }
 
@@ -73,45 +83,73 @@ pub async fn list_dir(
     sort_config: Option<crate::models::session::SortConfig>,
     show_hidden: Option<bool>,
     show_system: Option<bool>,
-    force_refresh: Option<bool>
+    force_refresh: Option<bool>,
+    group_by: Option<crate::models::session::GroupField>,
 ) -> Result<DirResponse, CommandError> {
     let show_hidden = show_hidden.unwrap_or(false);
     let show_system = show_system.unwrap_or(false);
-    let sort_config = sort_config.unwrap_or_default();
+    // This listing supersedes whatever `list_dir_streaming` was still reading for the panel
+    if let Some(streams) = app.try_state::<DirStreamState>() {
+        if let Some(flag) = streams.0.lock().ok().and_then(|mut tokens| tokens.remove(&panel_id)) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+    // An explicit sort wins over the one saved for this folder
+    let sort_config = sort_config
+        .or_else(|| {
+            let config = app.try_state::<crate::models::ConfigManager>()?;
+            let config = config.0.lock().ok()?;
+            config.folder_view_override(&path).cloned()
+        })
+        .unwrap_or_default();
 
     // 1. Check Cache
-    let cached_all_entries = {
-        if force_refresh.unwrap_or(false) {
+    let (cached_all_entries, name_filter) = {
+        let mut session = state.0.lock().unwrap();
+        let panel = if panel_id == "right" { &mut session.right_panel } else { &mut session.left_panel };
+
+        // The quick filter only applies to the folder it was typed in
+        if !panel.cached_results.as_ref().is_some_and(|c| c.path.to_string_lossy() == path) {
+            panel.filter = None;
+        }
+        let name_filter = panel.filter.as_deref().map(SearchPattern::name_filter);
+
+        let cached_all_entries = if force_refresh.unwrap_or(false) {
             None
-        } else {
-            let session = state.0.lock().unwrap();
-            let panel = if panel_id == "right" { &session.right_panel } else { &session.left_panel };
-            
-            if let Some(cached) = &panel.cached_results {
-                if cached.path.to_string_lossy() == path {
-                    // 1. Perfect match (path + config + filters)
-                    if cached.config == sort_config && cached.show_hidden == show_hidden && cached.show_system == show_system {
-                        return Ok(DirResponse {
-                            entries: cached.entries.clone(),
-                            summary: cached.summary.clone(),
-                            is_complete: true,
-                        });
-                    }
-                    
-                    // 2. Path match and filters match, but sort changed -> Re-sort cached entries
-                    if cached.show_hidden == show_hidden && cached.show_system == show_system {
-                        Some((cached.entries.clone(), cached.summary.clone()))
-                    } else {
-                        // Filters changed (hidden/system) -> Must re-read from disk to be accurate
-                        None
+        } else if let Some(cached) = &panel.cached_results {
+            if cached.path.to_string_lossy() == path {
+                // 1. Perfect match (path + config + filters)
+                if cached.config == sort_config && cached.show_hidden == show_hidden && cached.show_system == show_system {
+                    if let Some(filter) = &name_filter {
+                        let mut entries: Vec<FileEntry> = cached.entries.iter().filter(|e| filter.matches(&e.name)).cloned().collect();
+                        let summary = calculate_summary(&entries, Some(path.clone()));
+                        let groups = group_by.as_ref().map(|g| group_entries(&mut entries, g));
+                        return Ok(DirResponse { entries, summary, is_complete: true, groups });
                     }
+                    let mut entries = cached.entries.clone();
+                    let groups = group_by.as_ref().map(|g| group_entries(&mut entries, g));
+                    return Ok(DirResponse {
+                        entries,
+                        summary: cached.summary.clone(),
+                        is_complete: true,
+                        groups,
+                    });
+                }
+                
+                // 2. Path match and filters match, but sort changed -> Re-sort cached entries
+                if cached.show_hidden == show_hidden && cached.show_system == show_system {
+                    Some((cached.entries.clone(), cached.summary.clone()))
                 } else {
+                    // Filters changed (hidden/system) -> Must re-read from disk to be accurate
                     None
                 }
             } else {
                 None
             }
-    }
+        } else {
+            None
+        };
+        (cached_all_entries, name_filter)
     };
 
     let (mut all_entries, _summary) = if let Some(cached) = cached_all_entries {
@@ -123,35 +161,21 @@ pub async fn list_dir(
                 archive_path.to_string_lossy().to_string(),
                 internal_path
             )?;
+            let mut entries = entries;
+            if let Some(filter) = &name_filter {
+                entries.retain(|e| filter.matches(&e.name));
+            }
             let summary = calculate_summary(&entries, Some(path.clone()));
-            return Ok(DirResponse { entries, summary, is_complete: true });
+            let groups = group_by.as_ref().map(|g| group_entries(&mut entries, g));
+            return Ok(DirResponse { entries, summary, is_complete: true, groups });
         }
 
         let dir_path = validate_path(&path)?;
         let read_dir = fs::read_dir(&dir_path)?;
 
         let mut entries = Vec::with_capacity(2048);
-        for entry in read_dir.flatten() {
-            if let Ok(metadata) = entry.metadata() {
-                let name = entry.file_name().to_string_lossy().to_string();
-                let path = entry.path();
-                let mut file_entry = get_file_entry_from_metadata(&metadata, &name, &path);
-                
-                // If it's a directory, check if it's protected (Access Denied)
-                if file_entry.is_dir {
-                    // Try to peek into the directory. If it fails with permission error, it's protected.
-                    // We don't use read_dir fully, just check if it's possible.
-                    if let Err(e) = fs::read_dir(&path) {
-                         let kind = e.kind();
-                         if kind == std::io::ErrorKind::PermissionDenied || kind == std::io::ErrorKind::NotFound {
-                             file_entry.is_protected = true;
-                         }
-                    }
-                }
-                
-                entries.push(file_entry);
-            }
-        }
+        entries.extend(read_dir.flatten().filter_map(|entry| file_entry_from_dir_entry(&entry)));
+        mark_protected_dirs(&mut entries);
         (entries, calculate_summary(&[], None)) // temporary summary, will be replaced
     };
 
@@ -161,6 +185,11 @@ pub async fn list_dir(
         if e.is_hidden { return show_hidden; }
         true
     });
+    if let Some(favorites) = app.try_state::<crate::models::FavoriteFiles>() {
+        for entry in all_entries.iter_mut() {
+            entry.is_favorite = favorites.contains(&entry.path);
+        }
+    }
 
     let summary = calculate_summary(&all_entries, Some(path.clone()));
     sort_file_entries(&mut all_entries, &sort_config);
@@ -186,6 +215,16 @@ pub async fn list_dir(
         });
     }
 
+    // Quick filter: the cache above keeps the full listing so clearing the filter is free
+    let summary = match &name_filter {
+        Some(filter) => {
+            all_entries.retain(|e| filter.matches(&e.name));
+            calculate_summary(&all_entries, Some(path.clone()))
+        }
+        None => summary,
+    };
+    let groups = group_by.as_ref().map(|g| group_entries(&mut all_entries, g));
+
     // Progressive loading
     let total_visible = all_entries.len();
     let initial_count = 800;
@@ -195,6 +234,7 @@ pub async fn list_dir(
             entries: all_entries,
             summary,
             is_complete: true,
+            groups,
         })
     } else {
         // Split: keep initial, spawn remaining
@@ -228,7 +268,314 @@ pub async fn list_dir(
             entries: all_entries, // now contains only the initial batch
             summary,
             is_complete: false,
+            groups,
+        })
+    }
+}
+
+fn file_entry_from_dir_entry(entry: &fs::DirEntry) -> Option<FileEntry> {
+    let metadata = entry.metadata().ok()?;
+    let name = entry.file_name().to_string_lossy().to_string();
+    let path = entry.path();
+    // `is_protected` is filled in afterwards by `mark_protected_dirs`, in parallel
+    Some(get_file_entry_from_metadata(&metadata, &name, &path))
+}
+
+/// Whether `path` is a folder that can't be opened (Access Denied).
+fn is_protected_dir(path: &std::path::Path) -> bool {
+    // Try to peek into the directory. If it fails with permission error, it's protected.
+    // We don't use read_dir fully, just check if it's possible.
+    match fs::read_dir(path) {
+        Err(e) => matches!(e.kind(), std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::NotFound),
+        Ok(_) => false,
+    }
+}
+
+/// Flags the folders among `entries` that can't be opened. The peeks run on the rayon pool:
+/// done one after the other they doubled the time of listing a folder-heavy directory.
+fn mark_protected_dirs(entries: &mut [FileEntry]) {
+    use rayon::prelude::*;
+    entries.par_iter_mut()
+        .filter(|e| e.is_dir)
+        .for_each(|e| e.is_protected = is_protected_dir(std::path::Path::new(&e.path)));
+}
+
+/// Enumeration token of each panel's running `list_dir_streaming`, keyed by panel id.
+#[derive(Default)]
+pub struct DirStreamState(pub std::sync::Mutex<std::collections::HashMap<String, Arc<AtomicBool>>>);
+
+/// Sent once `list_dir_streaming` has enumerated the whole folder (or was cancelled).
+#[derive(Serialize, Clone)]
+pub struct DirStreamDone {
+    pub panel_id: String,
+    pub path: String,
+    pub summary: FileSummary,
+    pub cancelled: bool,
+}
+
+/// `DirBatchEvent` over borrowed entries, for `list_dir_streaming`.
+#[derive(Serialize, Clone)]
+struct DirBatchView<'a> {
+    panel_id: &'a str,
+    path: &'a str,
+    entries: &'a [FileEntry],
+    is_complete: bool,
+}
+
+/// Entries per `dir_batch` event of `list_dir_streaming`.
+const STREAM_BATCH_SIZE: usize = 2000;
+
+/// `list_dir` for huge folders: returns right away and emits `dir_batch` events while the folder
+/// is still being read, each batch filtered and sorted on its own, the last one with
+/// `is_complete`, then `dir_stream_done`.
+/// The fully sorted listing is cached at the end, so the next `list_dir` gets it for free; a
+/// folder already cached with the same filters is answered by `list_dir` directly. A new
+/// listing in the same panel (streamed or not), or `cancel_dir_stream`, stops the enumeration.
+#[tauri::command]
+pub async fn list_dir_streaming(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::models::SessionManager>,
+    streams: State<'_, DirStreamState>,
+    panel_id: String,
+    path: String,
+    sort_config: Option<crate::models::session::SortConfig>,
+    show_hidden: Option<bool>,
+    show_system: Option<bool>,
+    force_refresh: Option<bool>,
+) -> Result<DirResponse, CommandError> {
+    let show_hidden = show_hidden.unwrap_or(false);
+    let show_system = show_system.unwrap_or(false);
+
+    let is_cached = !force_refresh.unwrap_or(false) && {
+        let session = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session state".to_string()))?;
+        let panel = if panel_id == "right" { &session.right_panel } else { &session.left_panel };
+        panel.cached_results.as_ref().is_some_and(|c| {
+            c.path.to_string_lossy() == path && c.show_hidden == show_hidden && c.show_system == show_system
+        })
+    };
+    let is_virtual = crate::commands::archive::split_virtual_path(&path).is_some();
+    if is_cached || is_virtual {
+        return list_dir(app, state, panel_id, path, sort_config, Some(show_hidden), Some(show_system), force_refresh, None).await;
+    }
+
+    let sort_config = sort_config
+        .or_else(|| {
+            let config = app.try_state::<crate::models::ConfigManager>()?;
+            let config = config.0.lock().ok()?;
+            config.folder_view_override(&path).cloned()
+        })
+        .unwrap_or_default();
+    let dir_path = validate_path(&path)?;
+    let read_dir = fs::read_dir(&dir_path)?;
+
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = streams.0.lock()
+        .map_err(|_| CommandError::SystemError("Failed to lock dir stream state".to_string()))?
+        .insert(panel_id.clone(), cancel_flag.clone())
+    {
+        previous.store(true, Ordering::Relaxed);
+    }
+
+    let task_app = app.clone();
+    let task_panel_id = panel_id.clone();
+    let task_path = path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let favorites = task_app.try_state::<crate::models::FavoriteFiles>();
+        // Serialized from a borrow, so each batch is moved into the final listing instead of cloned
+        let emit_batch = |entries: &[FileEntry], is_complete: bool| {
+            let _ = task_app.emit("dir_batch", DirBatchView {
+                panel_id: &task_panel_id,
+                path: &task_path,
+                entries,
+                is_complete,
+            });
+        };
+
+        let mut all_entries = Vec::new();
+        let mut batch = Vec::with_capacity(STREAM_BATCH_SIZE);
+        let mut cancelled = false;
+        for entry in read_dir.flatten() {
+            if cancel_flag.load(Ordering::Relaxed) {
+                cancelled = true;
+                break;
+            }
+            let Some(mut file_entry) = file_entry_from_dir_entry(&entry) else { continue };
+            let visible = if file_entry.is_system { show_system } else if file_entry.is_hidden { show_hidden } else { true };
+            if !visible { continue; }
+            if let Some(favorites) = &favorites {
+                file_entry.is_favorite = favorites.contains(&file_entry.path);
+            }
+            batch.push(file_entry);
+
+            if batch.len() >= STREAM_BATCH_SIZE {
+                mark_protected_dirs(&mut batch);
+                sort_file_entries(&mut batch, &sort_config);
+                emit_batch(&batch, false);
+                all_entries.append(&mut batch);
+            }
+        }
+        if !cancelled {
+            // The last batch, possibly empty, tells the `dir_batch` listeners the folder is complete
+            mark_protected_dirs(&mut batch);
+            sort_file_entries(&mut batch, &sort_config);
+            emit_batch(&batch, true);
+            all_entries.append(&mut batch);
+        }
+
+        let summary = calculate_summary(&all_entries, Some(task_path.clone()));
+        let session_state = task_app.state::<crate::models::SessionManager>();
+        if let Ok(mut session) = session_state.0.lock() {
+            // Checked under the session lock: a `list_dir` or another stream in this panel since
+            // then cancels this one, and must not have its cache or search replaced
+            let is_current = !cancel_flag.load(Ordering::Relaxed)
+                && task_app.try_state::<DirStreamState>()
+                    .and_then(|streams| streams.0.lock().ok().map(|tokens| {
+                        tokens.get(&task_panel_id).is_some_and(|t| Arc::ptr_eq(t, &cancel_flag))
+                    }))
+                    .unwrap_or(false);
+            cancelled |= !is_current;
+            if is_current {
+                sort_file_entries(&mut all_entries, &sort_config);
+                let panel = if task_panel_id == "right" { &mut session.right_panel } else { &mut session.left_panel };
+                if let Some(mut ctx) = panel.search_context.take() {
+                    ctx.results.clear();
+                    ctx.results.shrink_to_fit();
+                }
+                panel.filter = None;
+                panel.cached_results = Some(crate::models::session::CachedResults {
+                    path: PathBuf::from(&task_path),
+                    entries: all_entries,
+                    summary: summary.clone(),
+                    config: sort_config,
+                    show_hidden,
+                    show_system,
+                });
+            }
+        }
+
+        if let Some(streams) = task_app.try_state::<DirStreamState>() {
+            if let Ok(mut tokens) = streams.0.lock() {
+                if tokens.get(&task_panel_id).is_some_and(|t| Arc::ptr_eq(t, &cancel_flag)) {
+                    tokens.remove(&task_panel_id);
+                }
+            }
+        }
+        let _ = task_app.emit("dir_stream_done", DirStreamDone {
+            panel_id: task_panel_id,
+            path: task_path,
+            summary,
+            cancelled,
+        });
+    });
+
+    Ok(DirResponse {
+        entries: Vec::new(),
+        summary: calculate_summary(&[], Some(path)),
+        is_complete: false,
+        groups: None,
+    })
+}
+
+/// Stops the panel's running `list_dir_streaming`, e.g. when the user navigates away.
+#[tauri::command]
+pub fn cancel_dir_stream(streams: State<'_, DirStreamState>, panel_id: String) {
+    if let Ok(tokens) = streams.0.lock() {
+        if let Some(flag) = tokens.get(&panel_id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Paths of the entries in the panel's loaded folder whose names match `pattern` (a glob, or a
+/// regex when `regex` is set), for "select matching". Uses the cached listing, so no disk access;
+/// entries hidden by the quick filter are left out.
+#[tauri::command]
+pub fn match_entries(
+    state: State<'_, crate::models::SessionManager>,
+    panel_id: String,
+    pattern: String,
+    regex: Option<bool>,
+    ignore_accents: Option<bool>,
+) -> Result<Vec<String>, CommandError> {
+    let matcher = SearchPattern::selection(pattern.trim(), regex.unwrap_or(false), ignore_accents.unwrap_or(false))
+        .map_err(CommandError::Other)?;
+
+    let session = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session state".to_string()))?;
+    let panel = if panel_id == "right" { &session.right_panel } else { &session.left_panel };
+    let cached = panel.cached_results.as_ref()
+        .ok_or_else(|| CommandError::Other("No folder loaded in this panel".to_string()))?;
+    // The cache can still hold another tab's folder right after a tab switch
+    let active_path = panel.tabs.iter().find(|t| t.id == panel.active_tab_id).map(|t| &t.path);
+    if active_path != Some(&cached.path) {
+        return Ok(Vec::new());
+    }
+    let name_filter = panel.filter.as_deref().map(SearchPattern::name_filter);
+
+    Ok(cached.entries.iter()
+        .filter(|e| name_filter.as_ref().is_none_or(|f| f.matches(&e.name)))
+        .filter(|e| matcher.matches(&e.name))
+        .map(|e| e.path.clone())
+        .collect())
+}
+
+/// Stars or unstars a file or folder and returns whether it is now starred. The panels' cached
+/// listings are updated so the next `list_dir` shows the change without a reload.
+#[tauri::command]
+pub fn toggle_favorite(
+    app: AppHandle,
+    favorites: State<'_, crate::models::FavoriteFiles>,
+    state: State<'_, crate::models::SessionManager>,
+    path: String,
+) -> Result<bool, CommandError> {
+    let pb = validate_path(&path)?;
+    if !pb.exists() {
+        return Err(CommandError::PathError(format!("{} does not exist", pb.display())));
+    }
+    let path = pb.to_string_lossy().to_string();
+    let starred = favorites.toggle(&app, path.clone())?;
+
+    let mut guard = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session state".to_string()))?;
+    let session = &mut *guard;
+    for panel in [&mut session.left_panel, &mut session.right_panel] {
+        if let Some(cached) = panel.cached_results.as_mut() {
+            if let Some(entry) = cached.entries.iter_mut().find(|e| e.path == path) {
+                entry.is_favorite = starred;
+            }
+        }
+    }
+    Ok(starred)
+}
+
+/// Starred files and folders that still exist, for the favorites view.
+#[tauri::command]
+pub fn list_favorite_files(
+    app: AppHandle,
+    favorites: State<'_, crate::models::FavoriteFiles>,
+) -> Result<Vec<FileEntry>, CommandError> {
+    Ok(favorites.live(&app)?
+        .iter()
+        .filter_map(|p| crate::models::get_file_entry_from_path(std::path::Path::new(p)).ok())
+        .map(|mut entry| {
+            entry.is_favorite = true;
+            entry
         })
+        .collect())
+}
+
+fn compare_by_field(field: &crate::models::session::SortField, a: &FileEntry, b: &FileEntry) -> std::cmp::Ordering {
+    use crate::models::session::SortField;
+
+    match field {
+        SortField::Name => crate::utils::compare_natural(&a.name, &b.name),
+        SortField::Size => a.size.cmp(&b.size),
+        SortField::Date => a.modified.cmp(&b.modified),
+        SortField::Type => {
+            let ext_a = std::path::Path::new(&a.name).extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+            let ext_b = std::path::Path::new(&b.name).extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+            ext_a.cmp(&ext_b)
+        },
+        SortField::Location => a.path.to_lowercase().cmp(&b.path.to_lowercase()),
+        SortField::DeletedDate => a.deleted_time.cmp(&b.deleted_time),
     }
 }
 
@@ -236,10 +583,17 @@ pub fn sort_file_entries(entries: &mut [FileEntry], config: &crate::models::sess
     use crate::models::session::SortField;
     use crate::models::session::SortDirection;
 
+    let keys = config.sort_keys();
+    let (primary_field, primary_direction) = &keys[0];
+    let apply_direction = |cmp: std::cmp::Ordering, direction: &SortDirection| match direction {
+        SortDirection::Asc => cmp,
+        SortDirection::Desc => cmp.reverse(),
+    };
+
     entries.sort_unstable_by(|a, b| {
         // Folders-first by default.
         // Exception: when sorting by size, we mix them ONLY IF the folders involved have been calculated.
-        if config.field == SortField::Size {
+        if *primary_field == SortField::Size {
             let a_uncalc = a.is_dir && !a.is_calculated;
             let b_uncalc = b.is_dir && !b.is_calculated;
             if (a_uncalc || b_uncalc) && a.is_dir != b.is_dir {
@@ -249,24 +603,103 @@ pub fn sort_file_entries(entries: &mut [FileEntry], config: &crate::models::sess
             return b.is_dir.cmp(&a.is_dir);
         }
 
-        let cmp = match config.field {
-            SortField::Name => crate::utils::compare_natural(&a.name, &b.name),
-            SortField::Size => a.size.cmp(&b.size).then_with(|| crate::utils::compare_natural(&a.name, &b.name)),
-            SortField::Date => a.modified.cmp(&b.modified).then_with(|| crate::utils::compare_natural(&a.name, &b.name)),
-            SortField::Type => {
-                let ext_a = std::path::Path::new(&a.name).extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
-                let ext_b = std::path::Path::new(&b.name).extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
-                ext_a.cmp(&ext_b).then_with(|| crate::utils::compare_natural(&a.name, &b.name))
-            },
-            SortField::Location => a.path.to_lowercase().cmp(&b.path.to_lowercase()).then_with(|| crate::utils::compare_natural(&a.name, &b.name)),
-            SortField::DeletedDate => a.deleted_time.cmp(&b.deleted_time).then_with(|| crate::utils::compare_natural(&a.name, &b.name)),
-        };
+        keys.iter()
+            .fold(std::cmp::Ordering::Equal, |acc, (field, direction)| {
+                acc.then_with(|| apply_direction(compare_by_field(field, a, b), direction))
+            })
+            // Ties fall back to the name, in the primary direction
+            .then_with(|| apply_direction(crate::utils::compare_natural(&a.name, &b.name), primary_direction))
+    });
+}
+
+/// Upper bound (exclusive) of each size group; larger files are "gigantic".
+const SIZE_GROUPS: [(u64, &str); 6] = [
+    (1, "group_size_empty"),
+    (16 * 1024, "group_size_tiny"),
+    (1024 * 1024, "group_size_small"),
+    (128 * 1024 * 1024, "group_size_medium"),
+    (1024 * 1024 * 1024, "group_size_large"),
+    (4 * 1024 * 1024 * 1024, "group_size_huge"),
+];
+
+/// Start (ms since epoch, local time) of each relative date bucket, most recent first.
+fn date_group_bounds() -> Vec<(i64, &'static str)> {
+    use chrono::{Datelike, Duration, Local, NaiveDate};
+
+    let today = Local::now().date_naive();
+    let start_of = |day: NaiveDate| {
+        day.and_hms_opt(0, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(Local).earliest())
+            .map(|dt| dt.timestamp_millis())
+            .unwrap_or(0)
+    };
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+    vec![
+        (start_of(today), "group_today"),
+        (start_of(today - Duration::days(1)), "group_yesterday"),
+        (start_of(week_start), "group_this_week"),
+        (start_of(week_start - Duration::days(7)), "group_last_week"),
+        (start_of(today.with_day(1).unwrap_or(today)), "group_this_month"),
+        (start_of(today.with_ordinal(1).unwrap_or(today)), "group_this_year"),
+    ]
+}
+
+/// Orders `entries` by group (keeping the existing sort inside each group), sets their
+/// `group_key` and returns the group headers in display order. The UI translates labels.
+fn group_entries(entries: &mut Vec<FileEntry>, group_by: &crate::models::session::GroupField) -> Vec<GroupKey> {
+    use crate::models::session::GroupField;
+
+    let date_bounds = if *group_by == GroupField::Date { date_group_bounds() } else { Vec::new() };
+    let label = |key: &str| GroupKey::Label(key.to_string());
+
+    // (rank, key): rank orders fixed buckets, the key orders free-form ones like extensions
+    let group_of = |e: &FileEntry| -> (usize, GroupKey) {
+        match group_by {
+            GroupField::Extension => {
+                if e.is_dir {
+                    return (0, label("group_folders"));
+                }
+                match std::path::Path::new(&e.name).extension() {
+                    Some(ext) => (1, GroupKey::Extension(ext.to_string_lossy().to_lowercase())),
+                    None => (2, label("group_no_extension")),
+                }
+            }
+            GroupField::Date => {
+                let modified = e.modified as i64;
+                date_bounds.iter()
+                    .position(|(start, _)| modified >= *start)
+                    .map(|i| (i, label(date_bounds[i].1)))
+                    .unwrap_or((date_bounds.len(), label("group_older")))
+            }
+            GroupField::Size => {
+                if e.is_dir {
+                    return (0, label("group_folders"));
+                }
+                match SIZE_GROUPS.iter().position(|(max, _)| e.size < *max) {
+                    Some(i) => (i + 1, label(SIZE_GROUPS[i].1)),
+                    None => (SIZE_GROUPS.len() + 1, label("group_size_gigantic")),
+                }
+            }
+        }
+    };
 
-        match config.direction {
-            SortDirection::Asc => cmp,
-            SortDirection::Desc => cmp.reverse(),
+    let mut keyed: Vec<((usize, GroupKey), FileEntry)> = std::mem::take(entries)
+        .into_iter()
+        .map(|e| (group_of(&e), e))
+        .collect();
+    // Stable sort, so entries keep the order from `sort_file_entries` within their group
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut groups: Vec<GroupKey> = Vec::new();
+    *entries = keyed.into_iter().map(|((_, key), mut entry)| {
+        if groups.last() != Some(&key) {
+            groups.push(key.clone());
         }
-    });
+        entry.group_key = Some(key);
+        entry
+    }).collect();
+    groups
 }
 
 fn calculate_summary(entries: &[FileEntry], parent_path: Option<String>) -> FileSummary {
@@ -366,6 +799,7 @@ pub fn get_file_properties(path: String) -> Result<FileProperties, CommandError>
             parent: "Voisinage Réseau".to_string(),
             is_dir: false,
             size: 0,
+            size_on_disk: None,
             is_calculated: true,
             created: 0,
             modified: 0,
@@ -510,6 +944,9 @@ pub fn get_file_properties(path: String) -> Result<FileProperties, CommandError>
 
     let is_dir = metadata.is_dir();
     let size = if is_dir { 0 } else { metadata.len() };
+    let size_on_disk = (!is_dir).then(|| {
+        crate::utils::hardware::size_on_disk(&path_buf, size, crate::utils::hardware::cluster_size(&path_buf))
+    });
 
     // Check if this is a trash item and populate trash metadata
     let (original_path, deleted_time) = if path.to_lowercase().contains("$recycle.bin") {
@@ -551,6 +988,7 @@ pub fn get_file_properties(path: String) -> Result<FileProperties, CommandError>
         parent,
         is_dir,
         size,
+        size_on_disk,
         is_calculated: false,
         created,
         modified,
@@ -656,21 +1094,76 @@ fn get_shortcut_info(_path: &std::path::Path) -> Option<crate::models::ShortcutI
     None
 }
 
+#[derive(Serialize, Clone)]
+pub struct FilesSummaryProgress {
+    pub op_id: String,
+    pub summary: FileSummary,
+    pub completed: bool,
+    pub cancelled: bool,
+}
+
+/// Totals for a selection. The walk runs off the async runtime; with an `op_id` it emits
+/// `files_summary_progress` with running counts and can be stopped through `cancel_folder_size`,
+/// in which case the partial totals are returned.
 #[tauri::command]
-pub async fn get_files_summary(paths: Vec<String>) -> Result<FileSummary, CommandError> {
-    let mut total_size = 0;
-    let mut files_count = 0;
-    let mut folders_count = 0;
-    let count = paths.len();
+pub async fn get_files_summary(
+    app: AppHandle,
+    state: State<'_, FolderSizeState>,
+    paths: Vec<String>,
+    op_id: Option<String>,
+    follow_links: Option<bool>,
+) -> Result<FileSummary, CommandError> {
+    let follow_links = follow_links.unwrap_or(false);
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    if let Some(id) = &op_id {
+        state.0.lock()
+            .map_err(|_| CommandError::SystemError("Failed to lock folder size state".to_string()))?
+            .insert(id.clone(), cancel_flag.clone());
+    }
 
-    let mut all_readonly = true;
-    let mut any_readonly = false;
-    let mut all_hidden = true;
-    let mut any_hidden = false;
-    let mut common_parent: Option<String> = None;
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let emit = |summary: &FileSummary, completed: bool, cancelled: bool| {
+            if let Some(id) = &task_op_id {
+                let _ = app.emit("files_summary_progress", FilesSummaryProgress {
+                    op_id: id.clone(),
+                    summary: summary.clone(),
+                    completed,
+                    cancelled,
+                });
+            }
+        };
+        let summary = summarize_paths(&paths, follow_links, &cancel_flag, |partial| emit(partial, false, false))?;
+        emit(&summary, !cancel_flag.load(Ordering::Relaxed), cancel_flag.load(Ordering::Relaxed));
+        Ok(summary)
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    if let Some(id) = &op_id {
+        if let Ok(mut tokens) = state.0.lock() {
+            tokens.remove(id);
+        }
+    }
+    result?
+}
+
+/// Walks the selection, calling `on_progress` every 200 ms. Stops early (keeping what was
+/// counted) once `cancel` is set. Reparse points are skipped unless `follow_links`.
+fn summarize_paths(paths: &[String], follow_links: bool, cancel: &AtomicBool, mut on_progress: impl FnMut(&FileSummary)) -> Result<FileSummary, CommandError> {
+    let mut summary = FileSummary {
+        count: paths.len(),
+        total_size: 0,
+        files_count: 0,
+        folders_count: 0,
+        all_readonly: false,
+        any_readonly: false,
+        all_hidden: false,
+        any_hidden: false,
+        parent_path: None,
+    };
     let mut different_parents = false;
+    let mut last_emit = std::time::Instant::now();
 
-    for (i, p) in paths.iter().enumerate() {
+    'paths: for (i, p) in paths.iter().enumerate() {
         let pb = PathBuf::from(p);
         let metadata = fs::metadata(&pb)?;
 
@@ -682,60 +1175,123 @@ pub async fn get_files_summary(paths: Vec<String>) -> Result<FileSummary, Comman
             .unwrap_or_default();
 
         if i == 0 {
-            all_readonly = readonly;
-            all_hidden = is_hidden;
-            common_parent = Some(parent);
+            summary.all_readonly = readonly;
+            summary.all_hidden = is_hidden;
+            summary.parent_path = Some(parent);
         } else {
-            all_readonly &= readonly;
-            all_hidden &= is_hidden;
-            if let Some(ref cp) = common_parent {
+            summary.all_readonly &= readonly;
+            summary.all_hidden &= is_hidden;
+            if let Some(ref cp) = summary.parent_path {
                 if cp != &parent {
                     different_parents = true;
                 }
             }
         }
-        any_readonly |= readonly;
-        any_hidden |= is_hidden;
+        summary.any_readonly |= readonly;
+        summary.any_hidden |= is_hidden;
 
         if pb.is_dir() {
-            folders_count += 1;
-            use walkdir::WalkDir;
-            for entry in WalkDir::new(&pb).into_iter().skip(1).filter_map(|e| e.ok()) {
+            summary.folders_count += 1;
+            for entry in crate::utils::walk_for_size(&pb, follow_links) {
+                if cancel.load(Ordering::Relaxed) {
+                    break 'paths;
+                }
                 if entry.file_type().is_file() {
-                    files_count += 1;
-                    total_size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    summary.files_count += 1;
+                    summary.total_size += entry.metadata().map(|m| m.len()).unwrap_or(0);
                 } else if entry.file_type().is_dir() {
-                    folders_count += 1;
+                    summary.folders_count += 1;
+                }
+                if last_emit.elapsed().as_millis() > 200 {
+                    on_progress(&summary);
+                    last_emit = std::time::Instant::now();
                 }
             }
         } else {
-            files_count += 1;
-            total_size += metadata.len();
+            summary.files_count += 1;
+            summary.total_size += metadata.len();
         }
     }
 
-    if count == 0 {
-        all_readonly = false;
-        all_hidden = false;
+    if different_parents {
+        summary.parent_path = None;
     }
+    Ok(summary)
+}
 
-    Ok(FileSummary {
-        count,
-        total_size,
-        files_count,
-        folders_count,
-        all_readonly,
-        any_readonly,
-        all_hidden,
-        any_hidden,
-        parent_path: if different_parents {
-            None
-        } else {
-            common_parent
-        },
-    })
+/// Bucket keys used by `summarize_selection_by_type` besides lowercased `.ext` ones.
+const FOLDERS_BUCKET: &str = "<folders>";
+const NO_EXTENSION_BUCKET: &str = "<no extension>";
+
+/// Counts and sizes of the selection grouped by lowercased extension, folders included (walked
+/// like `get_files_summary`). With an `op_id` it can be stopped through `cancel_folder_size`,
+/// in which case the partial totals are returned.
+#[tauri::command]
+pub async fn summarize_selection_by_type(
+    state: State<'_, FolderSizeState>,
+    paths: Vec<String>,
+    op_id: Option<String>,
+    follow_links: Option<bool>,
+) -> Result<std::collections::BTreeMap<String, TypeSummary>, CommandError> {
+    let follow_links = follow_links.unwrap_or(false);
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    if let Some(id) = &op_id {
+        state.0.lock()
+            .map_err(|_| CommandError::SystemError("Failed to lock folder size state".to_string()))?
+            .insert(id.clone(), cancel_flag.clone());
+    }
+
+    let task_cancel = cancel_flag.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || summarize_by_type(&paths, follow_links, &task_cancel))
+        .await
+        .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    if let Some(id) = &op_id {
+        if let Ok(mut tokens) = state.0.lock() {
+            tokens.remove(id);
+        }
+    }
+    result?
 }
 
+fn summarize_by_type(paths: &[String], follow_links: bool, cancel: &AtomicBool) -> Result<std::collections::BTreeMap<String, TypeSummary>, CommandError> {
+    let mut buckets: std::collections::BTreeMap<String, TypeSummary> = std::collections::BTreeMap::new();
+    let mut add_file = |path: &std::path::Path, size: u64| {
+        let key = path.extension()
+            .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()))
+            .unwrap_or_else(|| NO_EXTENSION_BUCKET.to_string());
+        let bucket = buckets.entry(key).or_default();
+        bucket.count += 1;
+        bucket.total_size += size;
+    };
+    let mut folders = 0;
+
+    'paths: for p in paths {
+        let pb = PathBuf::from(p);
+        let metadata = fs::metadata(&pb)?;
+        if !metadata.is_dir() {
+            add_file(&pb, metadata.len());
+            continue;
+        }
+
+        folders += 1;
+        for entry in crate::utils::walk_for_size(&pb, follow_links) {
+            if cancel.load(Ordering::Relaxed) {
+                break 'paths;
+            }
+            if entry.file_type().is_file() {
+                add_file(entry.path(), entry.metadata().map(|m| m.len()).unwrap_or(0));
+            } else if entry.file_type().is_dir() {
+                folders += 1;
+            }
+        }
+    }
+
+    if folders > 0 {
+        buckets.insert(FOLDERS_BUCKET.to_string(), TypeSummary { count: folders, total_size: 0 });
+    }
+    Ok(buckets)
+}
 
 #[tauri::command]
 pub async fn show_system_properties(path: String) -> Result<(), CommandError> {
@@ -746,7 +1302,7 @@ pub async fn show_system_properties(path: String) -> Result<(), CommandError> {
     };
     #[cfg(target_os = "windows")]
     {
-        use windows::core::{HSTRING, PCWSTR};
+        use windows::core::HSTRING;
         use windows::Win32::UI::Shell::{SHObjectProperties, SHOP_FILEPATH};
 
         let wide_path = HSTRING::from(&normalized_path);
@@ -767,8 +1323,10 @@ pub async fn show_system_properties(path: String) -> Result<(), CommandError> {
     Ok(())
 }
 
+/// `follow_links` walks into symlinks and junctions; by default they are skipped.
 #[tauri::command]
-pub async fn calculate_folder_size(path: String) -> Result<FolderSizeResult, CommandError> {
+pub async fn calculate_folder_size(path: String, follow_links: Option<bool>) -> Result<FolderSizeResult, CommandError> {
+    let follow_links = follow_links.unwrap_or(false);
     let pb = validate_path(&path)?;
     
     tauri::async_runtime::spawn_blocking(move || {
@@ -776,15 +1334,17 @@ pub async fn calculate_folder_size(path: String) -> Result<FolderSizeResult, Com
             return Err(CommandError::PathError("Path is not a directory".to_string()));
         }
 
+        let cluster = crate::utils::hardware::cluster_size(&pb);
         let mut size = 0;
+        let mut size_on_disk = 0;
         let mut folders_count = 0;
         let mut files_count = 0;
-        use walkdir::WalkDir;
-        // skip(1) to avoid counting the root folder itself
-        for entry in WalkDir::new(&pb).into_iter().skip(1).filter_map(|e| e.ok()) {
+        for entry in crate::utils::walk_for_size(&pb, follow_links) {
             if entry.file_type().is_file() {
                 files_count += 1;
-                size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                size += len;
+                size_on_disk += crate::utils::hardware::size_on_disk(entry.path(), len, cluster);
             } else if entry.file_type().is_dir() {
                 folders_count += 1;
             }
@@ -792,50 +1352,357 @@ pub async fn calculate_folder_size(path: String) -> Result<FolderSizeResult, Com
 
         Ok(FolderSizeResult {
             size,
+            size_on_disk,
             folders_count,
             files_count,
         })
     }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
 }
 
-#[tauri::command]
-pub async fn set_shortcut_info(path: String, info: crate::models::ShortcutInfo) -> Result<(), CommandError> {
-    #[cfg(target_os = "windows")]
-    {
-        use windows::core::{Interface, PCWSTR, HSTRING};
-        use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER, CoInitializeEx, COINIT_APARTMENTTHREADED, CoUninitialize, IPersistFile, STGM_READWRITE};
-        use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
-        use std::path::PathBuf;
+#[derive(Serialize, Clone)]
+pub struct FolderSizeProgress {
+    pub op_id: String,
+    pub result: FolderSizeResult,
+    pub completed: bool,
+    pub cancelled: bool,
+}
 
-        let path_buf = PathBuf::from(&path);
-        
-        unsafe {
-            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
-            
-            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
-                .map_err(|e| CommandError::SystemError(format!("CoCreateInstance failed: {}", e)))?;
+/// Cancellation tokens of running `calculate_folder_size_live` and `get_files_summary` walks, keyed by op id.
+#[derive(Default)]
+pub struct FolderSizeState(pub std::sync::Mutex<std::collections::HashMap<String, Arc<AtomicBool>>>);
 
-            let persist: IPersistFile = link.cast()
-                .map_err(|e| CommandError::SystemError(format!("Cast to IPersistFile failed: {}", e)))?;
+/// Streaming variant of `calculate_folder_size`: emits `folder_size_progress` with running totals
+/// while walking, and a final event (completed or cancelled) carrying the full result.
+#[tauri::command]
+pub async fn calculate_folder_size_live(
+    app: AppHandle,
+    state: State<'_, FolderSizeState>,
+    path: String,
+    op_id: String,
+    follow_links: Option<bool>,
+) -> Result<FolderSizeResult, CommandError> {
+    let follow_links = follow_links.unwrap_or(false);
+    let pb = validate_path(&path)?;
+    if !pb.is_dir() {
+        return Err(CommandError::PathError("Path is not a directory".to_string()));
+    }
 
-            let wide_path = HSTRING::from(path_buf.to_string_lossy().as_ref());
-            persist.Load(PCWSTR(wide_path.as_ptr()), STGM_READWRITE)
-                .map_err(|e| CommandError::SystemError(format!("Load failed: {}", e)))?;
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    state.0.lock()
+        .map_err(|_| CommandError::SystemError("Failed to lock folder size state".to_string()))?
+        .insert(op_id.clone(), cancel_flag.clone());
+
+    let task_app = app.clone();
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let cluster = crate::utils::hardware::cluster_size(&pb);
+        let mut totals = FolderSizeResult { size: 0, size_on_disk: 0, folders_count: 0, files_count: 0 };
+        let mut last_emit = std::time::Instant::now();
+        let emit = |totals: &FolderSizeResult, completed: bool, cancelled: bool| {
+            let _ = task_app.emit("folder_size_progress", FolderSizeProgress {
+                op_id: task_op_id.clone(),
+                result: totals.clone(),
+                completed,
+                cancelled,
+            });
+        };
 
-            let wide_target = HSTRING::from(info.target);
-            link.SetPath(PCWSTR(wide_target.as_ptr()))
-                .map_err(|e| CommandError::SystemError(format!("SetPath failed: {}", e)))?;
+        for entry in crate::utils::walk_for_size(&pb, follow_links) {
+            if cancel_flag.load(Ordering::Relaxed) {
+                emit(&totals, false, true);
+                return totals;
+            }
+            if entry.file_type().is_file() {
+                totals.files_count += 1;
+                let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                totals.size += len;
+                totals.size_on_disk += crate::utils::hardware::size_on_disk(entry.path(), len, cluster);
+            } else if entry.file_type().is_dir() {
+                totals.folders_count += 1;
+            }
+            if last_emit.elapsed().as_millis() > 200 {
+                emit(&totals, false, false);
+                last_emit = std::time::Instant::now();
+            }
+        }
 
-            let wide_args = HSTRING::from(info.arguments);
-            link.SetArguments(PCWSTR(wide_args.as_ptr()))
-                .map_err(|e| CommandError::SystemError(format!("SetArguments failed: {}", e)))?;
+        emit(&totals, true, false);
+        totals
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
 
-            let wide_dir = HSTRING::from(info.working_dir);
-            link.SetWorkingDirectory(PCWSTR(wide_dir.as_ptr()))
-                .map_err(|e| CommandError::SystemError(format!("SetWorkingDirectory failed: {}", e)))?;
+    if let Ok(mut tokens) = state.0.lock() {
+        tokens.remove(&op_id);
+    }
+    result
+}
 
-            let wide_desc = HSTRING::from(info.description);
-            link.SetDescription(PCWSTR(wide_desc.as_ptr()))
+#[tauri::command]
+pub fn cancel_folder_size(state: State<'_, FolderSizeState>, op_id: String) {
+    if let Ok(tokens) = state.0.lock() {
+        if let Some(flag) = tokens.get(&op_id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Run token of the current `index_folder_counts` pass, plus the counts already known, keyed by
+/// folder path with the folder's modification time they were computed for.
+#[derive(Default)]
+pub struct FolderCountState {
+    current: std::sync::Mutex<Option<Arc<AtomicBool>>>,
+    cache: std::sync::Mutex<std::collections::HashMap<String, (SystemTime, usize)>>,
+    /// `count_dir_entries` results, keyed by folder path and whether hidden items were included
+    entry_counts: std::sync::Mutex<std::collections::HashMap<(String, bool), (SystemTime, DirEntryCounts)>>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+pub struct DirEntryCounts {
+    pub files: usize,
+    pub folders: usize,
+}
+
+#[derive(Clone, Serialize)]
+pub struct FolderCountsEvent {
+    /// The listed directory the counts belong to
+    pub path: String,
+    pub counts: std::collections::HashMap<String, usize>,
+    pub completed: bool,
+}
+
+/// Counts the immediate children of every subfolder of `path` on a low-priority thread and emits
+/// them as `folder_counts` events, in batches. Returns right away; starting a new pass (or calling
+/// `cancel_folder_counts`) stops the previous one. Counts include hidden and system items.
+#[tauri::command]
+pub fn index_folder_counts(app: AppHandle, state: State<'_, FolderCountState>, path: String) -> Result<(), CommandError> {
+    let pb = validate_path(&path)?;
+    if !pb.is_dir() {
+        return Err(CommandError::PathError("Path is not a directory".to_string()));
+    }
+
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    if let Some(previous) = state.current.lock()
+        .map_err(|_| CommandError::SystemError("Failed to lock folder count state".to_string()))?
+        .replace(cancel_flag.clone())
+    {
+        previous.store(true, Ordering::Relaxed);
+    }
+
+    std::thread::spawn(move || {
+        #[cfg(target_os = "windows")]
+        unsafe {
+            use windows::Win32::System::Threading::{GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN};
+            let _ = SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN);
+        }
+
+        let state = app.state::<FolderCountState>();
+        let subfolders: Vec<PathBuf> = match fs::read_dir(&pb) {
+            Ok(entries) => entries.filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .map(|e| e.path())
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+
+        let mut batch = std::collections::HashMap::new();
+        let mut last_emit = std::time::Instant::now();
+        let emit = |counts: std::collections::HashMap<String, usize>, completed: bool| {
+            let _ = app.emit("folder_counts", FolderCountsEvent { path: path.clone(), counts, completed });
+        };
+
+        for folder in subfolders {
+            if cancel_flag.load(Ordering::Relaxed) {
+                return;
+            }
+            let key = folder.to_string_lossy().to_string();
+            let Some(modified) = fs::metadata(&folder).and_then(|m| m.modified()).ok() else { continue };
+            let cached = state.cache.lock().ok()
+                .and_then(|cache| cache.get(&key).filter(|(at, _)| *at == modified).map(|(_, count)| *count));
+            let count = match cached {
+                Some(count) => count,
+                // Unreadable folders (access denied...) get no badge
+                None => match fs::read_dir(&folder) {
+                    Ok(entries) => {
+                        let count = entries.count();
+                        if let Ok(mut cache) = state.cache.lock() {
+                            cache.insert(key.clone(), (modified, count));
+                        }
+                        count
+                    }
+                    Err(_) => continue,
+                },
+            };
+            batch.insert(key, count);
+
+            if last_emit.elapsed().as_millis() > 200 {
+                emit(std::mem::take(&mut batch), false);
+                last_emit = std::time::Instant::now();
+            }
+        }
+
+        if !cancel_flag.load(Ordering::Relaxed) {
+            emit(batch, true);
+        }
+        if let Ok(mut current) = state.current.lock() {
+            if current.as_ref().is_some_and(|flag| Arc::ptr_eq(flag, &cancel_flag)) {
+                *current = None;
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_folder_counts(state: State<'_, FolderCountState>) {
+    if let Ok(mut current) = state.current.lock() {
+        if let Some(flag) = current.take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Number of files and folders directly in `path`, for badges and tooltips. Only names and file
+/// types are read (plus attributes when hidden and system items must be left out), so this is
+/// far cheaper than `list_dir`. Cached until the folder's modification time changes.
+#[tauri::command]
+pub async fn count_dir_entries(
+    state: State<'_, FolderCountState>,
+    path: String,
+    include_hidden: bool,
+) -> Result<DirEntryCounts, CommandError> {
+    let pb = validate_path(&path)?;
+    let modified = fs::metadata(&pb)?.modified()?;
+    let key = (pb.to_string_lossy().to_string(), include_hidden);
+    if let Some((_, counts)) = state.entry_counts.lock().ok()
+        .and_then(|cache| cache.get(&key).filter(|(at, _)| *at == modified).copied())
+    {
+        return Ok(counts);
+    }
+
+    let counts = tauri::async_runtime::spawn_blocking(move || {
+        let mut counts = DirEntryCounts { files: 0, folders: 0 };
+        for entry in fs::read_dir(&pb)?.flatten() {
+            if !include_hidden {
+                let Ok(metadata) = entry.metadata() else { continue };
+                let (is_hidden, is_system, _) = crate::utils::get_file_attributes(&metadata, &entry.file_name().to_string_lossy());
+                if is_hidden || is_system { continue; }
+            }
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                counts.folders += 1;
+            } else {
+                counts.files += 1;
+            }
+        }
+        Ok::<_, CommandError>(counts)
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))??;
+
+    if let Ok(mut cache) = state.entry_counts.lock() {
+        cache.insert(key, (modified, counts));
+    }
+    Ok(counts)
+}
+
+#[derive(Serialize)]
+pub struct FileTypeInfo {
+    /// Detected format ("png", "zip", "exe", ...), None if the content wasn't recognised
+    pub kind: Option<String>,
+    pub mime: Option<String>,
+    pub extension: String,
+    /// True when the content was recognised and the extension isn't one it normally has
+    pub extension_mismatch: bool,
+    pub is_executable: bool,
+}
+
+/// Identifies a file by its magic bytes rather than its name.
+#[tauri::command]
+pub async fn detect_file_type(path: String) -> Result<FileTypeInfo, CommandError> {
+    let pb = validate_path(&path)?;
+    let extension = pb.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let signature = crate::utils::file_type::detect_file(&pb).map_err(|e| CommandError::IoError(e.to_string()))?;
+        Ok(FileTypeInfo {
+            kind: signature.map(|s| s.kind.to_string()),
+            mime: signature.map(|s| s.mime.to_string()),
+            extension_mismatch: signature.is_some_and(|s| !s.matches_extension(&extension)),
+            is_executable: signature.is_some_and(|s| matches!(s.kind, "exe" | "elf" | "msi")),
+            extension,
+        })
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+#[derive(Serialize)]
+pub struct PathProbe {
+    pub exists: bool,
+    pub is_dir: bool,
+    pub is_file: bool,
+    pub is_archive: bool,
+    pub is_drive_root: bool,
+    pub is_virtual_archive_path: bool,
+}
+
+/// Describes what a typed path points to so the address bar can decide how to navigate.
+#[tauri::command]
+pub fn probe_path(path: String) -> Result<PathProbe, CommandError> {
+    use crate::utils::archive::{is_archive, split_virtual_path};
+
+    let pb = validate_path(&crate::utils::path_security::normalize_drive_root(path.trim()))?;
+    let metadata = fs::metadata(&pb).ok();
+    let is_drive_root = pb.parent().is_none();
+
+    // Inside an archive: exists as far as the archive file does, the entry itself isn't checked
+    let virtual_archive = match split_virtual_path(&pb.to_string_lossy()) {
+        Some((archive_path, internal_path)) if !internal_path.is_empty() => Some(archive_path),
+        _ => None,
+    };
+
+    Ok(PathProbe {
+        exists: metadata.is_some() || virtual_archive.as_ref().map(|a| a.is_file()).unwrap_or(false),
+        is_dir: metadata.as_ref().map(|m| m.is_dir()).unwrap_or(false),
+        is_file: metadata.as_ref().map(|m| m.is_file()).unwrap_or(false),
+        is_archive: metadata.as_ref().map(|m| m.is_file()).unwrap_or(false) && is_archive(&pb),
+        is_drive_root,
+        is_virtual_archive_path: virtual_archive.is_some(),
+    })
+}
+
+#[tauri::command]
+pub async fn set_shortcut_info(path: String, info: crate::models::ShortcutInfo) -> Result<(), CommandError> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::{Interface, PCWSTR, HSTRING};
+        use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER, CoInitializeEx, COINIT_APARTMENTTHREADED, CoUninitialize, IPersistFile, STGM_READWRITE};
+        use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+        use std::path::PathBuf;
+
+        let path_buf = PathBuf::from(&path);
+        
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+                .map_err(|e| CommandError::SystemError(format!("CoCreateInstance failed: {}", e)))?;
+
+            let persist: IPersistFile = link.cast()
+                .map_err(|e| CommandError::SystemError(format!("Cast to IPersistFile failed: {}", e)))?;
+
+            let wide_path = HSTRING::from(path_buf.to_string_lossy().as_ref());
+            persist.Load(PCWSTR(wide_path.as_ptr()), STGM_READWRITE)
+                .map_err(|e| CommandError::SystemError(format!("Load failed: {}", e)))?;
+
+            let wide_target = HSTRING::from(info.target);
+            link.SetPath(PCWSTR(wide_target.as_ptr()))
+                .map_err(|e| CommandError::SystemError(format!("SetPath failed: {}", e)))?;
+
+            let wide_args = HSTRING::from(info.arguments);
+            link.SetArguments(PCWSTR(wide_args.as_ptr()))
+                .map_err(|e| CommandError::SystemError(format!("SetArguments failed: {}", e)))?;
+
+            let wide_dir = HSTRING::from(info.working_dir);
+            link.SetWorkingDirectory(PCWSTR(wide_dir.as_ptr()))
+                .map_err(|e| CommandError::SystemError(format!("SetWorkingDirectory failed: {}", e)))?;
+
+            let wide_desc = HSTRING::from(info.description);
+            link.SetDescription(PCWSTR(wide_desc.as_ptr()))
                 .map_err(|e| CommandError::SystemError(format!("SetDescription failed: {}", e)))?;
 
             link.SetShowCmd(windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD(info.run_window))
@@ -849,3 +1716,1164 @@ pub async fn set_shortcut_info(path: String, info: crate::models::ShortcutInfo)
     }
     Ok(())
 }
+
+/// Creates a `.lnk` pointing at `target` (the extension is added when missing) and records it
+/// in the history so it can be undone. Fails if the shortcut file already exists.
+#[tauri::command]
+pub async fn create_shortcut(
+    app: AppHandle,
+    target: String,
+    shortcut_path: String,
+    options: Option<crate::models::ShortcutOptions>,
+) -> Result<String, CommandError> {
+    let target_pb = validate_path(&target)?;
+    if !target_pb.exists() {
+        return Err(CommandError::PathError(format!("Shortcut target not found: {}", target_pb.display())));
+    }
+    let mut shortcut_pb = validate_path(&shortcut_path)?;
+    if !shortcut_pb.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lnk")) {
+        let mut name = shortcut_pb.file_name().unwrap_or_default().to_os_string();
+        name.push(".lnk");
+        shortcut_pb.set_file_name(name);
+    }
+    if shortcut_pb.exists() {
+        return Err(CommandError::PathError(format!("A file named {} already exists", shortcut_pb.display())));
+    }
+    write_shortcut(&target_pb, &shortcut_pb, &options.unwrap_or_default())?;
+
+    let shortcut_abs = shortcut_pb.to_string_lossy().to_string();
+    info!("Created shortcut {:?} -> {:?}", shortcut_pb, target_pb);
+    let tx_details = TransactionDetails {
+        paths: vec![shortcut_abs.clone()],
+        target_dir: None,
+        old_path: None,
+        new_path: None,
+        created_files: None,
+    };
+    let history = app.state::<HistoryManager>();
+    history.push(Transaction::new(TransactionType::NewFile, tx_details));
+
+    Ok(shortcut_abs)
+}
+
+#[cfg(target_os = "windows")]
+fn write_shortcut(target: &std::path::Path, shortcut: &std::path::Path, options: &crate::models::ShortcutOptions) -> Result<(), CommandError> {
+    use windows::core::{Interface, PCWSTR, HSTRING};
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER, CoInitializeEx, COINIT_APARTMENTTHREADED, CoUninitialize, IPersistFile};
+    use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+    use windows::Win32::UI::WindowsAndMessaging::{SHOW_WINDOW_CMD, SW_SHOWNORMAL};
+
+    let working_dir = options.working_dir.clone().unwrap_or_else(|| {
+        let dir = if target.is_dir() { Some(target) } else { target.parent() };
+        dir.map(|d| d.to_string_lossy().to_string()).unwrap_or_default()
+    });
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| CommandError::SystemError(format!("CoCreateInstance failed: {}", e)))?;
+
+        let wide_target = HSTRING::from(target.as_os_str());
+        link.SetPath(PCWSTR(wide_target.as_ptr()))
+            .map_err(|e| CommandError::SystemError(format!("SetPath failed: {}", e)))?;
+
+        let wide_args = HSTRING::from(options.arguments.as_str());
+        link.SetArguments(PCWSTR(wide_args.as_ptr()))
+            .map_err(|e| CommandError::SystemError(format!("SetArguments failed: {}", e)))?;
+
+        let wide_dir = HSTRING::from(working_dir);
+        link.SetWorkingDirectory(PCWSTR(wide_dir.as_ptr()))
+            .map_err(|e| CommandError::SystemError(format!("SetWorkingDirectory failed: {}", e)))?;
+
+        let wide_desc = HSTRING::from(options.description.as_str());
+        link.SetDescription(PCWSTR(wide_desc.as_ptr()))
+            .map_err(|e| CommandError::SystemError(format!("SetDescription failed: {}", e)))?;
+
+        if !options.icon_location.is_empty() {
+            let wide_icon = HSTRING::from(options.icon_location.as_str());
+            link.SetIconLocation(PCWSTR(wide_icon.as_ptr()), options.icon_index)
+                .map_err(|e| CommandError::SystemError(format!("SetIconLocation failed: {}", e)))?;
+        }
+
+        link.SetShowCmd(SHOW_WINDOW_CMD(options.run_window.unwrap_or(SW_SHOWNORMAL.0)))
+            .map_err(|e| CommandError::SystemError(format!("SetShowCmd failed: {}", e)))?;
+
+        let persist: IPersistFile = link.cast()
+            .map_err(|e| CommandError::SystemError(format!("Cast to IPersistFile failed: {}", e)))?;
+
+        let wide_path = HSTRING::from(shortcut.as_os_str());
+        persist.Save(PCWSTR(wide_path.as_ptr()), true)
+            .map_err(|e| CommandError::SystemError(format!("Save failed: {}", e)))?;
+
+        let _ = CoUninitialize();
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn write_shortcut(_target: &std::path::Path, _shortcut: &std::path::Path, _options: &crate::models::ShortcutOptions) -> Result<(), CommandError> {
+    Err(CommandError::SystemError("Shortcuts are only supported on Windows".to_string()))
+}
+
+/// Lines in a `get_tree_listing` output before it is cut short.
+const MAX_TREE_LINES: usize = 10_000;
+
+/// ASCII tree (├── / └──) of `path` down to `max_depth` levels (3 by default), folders first,
+/// for pasting into docs or bug reports. Output stops after `MAX_TREE_LINES` lines.
+#[tauri::command]
+pub async fn get_tree_listing(path: String, max_depth: Option<usize>, include_files: Option<bool>) -> Result<String, CommandError> {
+    let root = validate_path(&path)?;
+    if !root.is_dir() {
+        return Err(CommandError::PathError(format!("Not a folder: {}", root.display())));
+    }
+    let max_depth = max_depth.unwrap_or(3).max(1);
+    let include_files = include_files.unwrap_or(true);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut walker = walkdir::WalkDir::new(&root)
+            .max_depth(max_depth)
+            .sort_by(|a, b| {
+                b.file_type().is_dir().cmp(&a.file_type().is_dir())
+                    .then_with(|| crate::utils::compare_natural(&a.file_name().to_string_lossy(), &b.file_name().to_string_lossy()))
+            })
+            .into_iter();
+
+        let mut entries = Vec::new();
+        let mut truncated = false;
+        while let Some(entry) = walker.next() {
+            let Ok(entry) = entry else { continue; };
+            if entry.depth() == 0 {
+                continue;
+            }
+            let is_link = crate::utils::is_reparse_point(&entry);
+            let is_dir = entry.file_type().is_dir() || (is_link && entry.path().is_dir());
+            if !is_dir && !include_files {
+                continue;
+            }
+            if entries.len() == MAX_TREE_LINES {
+                truncated = true;
+                break;
+            }
+            // Links and junctions are listed but not entered, so the walk can't loop
+            if is_link && entry.file_type().is_dir() {
+                walker.skip_current_dir();
+            }
+            entries.push((entry.depth(), entry.file_name().to_string_lossy().to_string()));
+        }
+
+        let root_name = root.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| root.to_string_lossy().to_string());
+        let mut tree = crate::utils::render_tree(&root_name, &entries);
+        if truncated {
+            tree.push_str(&format!("\n... (cut at {} lines)", MAX_TREE_LINES));
+        }
+        Ok(tree)
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+/// Target URL of an Internet shortcut (`.url` file).
+#[tauri::command]
+pub fn get_url_shortcut(path: String) -> Result<String, CommandError> {
+    let path = validate_path(&path)?;
+    read_url_shortcut(&path)
+}
+
+pub(crate) fn read_url_shortcut(path: &std::path::Path) -> Result<String, CommandError> {
+    // Usually ANSI, sometimes UTF-8: a lossy read is enough for the ASCII keys we look for
+    let bytes = fs::read(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    crate::utils::parse_url_shortcut(&String::from_utf8_lossy(&bytes))
+        .ok_or_else(|| CommandError::Other(format!("No URL found in {}", path.display())))
+}
+
+/// Applies the requested attribute changes to a single path and returns the resulting state.
+pub(crate) fn apply_file_attributes(path: &std::path::Path, changes: &FileAttributeChanges) -> Result<FileAttributes, CommandError> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::MetadataExt;
+        use windows::core::HSTRING;
+        use windows::Win32::Storage::FileSystem::{
+            SetFileAttributesW, FILE_FLAGS_AND_ATTRIBUTES, FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_HIDDEN,
+            FILE_ATTRIBUTE_NORMAL, FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_SYSTEM, FILE_ATTRIBUTE_DIRECTORY,
+        };
+
+        let current = fs::symlink_metadata(path)?.file_attributes();
+        let mut attrs = current & !FILE_ATTRIBUTE_DIRECTORY.0;
+        for (flag, wanted) in [
+            (FILE_ATTRIBUTE_READONLY.0, changes.read_only),
+            (FILE_ATTRIBUTE_HIDDEN.0, changes.hidden),
+            (FILE_ATTRIBUTE_SYSTEM.0, changes.system),
+            (FILE_ATTRIBUTE_ARCHIVE.0, changes.archive),
+        ] {
+            match wanted {
+                Some(true) => attrs |= flag,
+                Some(false) => attrs &= !flag,
+                None => {}
+            }
+        }
+        // FILE_ATTRIBUTE_NORMAL is only valid on its own
+        attrs &= !FILE_ATTRIBUTE_NORMAL.0;
+        let to_set = if attrs == 0 { FILE_ATTRIBUTE_NORMAL.0 } else { attrs };
+
+        if to_set != (current & !FILE_ATTRIBUTE_DIRECTORY.0) {
+            let wide_path = HSTRING::from(path.as_os_str());
+            unsafe {
+                SetFileAttributesW(PCWSTR(wide_path.as_ptr()), FILE_FLAGS_AND_ATTRIBUTES(to_set))
+                    .map_err(|e| CommandError::SystemError(format!("SetFileAttributesW failed for {}: {}", path.display(), e)))?;
+            }
+        }
+
+        let result = fs::symlink_metadata(path)?.file_attributes();
+        Ok(FileAttributes {
+            read_only: result & FILE_ATTRIBUTE_READONLY.0 != 0,
+            hidden: result & FILE_ATTRIBUTE_HIDDEN.0 != 0,
+            system: result & FILE_ATTRIBUTE_SYSTEM.0 != 0,
+            archive: result & FILE_ATTRIBUTE_ARCHIVE.0 != 0,
+        })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Only the read-only flag maps onto Unix permissions; hidden follows the dot-file convention
+        if let Some(read_only) = changes.read_only {
+            let mut perms = fs::metadata(path)?.permissions();
+            let mode = perms.mode();
+            perms.set_mode(if read_only { mode & !0o222 } else { mode | 0o200 });
+            fs::set_permissions(path, perms)?;
+        }
+
+        let metadata = fs::metadata(path)?;
+        Ok(FileAttributes {
+            read_only: metadata.permissions().readonly(),
+            hidden: path.file_name().map(|n| n.to_string_lossy().starts_with('.')).unwrap_or(false),
+            system: false,
+            archive: false,
+        })
+    }
+}
+
+#[tauri::command]
+pub async fn set_file_attributes(path: String, attributes: FileAttributeChanges) -> Result<FileAttributes, CommandError> {
+    let pb = validate_path(&path)?;
+    info!("Setting attributes on {:?}: {:?}", pb, attributes);
+    apply_file_attributes(&pb, &attributes)
+}
+
+/// Cancellation tokens of running bulk attribute operations (attributes, compression, ownership,
+/// permissions), keyed by op id, so `cancel_set_attributes` only stops the one it names.
+#[derive(Default)]
+pub struct AttributeOpState(pub std::sync::Mutex<std::collections::HashMap<String, Arc<AtomicBool>>>);
+
+impl AttributeOpState {
+    /// Registers `op_id` and returns its cancel token.
+    fn start(&self, op_id: &str) -> Result<Arc<AtomicBool>, CommandError> {
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        self.0.lock()
+            .map_err(|_| CommandError::SystemError("Failed to lock attribute op state".to_string()))?
+            .insert(op_id.to_string(), cancel_flag.clone());
+        Ok(cancel_flag)
+    }
+
+    fn finish(&self, op_id: &str) {
+        if let Ok(mut tokens) = self.0.lock() {
+            tokens.remove(op_id);
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct AttributeFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BulkAttributeResult {
+    pub processed: u64,
+    pub failures: Vec<AttributeFailure>,
+    pub cancelled: bool,
+}
+
+#[tauri::command]
+pub async fn set_attributes_recursive(
+    app: AppHandle,
+    state: State<'_, AttributeOpState>,
+    paths: Vec<String>,
+    attributes: FileAttributeChanges,
+    recursive: bool,
+    op_id: String,
+) -> Result<BulkAttributeResult, CommandError> {
+    let roots = paths.iter().map(|p| validate_path(p)).collect::<Result<Vec<PathBuf>, CommandError>>()?;
+    let cancel_flag = state.start(&op_id)?;
+
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut failures = Vec::new();
+
+        // Collect targets first so progress has a meaningful total
+        let mut targets: Vec<PathBuf> = Vec::new();
+        for root in roots {
+            if recursive && root.is_dir() {
+                use walkdir::WalkDir;
+                for entry in WalkDir::new(&root) {
+                    if cancel_flag.load(Ordering::Relaxed) { break; }
+                    match entry {
+                        Ok(e) => targets.push(e.into_path()),
+                        Err(e) => failures.push(AttributeFailure {
+                            path: e.path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+                            error: e.to_string(),
+                        }),
+                    }
+                }
+            } else {
+                targets.push(root);
+            }
+        }
+
+        let total = targets.len() as u64;
+        let emit_progress = |current: u64, status: &str, filename: Option<String>| {
+            let _ = app.emit("progress", ProgressEvent {
+                id: task_op_id.clone(),
+                task: "attributes".to_string(),
+                current,
+                total,
+                status: status.to_string(),
+                filename,
+            });
+        };
+
+        let mut processed = 0u64;
+        let mut last_emit = std::time::Instant::now();
+        for target in &targets {
+            if cancel_flag.load(Ordering::Relaxed) { break; }
+            if let Err(e) = apply_file_attributes(target, &attributes) {
+                failures.push(AttributeFailure {
+                    path: target.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                });
+            }
+            processed += 1;
+            if last_emit.elapsed().as_millis() > 100 {
+                emit_progress(processed, "running", target.file_name().map(|n| n.to_string_lossy().to_string()));
+                last_emit = std::time::Instant::now();
+            }
+        }
+
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        emit_progress(processed, if cancelled { "cancelled" } else { "completed" }, None);
+        info!("Attributes applied to {} item(s), {} failure(s)", processed, failures.len());
+
+        Ok(BulkAttributeResult { processed, failures, cancelled })
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    state.finish(&op_id);
+    result?
+}
+
+/// Stops the bulk attribute operation started with `op_id`.
+#[tauri::command]
+pub fn cancel_set_attributes(state: State<'_, AttributeOpState>, op_id: String) {
+    if let Ok(tokens) = state.0.lock() {
+        if let Some(flag) = tokens.get(&op_id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CompressionResult {
+    /// Compression state of `path` itself once done
+    pub compressed: bool,
+    pub processed: u64,
+    pub failures: Vec<AttributeFailure>,
+    pub cancelled: bool,
+}
+
+/// Turns NTFS transparent compression on or off for `path`. On a folder this also decides whether
+/// files created in it later get compressed; `recursive` applies it to everything already inside.
+/// Cancelled with `cancel_set_attributes` and the same `op_id`.
+#[tauri::command]
+pub async fn set_ntfs_compression(
+    app: AppHandle,
+    state: State<'_, AttributeOpState>,
+    path: String,
+    compressed: bool,
+    recursive: bool,
+    op_id: String,
+) -> Result<CompressionResult, CommandError> {
+    let root = validate_path(&path)?;
+    if !root.exists() {
+        return Err(CommandError::PathError(format!("{} does not exist", root.display())));
+    }
+    if !crate::utils::hardware::supports_compression(&root) {
+        return Err(CommandError::Other("This drive doesn't support compression, only NTFS volumes do".to_string()));
+    }
+    let cancel_flag = state.start(&op_id)?;
+
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut failures = Vec::new();
+
+        let mut targets: Vec<PathBuf> = Vec::new();
+        if recursive && root.is_dir() {
+            use walkdir::WalkDir;
+            // Contents first, the folder itself last, as Explorer does. Links and junctions are
+            // left out: their target may be outside the selection
+            let walker = WalkDir::new(&root)
+                .contents_first(true)
+                .into_iter()
+                .filter_entry(|e| e.depth() == 0 || !crate::utils::is_reparse_point(e));
+            for entry in walker {
+                if cancel_flag.load(Ordering::Relaxed) { break; }
+                match entry {
+                    Ok(e) => targets.push(e.into_path()),
+                    Err(e) => failures.push(AttributeFailure {
+                        path: e.path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        } else {
+            targets.push(root.clone());
+        }
+
+        let total = targets.len() as u64;
+        let emit_progress = |current: u64, status: &str, filename: Option<String>| {
+            let _ = app.emit("progress", ProgressEvent {
+                id: task_op_id.clone(),
+                task: "compression".to_string(),
+                current,
+                total,
+                status: status.to_string(),
+                filename,
+            });
+        };
+
+        let mut processed = 0u64;
+        let mut last_emit = std::time::Instant::now();
+        for target in &targets {
+            if cancel_flag.load(Ordering::Relaxed) { break; }
+            if let Err(e) = apply_ntfs_compression(target, compressed) {
+                failures.push(AttributeFailure {
+                    path: target.to_string_lossy().to_string(),
+                    error: e.to_string(),
+                });
+            }
+            processed += 1;
+            if last_emit.elapsed().as_millis() > 100 {
+                emit_progress(processed, "running", target.file_name().map(|n| n.to_string_lossy().to_string()));
+                last_emit = std::time::Instant::now();
+            }
+        }
+
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        emit_progress(processed, if cancelled { "cancelled" } else { "completed" }, None);
+        info!("Compression {} on {} item(s), {} failure(s)", if compressed { "enabled" } else { "disabled" }, processed, failures.len());
+
+        Ok(CompressionResult { compressed: is_compressed(&root), processed, failures, cancelled })
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    state.finish(&op_id);
+    result?
+}
+
+fn is_compressed(path: &std::path::Path) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::fs::MetadataExt;
+        use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_COMPRESSED;
+        fs::symlink_metadata(path).is_ok_and(|m| m.file_attributes() & FILE_ATTRIBUTE_COMPRESSED.0 != 0)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Owner account of a file or folder, as "DOMAIN\user" (or just the name for well-known accounts).
+#[tauri::command]
+pub async fn get_file_owner(path: String) -> Result<String, CommandError> {
+    let pb = validate_path(&path)?;
+    tauri::async_runtime::spawn_blocking(move || file_owner(&pb))
+        .await
+        .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+/// Makes the current user the owner of `path` (and everything below it with `recursive`), so
+/// files left behind by another profile become accessible again. Needs SeTakeOwnershipPrivilege,
+/// i.e. an elevated process; items that can't be taken over are reported in `failures`.
+/// Cancelled with `cancel_set_attributes` and the same `op_id`.
+#[tauri::command]
+pub async fn take_ownership(
+    app: AppHandle,
+    state: State<'_, AttributeOpState>,
+    path: String,
+    recursive: bool,
+    op_id: String,
+) -> Result<BulkAttributeResult, CommandError> {
+    let root = validate_path(&path)?;
+    if !root.exists() {
+        return Err(CommandError::PathError(format!("{} does not exist", root.display())));
+    }
+    let cancel_flag = state.start(&op_id)?;
+
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let owner = OwnershipContext::acquire()?;
+        let mut failures = Vec::new();
+
+        // Each folder is taken over, and opened up if needed, before descending: otherwise it may
+        // not even be listable
+        let mut targets: Vec<PathBuf> = Vec::new();
+        if recursive && root.is_dir() {
+            use walkdir::WalkDir;
+            let mut walker = WalkDir::new(&root).into_iter();
+            while let Some(entry) = walker.next() {
+                if cancel_flag.load(Ordering::Relaxed) { break; }
+                match entry {
+                    Ok(e) => {
+                        if e.file_type().is_dir() {
+                            if let Err(err) = owner.apply(e.path()).and_then(|_| owner.ensure_readable(e.path())) {
+                                failures.push(AttributeFailure { path: e.path().to_string_lossy().to_string(), error: err.to_string() });
+                                walker.skip_current_dir();
+                                continue;
+                            }
+                        }
+                        targets.push(e.into_path());
+                    }
+                    Err(e) => failures.push(AttributeFailure {
+                        path: e.path().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
+                        error: e.to_string(),
+                    }),
+                }
+            }
+        } else {
+            targets.push(root);
+        }
+
+        let total = targets.len() as u64;
+        let emit_progress = |current: u64, status: &str, filename: Option<String>| {
+            let _ = app.emit("progress", ProgressEvent {
+                id: task_op_id.clone(),
+                task: "ownership".to_string(),
+                current,
+                total,
+                status: status.to_string(),
+                filename,
+            });
+        };
+
+        let mut processed = 0u64;
+        let mut last_emit = std::time::Instant::now();
+        for target in &targets {
+            if cancel_flag.load(Ordering::Relaxed) { break; }
+            // Folders of a recursive run were already taken over during the walk
+            let done = recursive && target.is_dir();
+            if !done {
+                if let Err(e) = owner.apply(target) {
+                    failures.push(AttributeFailure {
+                        path: target.to_string_lossy().to_string(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+            processed += 1;
+            if last_emit.elapsed().as_millis() > 100 {
+                emit_progress(processed, "running", target.file_name().map(|n| n.to_string_lossy().to_string()));
+                last_emit = std::time::Instant::now();
+            }
+        }
+
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        emit_progress(processed, if cancelled { "cancelled" } else { "completed" }, None);
+        info!("Took ownership of {} item(s), {} failure(s)", processed, failures.len());
+
+        Ok(BulkAttributeResult { processed, failures, cancelled })
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    state.finish(&op_id);
+    result?
+}
+
+#[cfg(target_os = "windows")]
+fn file_owner(path: &std::path::Path) -> Result<String, CommandError> {
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Authorization::{GetNamedSecurityInfoW, SE_FILE_OBJECT};
+    use windows::Win32::Security::{LookupAccountSidW, OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR, PSID, SID_NAME_USE};
+
+    let wide_path = HSTRING::from(path.as_os_str());
+    unsafe {
+        let mut owner = PSID::default();
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        GetNamedSecurityInfoW(
+            PCWSTR(wide_path.as_ptr()),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION,
+            Some(&mut owner),
+            None,
+            None,
+            None,
+            &mut descriptor,
+        ).ok().map_err(|e| CommandError::SystemError(format!("Cannot read the owner of {}: {}", path.display(), e)))?;
+
+        let mut name = [0u16; 256];
+        let mut name_len = name.len() as u32;
+        let mut domain = [0u16; 256];
+        let mut domain_len = domain.len() as u32;
+        let mut sid_use = SID_NAME_USE::default();
+        let lookup = LookupAccountSidW(
+            PCWSTR::null(),
+            owner,
+            Some(PWSTR(name.as_mut_ptr())),
+            &mut name_len,
+            Some(PWSTR(domain.as_mut_ptr())),
+            &mut domain_len,
+            &mut sid_use,
+        );
+        // `owner` points into the descriptor, so it's only freed once the lookup is done
+        let _ = LocalFree(Some(HLOCAL(descriptor.0)));
+        lookup.map_err(|e| CommandError::SystemError(format!("Unknown owner account: {}", e)))?;
+
+        let name = String::from_utf16_lossy(&name[..name_len as usize]);
+        let domain = String::from_utf16_lossy(&domain[..domain_len as usize]);
+        Ok(if domain.is_empty() { name } else { format!("{}\\{}", domain, name) })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn file_owner(_path: &std::path::Path) -> Result<String, CommandError> {
+    Err(CommandError::Other("File owners are only available on Windows".to_string()))
+}
+
+/// TOKEN_USER of `token` (the SID of the user it belongs to), in a buffer since it's variable-sized.
+#[cfg(target_os = "windows")]
+fn token_user(token: windows::Win32::Foundation::HANDLE) -> Result<Vec<u8>, CommandError> {
+    use windows::Win32::Security::{GetTokenInformation, TokenUser};
+
+    unsafe {
+        // Asks for the length first
+        let mut len = 0u32;
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut len);
+        let mut user = vec![0u8; len as usize];
+        GetTokenInformation(token, TokenUser, Some(user.as_mut_ptr() as *mut _), len, &mut len)
+            .map_err(|e| CommandError::SystemError(format!("Cannot identify the current user: {}", e)))?;
+        Ok(user)
+    }
+}
+
+/// Privileges enabled on the process token, put back in their previous state when dropped so
+/// they don't outlive the operation that needed them, whichever way it ends.
+#[cfg(target_os = "windows")]
+struct PrivilegeGuard {
+    token: windows::Win32::Foundation::HANDLE,
+    /// Privilege states to put back, only those that were actually changed
+    previous: Vec<windows::Win32::Security::TOKEN_PRIVILEGES>,
+}
+
+#[cfg(target_os = "windows")]
+impl PrivilegeGuard {
+    /// Enables each of `privileges` on the process token, failing if one isn't held.
+    fn enable(privileges: &[PCWSTR]) -> Result<Self, CommandError> {
+        use windows::Win32::Foundation::{GetLastError, ERROR_NOT_ALL_ASSIGNED};
+        use windows::Win32::Security::{
+            AdjustTokenPrivileges, LookupPrivilegeValueW, LUID_AND_ATTRIBUTES, SE_PRIVILEGE_ENABLED,
+            TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES, TOKEN_QUERY,
+        };
+        use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+        unsafe {
+            let mut token = windows::Win32::Foundation::HANDLE::default();
+            OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY, &mut token)
+                .map_err(|e| CommandError::SystemError(format!("Cannot open process token: {}", e)))?;
+            // Built right away so whatever was enabled is restored on every early return
+            let mut guard = Self { token, previous: Vec::new() };
+
+            for &privilege in privileges {
+                let mut requested = TOKEN_PRIVILEGES {
+                    PrivilegeCount: 1,
+                    Privileges: [LUID_AND_ATTRIBUTES { Luid: Default::default(), Attributes: SE_PRIVILEGE_ENABLED }],
+                };
+                LookupPrivilegeValueW(PCWSTR::null(), privilege, &mut requested.Privileges[0].Luid)
+                    .map_err(|e| CommandError::SystemError(e.to_string()))?;
+                let mut previous = TOKEN_PRIVILEGES::default();
+                let mut previous_len = 0u32;
+                AdjustTokenPrivileges(
+                    token,
+                    false,
+                    Some(&requested),
+                    std::mem::size_of::<TOKEN_PRIVILEGES>() as u32,
+                    Some(&mut previous),
+                    Some(&mut previous_len),
+                ).map_err(|e| CommandError::SystemError(e.to_string()))?;
+                // Succeeds without enabling anything when the privilege isn't held
+                if GetLastError() == ERROR_NOT_ALL_ASSIGNED {
+                    return Err(CommandError::SystemError("This requires running as administrator".to_string()));
+                }
+                if previous.PrivilegeCount > 0 {
+                    guard.previous.push(previous);
+                }
+            }
+            Ok(guard)
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+impl Drop for PrivilegeGuard {
+    fn drop(&mut self) {
+        use windows::Win32::Security::AdjustTokenPrivileges;
+        unsafe {
+            for previous in &self.previous {
+                let _ = AdjustTokenPrivileges(self.token, false, Some(previous), 0, None, None);
+            }
+            let _ = windows::Win32::Foundation::CloseHandle(self.token);
+        }
+    }
+}
+
+/// The current user's SID, with SeTakeOwnershipPrivilege and SeRestorePrivilege enabled for as
+/// long as ownership is being changed.
+#[cfg(target_os = "windows")]
+struct OwnershipContext {
+    user: Vec<u8>,
+    _privileges: PrivilegeGuard,
+}
+
+#[cfg(target_os = "windows")]
+impl OwnershipContext {
+    fn acquire() -> Result<Self, CommandError> {
+        let user = current_user()?;
+        let privileges = PrivilegeGuard::enable(&[
+            windows::core::w!("SeTakeOwnershipPrivilege"),
+            windows::core::w!("SeRestorePrivilege"),
+        ])?;
+        Ok(Self { user, _privileges: privileges })
+    }
+
+    fn apply(&self, path: &std::path::Path) -> Result<(), CommandError> {
+        use windows::core::HSTRING;
+        use windows::Win32::Security::Authorization::{SetNamedSecurityInfoW, SE_FILE_OBJECT};
+        use windows::Win32::Security::{OWNER_SECURITY_INFORMATION, TOKEN_USER};
+
+        let wide_path = HSTRING::from(path.as_os_str());
+        unsafe {
+            let sid = (*(self.user.as_ptr() as *const TOKEN_USER)).User.Sid;
+            SetNamedSecurityInfoW(PCWSTR(wide_path.as_ptr()), SE_FILE_OBJECT, OWNER_SECURITY_INFORMATION, Some(sid), None, None, None)
+                .ok()
+                .map_err(|e| CommandError::SystemError(format!("Cannot take ownership of {}: {}", path.display(), e)))
+        }
+    }
+
+    /// Makes a folder just taken over listable: owning it doesn't grant reading it, so when its
+    /// DACL still shuts the user out they're given access, as `takeown /r` followed by
+    /// `icacls /grant` would.
+    fn ensure_readable(&self, dir: &std::path::Path) -> Result<(), CommandError> {
+        if fs::read_dir(dir).is_ok() {
+            return Ok(());
+        }
+        grant_access(&self.user, dir, true)?;
+        fs::read_dir(dir)
+            .map(|_| ())
+            .map_err(|e| CommandError::IoError(format!("Cannot read {}: {}", dir.display(), e)))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+struct OwnershipContext;
+
+#[cfg(not(target_os = "windows"))]
+impl OwnershipContext {
+    fn acquire() -> Result<Self, CommandError> {
+        Err(CommandError::Other("Taking ownership is only available on Windows".to_string()))
+    }
+
+    fn apply(&self, _path: &std::path::Path) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    fn ensure_readable(&self, _dir: &std::path::Path) -> Result<(), CommandError> {
+        Ok(())
+    }
+}
+
+/// Adds an access rule giving the current user full control over `path`, to get back into folders
+/// `list_dir` flags as protected. With `recursive` the rule is inheritable, so Windows extends it
+/// to the folder's content and to files created later, object by object with progress and
+/// cancellation; otherwise it only applies to `path` itself. Needs ownership or administrator rights; take ownership
+/// first when both are missing. Cancelled with `cancel_set_attributes` and the same `op_id`.
+#[tauri::command]
+pub async fn grant_full_control(
+    app: AppHandle,
+    state: State<'_, AttributeOpState>,
+    path: String,
+    recursive: bool,
+    op_id: String,
+) -> Result<BulkAttributeResult, CommandError> {
+    let root = validate_path(&path)?;
+    if !root.exists() {
+        return Err(CommandError::PathError(format!("{} does not exist", root.display())));
+    }
+    let cancel_flag = state.start(&op_id)?;
+
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let user = current_user()?;
+        let emit_progress = |current: u64, status: &str, filename: Option<String>| {
+            let _ = app.emit("progress", ProgressEvent {
+                id: task_op_id.clone(),
+                task: "permissions".to_string(),
+                current,
+                // The tree is walked by Windows itself, so its size isn't known up front
+                total: 0,
+                status: status.to_string(),
+                filename,
+            });
+        };
+
+        // An inheritable rule is only written through the tree variant, which reports each object
+        // as Windows propagates it and can be cancelled
+        let (processed, failures) = if recursive && root.is_dir() {
+            let mut last_emit = std::time::Instant::now();
+            grant_access_tree(&user, &root, &cancel_flag, &mut |processed, name| {
+                if last_emit.elapsed().as_millis() > 100 {
+                    let filename = std::path::Path::new(name).file_name().map(|n| n.to_string_lossy().to_string());
+                    emit_progress(processed, "running", filename);
+                    last_emit = std::time::Instant::now();
+                }
+            })?
+        } else {
+            let failures = match grant_access(&user, &root, false) {
+                Ok(()) => Vec::new(),
+                Err(e) => vec![AttributeFailure { path: root.to_string_lossy().to_string(), error: e.to_string() }],
+            };
+            (1, failures)
+        };
+
+        let cancelled = cancel_flag.load(Ordering::Relaxed);
+        emit_progress(processed, if cancelled { "cancelled" } else { "completed" }, None);
+        info!("Granted full control on {} item(s), {} failure(s)", processed, failures.len());
+
+        Ok(BulkAttributeResult { processed, failures, cancelled })
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    state.finish(&op_id);
+    result?
+}
+
+#[cfg(target_os = "windows")]
+fn current_user() -> Result<Vec<u8>, CommandError> {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::TOKEN_QUERY;
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)
+            .map_err(|e| CommandError::SystemError(format!("Cannot open process token: {}", e)))?;
+        let user = token_user(token);
+        let _ = CloseHandle(token);
+        user
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn current_user() -> Result<Vec<u8>, CommandError> {
+    Err(CommandError::Other("Permissions can only be changed on Windows".to_string()))
+}
+
+#[cfg(target_os = "windows")]
+fn permission_error(path: &std::path::Path, e: windows::core::Error) -> CommandError {
+    if e.code() == windows::Win32::Foundation::ERROR_ACCESS_DENIED.to_hresult() {
+        CommandError::SystemError(format!("Access denied on {}: this requires administrator rights or ownership", path.display()))
+    } else {
+        CommandError::SystemError(format!("Cannot change permissions of {}: {}", path.display(), e))
+    }
+}
+
+/// The DACL of `path` with a full-control entry for `user` (a TOKEN_USER buffer) merged in,
+/// inherited by everything below with `inherit`. The caller frees it with `LocalFree`.
+#[cfg(target_os = "windows")]
+unsafe fn dacl_granting(user: &[u8], path: &std::path::Path, inherit: bool) -> Result<*mut windows::Win32::Security::ACL, CommandError> {
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Authorization::{
+        GetNamedSecurityInfoW, SetEntriesInAclW, EXPLICIT_ACCESS_W, GRANT_ACCESS, SE_FILE_OBJECT, TRUSTEE_IS_SID,
+        TRUSTEE_IS_USER, TRUSTEE_W,
+    };
+    use windows::Win32::Security::{
+        ACL, DACL_SECURITY_INFORMATION, NO_INHERITANCE, PSECURITY_DESCRIPTOR, SUB_CONTAINERS_AND_OBJECTS_INHERIT, TOKEN_USER,
+    };
+    use windows::Win32::Storage::FileSystem::FILE_ALL_ACCESS;
+
+    let wide_path = HSTRING::from(path.as_os_str());
+    let sid = (*(user.as_ptr() as *const TOKEN_USER)).User.Sid;
+    let mut dacl: *mut ACL = std::ptr::null_mut();
+    let mut descriptor = PSECURITY_DESCRIPTOR::default();
+    GetNamedSecurityInfoW(
+        PCWSTR(wide_path.as_ptr()),
+        SE_FILE_OBJECT,
+        DACL_SECURITY_INFORMATION,
+        None,
+        None,
+        Some(&mut dacl),
+        None,
+        &mut descriptor,
+    ).ok().map_err(|e| permission_error(path, e))?;
+
+    let access = EXPLICIT_ACCESS_W {
+        grfAccessPermissions: FILE_ALL_ACCESS.0,
+        grfAccessMode: GRANT_ACCESS,
+        grfInheritance: if inherit { SUB_CONTAINERS_AND_OBJECTS_INHERIT } else { NO_INHERITANCE },
+        Trustee: TRUSTEE_W {
+            TrusteeForm: TRUSTEE_IS_SID,
+            TrusteeType: TRUSTEE_IS_USER,
+            ptstrName: PWSTR(sid.0 as *mut u16),
+            ..Default::default()
+        },
+    };
+    let mut new_dacl: *mut ACL = std::ptr::null_mut();
+    let merged = SetEntriesInAclW(Some(&[access]), Some(dacl), &mut new_dacl).ok();
+    // `dacl` points into the descriptor, so it's only freed once the merge is done
+    let _ = LocalFree(Some(HLOCAL(descriptor.0)));
+    merged.map_err(|e| permission_error(path, e))?;
+    Ok(new_dacl)
+}
+
+/// Merges a full-control entry for `user` (a TOKEN_USER buffer) into the DACL of `path`.
+/// With `inherit` (folders only) Windows also propagates it to what's inside, in one go.
+#[cfg(target_os = "windows")]
+fn grant_access(user: &[u8], path: &std::path::Path, inherit: bool) -> Result<(), CommandError> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+    use windows::Win32::Security::Authorization::{SetNamedSecurityInfoW, SE_FILE_OBJECT};
+    use windows::Win32::Security::DACL_SECURITY_INFORMATION;
+
+    let wide_path = HSTRING::from(path.as_os_str());
+    unsafe {
+        let new_dacl = dacl_granting(user, path, inherit)?;
+        let result = SetNamedSecurityInfoW(PCWSTR(wide_path.as_ptr()), SE_FILE_OBJECT, DACL_SECURITY_INFORMATION, None, None, Some(new_dacl), None).ok();
+        let _ = LocalFree(Some(HLOCAL(new_dacl as *mut _)));
+        result.map_err(|e| permission_error(path, e))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn grant_access(_user: &[u8], _path: &std::path::Path, _inherit: bool) -> Result<(), CommandError> {
+    Ok(())
+}
+
+/// State shared with the `TreeSetNamedSecurityInfoW` callback of `grant_access_tree`.
+#[cfg(target_os = "windows")]
+struct TreeGrant<'a> {
+    cancel: &'a AtomicBool,
+    processed: u64,
+    failures: Vec<AttributeFailure>,
+    on_object: &'a mut dyn FnMut(u64, &str),
+}
+
+/// `grant_access` on the folder `root`, propagating the entry through its tree with
+/// `TreeSetNamedSecurityInfoW` so each object is reported to `on_object` and the run can be
+/// cancelled. Returns the number of objects visited and those that couldn't be updated.
+#[cfg(target_os = "windows")]
+fn grant_access_tree(
+    user: &[u8],
+    root: &std::path::Path,
+    cancel: &AtomicBool,
+    on_object: &mut dyn FnMut(u64, &str),
+) -> Result<(u64, Vec<AttributeFailure>), CommandError> {
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::{LocalFree, HLOCAL, WIN32_ERROR};
+    use windows::Win32::Security::Authorization::{
+        ProgressCancelOperation, ProgressInvokeEveryObject, TreeSetNamedSecurityInfoW, PROG_INVOKE_SETTING, SE_FILE_OBJECT,
+        TREE_SEC_INFO_SET,
+    };
+    use windows::Win32::Security::DACL_SECURITY_INFORMATION;
+
+    unsafe extern "system" fn on_progress(
+        name: PWSTR,
+        status: u32,
+        invoke_setting: *mut PROG_INVOKE_SETTING,
+        args: *const std::ffi::c_void,
+        _security_set: *mut windows::core::BOOL,
+    ) {
+        let grant = &mut *(args as *mut TreeGrant);
+        let name = name.to_string().unwrap_or_default();
+        if status != 0 {
+            let error = windows::core::Error::from(WIN32_ERROR(status).to_hresult());
+            grant.failures.push(AttributeFailure { path: name.clone(), error: error.message() });
+        }
+        grant.processed += 1;
+        (grant.on_object)(grant.processed, &name);
+        if grant.cancel.load(Ordering::Relaxed) {
+            *invoke_setting = ProgressCancelOperation;
+        }
+    }
+
+    let wide_path = HSTRING::from(root.as_os_str());
+    let mut grant = TreeGrant { cancel, processed: 0, failures: Vec::new(), on_object };
+    unsafe {
+        let new_dacl = dacl_granting(user, root, true)?;
+        let result = TreeSetNamedSecurityInfoW(
+            PCWSTR(wide_path.as_ptr()),
+            SE_FILE_OBJECT,
+            DACL_SECURITY_INFORMATION,
+            None,
+            None,
+            Some(new_dacl),
+            None,
+            TREE_SEC_INFO_SET,
+            Some(on_progress),
+            ProgressInvokeEveryObject,
+            Some(&mut grant as *mut TreeGrant as *const _),
+        ).ok();
+        let _ = LocalFree(Some(HLOCAL(new_dacl as *mut _)));
+        // A cancelled run stops with an error of its own, which isn't a failure
+        if !cancel.load(Ordering::Relaxed) {
+            result.map_err(|e| permission_error(root, e))?;
+        }
+    }
+    Ok((grant.processed, grant.failures))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn grant_access_tree(
+    _user: &[u8],
+    _root: &std::path::Path,
+    _cancel: &AtomicBool,
+    _on_object: &mut dyn FnMut(u64, &str),
+) -> Result<(u64, Vec<AttributeFailure>), CommandError> {
+    Ok((0, Vec::new()))
+}
+
+/// Issues FSCTL_SET_COMPRESSION on a single file or folder (not recursive).
+fn apply_ntfs_compression(path: &std::path::Path, compressed: bool) -> Result<(), CommandError> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::HSTRING;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::Storage::FileSystem::{
+            CreateFileW, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT, FILE_GENERIC_READ, FILE_GENERIC_WRITE,
+            FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+        };
+        use windows::Win32::System::Ioctl::FSCTL_SET_COMPRESSION;
+        use windows::Win32::System::IO::DeviceIoControl;
+
+        // COMPRESSION_FORMAT_DEFAULT (LZNT1) or COMPRESSION_FORMAT_NONE
+        let mut format: u16 = if compressed { 1 } else { 0 };
+        let wide_path = HSTRING::from(path.as_os_str());
+        unsafe {
+            // FILE_FLAG_BACKUP_SEMANTICS is required to open directories; a link is opened itself,
+            // never its target
+            let handle = CreateFileW(
+                PCWSTR(wide_path.as_ptr()),
+                (FILE_GENERIC_READ | FILE_GENERIC_WRITE).0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OPEN_REPARSE_POINT,
+                None,
+            ).map_err(|e| CommandError::SystemError(format!("Cannot open {}: {}", path.display(), e)))?;
+
+            let mut bytes_returned = 0u32;
+            let result = DeviceIoControl(
+                handle,
+                FSCTL_SET_COMPRESSION,
+                Some(&mut format as *mut _ as *mut _),
+                std::mem::size_of::<u16>() as u32,
+                None,
+                0,
+                Some(&mut bytes_returned),
+                None,
+            );
+            let _ = CloseHandle(handle);
+            result.map_err(|e| CommandError::SystemError(format!("FSCTL_SET_COMPRESSION failed for {}: {}", path.display(), e)))?;
+        }
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (path, compressed);
+        Err(CommandError::Other("NTFS compression is only available on Windows".to_string()))
+    }
+}
+
+fn system_time_to_ms(time: std::io::Result<SystemTime>) -> u64 {
+    time.unwrap_or(SystemTime::UNIX_EPOCH)
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Applies the provided timestamps to a path (file or folder) and returns the resulting times.
+pub(crate) fn apply_file_times(path: &std::path::Path, changes: &FileTimestampChanges) -> Result<FileTimestamps, CommandError> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::HSTRING;
+        use windows::Win32::Foundation::{CloseHandle, FILETIME};
+        use windows::Win32::Storage::FileSystem::{
+            CreateFileW, SetFileTime, FILE_FLAG_BACKUP_SEMANTICS, FILE_SHARE_DELETE, FILE_SHARE_READ,
+            FILE_SHARE_WRITE, FILE_WRITE_ATTRIBUTES, OPEN_EXISTING,
+        };
+
+        // FILETIME counts 100ns intervals since 1601-01-01
+        let to_filetime = |ms: u64| {
+            let ticks = ms * 10_000 + 116_444_736_000_000_000;
+            FILETIME { dwLowDateTime: ticks as u32, dwHighDateTime: (ticks >> 32) as u32 }
+        };
+        let created = changes.created.map(to_filetime);
+        let accessed = changes.accessed.map(to_filetime);
+        let modified = changes.modified.map(to_filetime);
+
+        let wide_path = HSTRING::from(path.as_os_str());
+        unsafe {
+            // FILE_FLAG_BACKUP_SEMANTICS is required to open directories
+            let handle = CreateFileW(
+                PCWSTR(wide_path.as_ptr()),
+                FILE_WRITE_ATTRIBUTES.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAG_BACKUP_SEMANTICS,
+                None,
+            ).map_err(|e| CommandError::SystemError(format!("Cannot open {}: {}", path.display(), e)))?;
+
+            let result = SetFileTime(
+                handle,
+                created.as_ref().map(|t| t as *const FILETIME),
+                accessed.as_ref().map(|t| t as *const FILETIME),
+                modified.as_ref().map(|t| t as *const FILETIME),
+            );
+            let _ = CloseHandle(handle);
+            result.map_err(|e| CommandError::SystemError(format!("SetFileTime failed for {}: {}", path.display(), e)))?;
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        // Creation time can't be changed on most Unix filesystems; only modified/accessed are applied
+        let to_time = |ms: u64| SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(ms);
+        let mut times = fs::FileTimes::new();
+        if let Some(ms) = changes.modified { times = times.set_modified(to_time(ms)); }
+        if let Some(ms) = changes.accessed { times = times.set_accessed(to_time(ms)); }
+        fs::File::options().write(true).open(path)
+            .or_else(|_| fs::File::open(path))?
+            .set_times(times)?;
+    }
+
+    let metadata = fs::metadata(path)?;
+    Ok(FileTimestamps {
+        created: system_time_to_ms(metadata.created()),
+        modified: system_time_to_ms(metadata.modified()),
+        accessed: system_time_to_ms(metadata.accessed()),
+    })
+}
+
+#[tauri::command]
+pub async fn set_file_times(path: String, times: FileTimestampChanges) -> Result<FileTimestamps, CommandError> {
+    let pb = validate_path(&path)?;
+    info!("Setting file times on {:?}: {:?}", pb, times);
+    apply_file_times(&pb, &times)
+}
@@ -1,10 +1,13 @@
 pub mod archive;
 pub mod clipboard;
 pub mod icons;
+pub mod image;
 pub mod io;
 pub mod ops;
 pub mod search;
 pub mod session;
+pub mod shadow;
+pub mod split;
 pub mod config;
 
 pub mod sidebar;
@@ -1,10 +1,17 @@
 use crate::models::NetResource;
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter};
 use windows::Win32::NetworkManagement::WNet::{
     WNetOpenEnumW, WNetEnumResourceW, WNetCloseEnum, WNetAddConnection2W, WNetCancelConnection2W,
     RESOURCE_GLOBALNET, RESOURCETYPE_ANY, NETRESOURCEW, RESOURCEUSAGE_CONTAINER,
-    WNET_OPEN_ENUM_USAGE, RESOURCETYPE_DISK, CONNECT_UPDATE_PROFILE, NET_CONNECT_FLAGS
+    WNET_OPEN_ENUM_USAGE, RESOURCETYPE_DISK, RESOURCEDISPLAYTYPE_SHARE, CONNECT_UPDATE_PROFILE, NET_CONNECT_FLAGS
+};
+use windows::Win32::Foundation::{
+    WIN32_ERROR, ERROR_MORE_DATA, ERROR_ACCESS_DENIED, ERROR_LOGON_FAILURE, ERROR_INVALID_PASSWORD,
+    ERROR_BAD_NETPATH, ERROR_BAD_NET_NAME, ERROR_NO_NETWORK, ERROR_ALREADY_ASSIGNED,
+    ERROR_DEVICE_ALREADY_REMEMBERED, ERROR_SESSION_CREDENTIAL_CONFLICT, ERROR_BAD_DEVICE,
+    ERROR_NOT_CONNECTED, ERROR_OPEN_FILES,
 };
-use windows::Win32::Foundation::WIN32_ERROR;
 use windows::Win32::UI::Shell::{
     SHGetKnownFolderItem, FOLDERID_NetworkFolder, KF_FLAG_DEFAULT, IShellItem,
     IEnumShellItems, SIGDN_NORMALDISPLAY, SIGDN_DESKTOPABSOLUTEPARSING, BHID_EnumItems,
@@ -104,6 +111,7 @@ pub async fn get_network_resources(path: Option<String>) -> Result<Vec<NetResour
                                         provider: Some("Windows Shell".to_string()),
                                         is_media_device,
                                         has_web_page,
+                                        requires_auth: None,
                                     });
                                 }
                             }
@@ -119,85 +127,131 @@ pub async fn get_network_resources(path: Option<String>) -> Result<Vec<NetResour
             }
 
             // Path is Some (a specific server like \\SERVER)
-            let mut handle = windows::Win32::Foundation::HANDLE::default();
-            let mut nr = NETRESOURCEW::default();
-            let mut wide_path: Vec<u16>;
-            
-            let p = path.unwrap();
-            wide_path = p.encode_utf16().chain(std::iter::once(0)).collect();
-            nr.lpRemoteName = PWSTR(wide_path.as_mut_ptr());
+            // Return empty instead of error, so interface doesn't complain endlessly if unreachable
+            resources = enumerate_net_resources(path.as_deref()).unwrap_or_default();
+        }
+        
+        resources.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(resources)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("Network discovery is only available on Windows".to_string())
+    }
+}
+
+/// Enumerates the children of `parent` (network root when `None`) through the WNet API.
+unsafe fn enumerate_net_resources(parent: Option<&str>) -> Result<Vec<NetResource>, WIN32_ERROR> {
+    let mut handle = windows::Win32::Foundation::HANDLE::default();
+    let mut nr = NETRESOURCEW::default();
+    let mut wide_parent: Vec<u16>;
+
+    let nr_ptr = match parent {
+        Some(p) => {
+            wide_parent = p.encode_utf16().chain(std::iter::once(0)).collect();
+            nr.lpRemoteName = PWSTR(wide_parent.as_mut_ptr());
             nr.dwUsage = RESOURCEUSAGE_CONTAINER.0;
-            let nr_ptr = &nr as *const _;
+            Some(&nr as *const _)
+        }
+        None => None,
+    };
 
-            let res = WNetOpenEnumW(
-                RESOURCE_GLOBALNET,
-                RESOURCETYPE_ANY,
-                WNET_OPEN_ENUM_USAGE(0),
-                Some(nr_ptr),
-                &mut handle
-            );
+    let res = WNetOpenEnumW(RESOURCE_GLOBALNET, RESOURCETYPE_ANY, WNET_OPEN_ENUM_USAGE(0), nr_ptr, &mut handle);
+    if !res.is_ok() {
+        return Err(res);
+    }
 
-            if res.is_ok() {
-                let mut buffer = vec![0u8; 16384];
-                loop {
-                    let mut count = 0xFFFFFFFFu32;
-                    let mut buffer_size = buffer.len() as u32;
-                    
-                    let enum_res = WNetEnumResourceW(
-                        handle,
-                        &mut count,
-                        buffer.as_mut_ptr() as *mut _,
-                        &mut buffer_size
-                    );
+    let mut resources = Vec::new();
+    let mut buffer = vec![0u8; 16384];
+    loop {
+        let mut count = 0xFFFFFFFFu32;
+        let mut buffer_size = buffer.len() as u32;
 
-                    if enum_res.is_ok() {
-                        let ptr = buffer.as_ptr() as *const NETRESOURCEW;
-                        for i in 0..count as usize {
-                            let item = &*ptr.add(i);
-                            let remote_name = if !item.lpRemoteName.is_null() {
-                                item.lpRemoteName.to_string().unwrap_or_default()
-                            } else {
-                                String::new()
-                            };
+        let enum_res = WNetEnumResourceW(handle, &mut count, buffer.as_mut_ptr() as *mut _, &mut buffer_size);
+        if enum_res == ERROR_MORE_DATA {
+            // A single entry didn't fit; buffer_size now holds the required size
+            buffer.resize(buffer_size as usize, 0);
+            continue;
+        }
+        if !enum_res.is_ok() {
+            break;
+        }
 
-                            let name = if !item.lpComment.is_null() && !item.lpComment.to_string().unwrap_or_default().is_empty() {
-                                item.lpComment.to_string().unwrap_or_default()
-                            } else if !item.lpRemoteName.is_null() {
-                                let rn = item.lpRemoteName.to_string().unwrap_or_default();
-                                let parts: Vec<&str> = rn.split('\\').collect();
-                                parts.last().unwrap_or(&"Unknown").to_string()
-                            } else {
-                                "Unknown".to_string()
-                            };
+        let ptr = buffer.as_ptr() as *const NETRESOURCEW;
+        for i in 0..count as usize {
+            let item = &*ptr.add(i);
+            let remote_name = if !item.lpRemoteName.is_null() {
+                item.lpRemoteName.to_string().unwrap_or_default()
+            } else {
+                String::new()
+            };
 
-                            resources.push(NetResource {
-                                name,
-                                remote_path: remote_name,
-                                resource_type: item.dwType.0,
-                                display_type: item.dwDisplayType,
-                                usage: item.dwUsage,
-                                provider: if !item.lpProvider.is_null() {
-                                    Some(item.lpProvider.to_string().unwrap_or_default())
-                                } else {
-                                    None
-                                },
-                                is_media_device: None,
-                                has_web_page: None,
-                            });
-                        }
-                    } else {
-                        break;
-                    }
-                }
-                let _ = WNetCloseEnum(handle);
+            let name = if !item.lpComment.is_null() && !item.lpComment.to_string().unwrap_or_default().is_empty() {
+                item.lpComment.to_string().unwrap_or_default()
+            } else if !item.lpRemoteName.is_null() {
+                let parts: Vec<&str> = remote_name.split('\\').collect();
+                parts.last().unwrap_or(&"Unknown").to_string()
             } else {
-                // Return empty instead of error, so interface doesn't complain endlessly if unreachable
-                return Ok(resources);
-            }
+                "Unknown".to_string()
+            };
+
+            resources.push(NetResource {
+                name,
+                remote_path: remote_name,
+                resource_type: item.dwType.0,
+                display_type: item.dwDisplayType,
+                usage: item.dwUsage,
+                provider: if !item.lpProvider.is_null() {
+                    Some(item.lpProvider.to_string().unwrap_or_default())
+                } else {
+                    None
+                },
+                is_media_device: None,
+                has_web_page: None,
+                requires_auth: None,
+            });
         }
-        
-        resources.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-        Ok(resources)
+    }
+    let _ = WNetCloseEnum(handle);
+    Ok(resources)
+}
+
+fn is_auth_error(code: WIN32_ERROR) -> bool {
+    code == ERROR_ACCESS_DENIED || code == ERROR_LOGON_FAILURE || code == ERROR_INVALID_PASSWORD
+}
+
+/// Browses the network with WNet: `None` lists providers/domains, then servers, then a server's shares.
+/// Disk shares that deny access with the current credentials are flagged with `requires_auth`.
+#[tauri::command]
+pub async fn list_network_resources(parent: Option<String>) -> Result<Vec<NetResource>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut resources = match unsafe { enumerate_net_resources(parent.as_deref()) } {
+                Ok(r) => r,
+                Err(code) if is_auth_error(code) => {
+                    return Err(format!("Authentication required for {}", parent.unwrap_or_default()));
+                }
+                Err(code) if code == ERROR_BAD_NETPATH || code == ERROR_BAD_NET_NAME || code == ERROR_NO_NETWORK => {
+                    return Err(format!("Network path not reachable: {}", parent.unwrap_or_default()));
+                }
+                Err(code) => return Err(format!("WNetOpenEnumW failed with code {:?}", code)),
+            };
+
+            for res in resources.iter_mut() {
+                if res.display_type == RESOURCEDISPLAYTYPE_SHARE && res.resource_type == RESOURCETYPE_DISK.0 {
+                    let denied = match std::fs::read_dir(&res.remote_path) {
+                        Ok(_) => false,
+                        Err(e) => e.kind() == std::io::ErrorKind::PermissionDenied
+                            || e.raw_os_error().map(|c| is_auth_error(WIN32_ERROR(c as u32))).unwrap_or(false),
+                    };
+                    res.requires_auth = Some(denied);
+                }
+            }
+
+            resources.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+            Ok(resources)
+        }).await.map_err(|e| format!("Thread panic: {}", e))?
     }
     #[cfg(not(target_os = "windows"))]
     {
@@ -205,59 +259,116 @@ pub async fn get_network_resources(path: Option<String>) -> Result<Vec<NetResour
     }
 }
 
+#[derive(Deserialize)]
+pub struct NetCredentials {
+    pub user: String,
+    pub pass: String,
+}
+
+/// Turns a WNet error code into a message the UI can show. The code stays in the text
+/// because the frontend keys off it (e.g. 2401 to offer a forced disconnect).
+fn describe_wnet_error(code: WIN32_ERROR) -> String {
+    let msg = match code {
+        ERROR_ALREADY_ASSIGNED => "Drive letter is already in use",
+        ERROR_DEVICE_ALREADY_REMEMBERED => "Drive letter is already mapped to another share",
+        ERROR_SESSION_CREDENTIAL_CONFLICT => "Already connected to this server with different credentials",
+        ERROR_LOGON_FAILURE | ERROR_INVALID_PASSWORD => "Bad user name or password",
+        ERROR_ACCESS_DENIED => "Access denied",
+        ERROR_BAD_NETPATH | ERROR_NO_NETWORK => "Server is unreachable",
+        ERROR_BAD_NET_NAME => "Share not found on server",
+        ERROR_BAD_DEVICE => "Invalid drive letter",
+        ERROR_NOT_CONNECTED => "Not a connected network drive",
+        ERROR_OPEN_FILES => "Files are open on this connection",
+        _ => "Network operation failed",
+    };
+    format!("{} (error {})", msg, code.0)
+}
+
+/// Accepts "Z", "Z:" or "Z:\" and returns "Z:"; UNC paths are kept without trailing separators.
+fn normalize_local_name(letter: &str) -> String {
+    let trimmed = letter.trim().trim_end_matches(['\\', '/']);
+    if trimmed.len() == 1 { format!("{}:", trimmed.to_uppercase()) } else { trimmed.to_string() }
+}
+
+/// Connects `unc_path`, optionally to `drive_letter` (without one, the share is only authenticated).
 #[tauri::command]
-pub async fn map_network_drive(letter: String, path: String, reconnect: bool) -> Result<(), String> {
+pub async fn map_network_drive(
+    app: AppHandle,
+    unc_path: String,
+    drive_letter: Option<String>,
+    persistent: bool,
+    credentials: Option<NetCredentials>,
+) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        unsafe {
+        let remote = unc_path.trim().trim_end_matches(['\\', '/']).to_string();
+        if !remote.starts_with("\\\\") {
+            return Err(format!("Not a network path: {}", unc_path));
+        }
+
+        let result = tauri::async_runtime::spawn_blocking(move || unsafe {
             let mut nr = NETRESOURCEW::default();
             nr.dwType = RESOURCETYPE_DISK;
-            
-            let mut wide_remote: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
 
-            // Make sure the letter is formatted e.g., "Z:" not just "Z"
-            let local_name = if letter.len() == 1 { format!("{}:", letter) } else { letter.clone() };
-            let mut wide_local: Vec<u16> = local_name.encode_utf16().chain(std::iter::once(0)).collect();
-
-            nr.lpLocalName = PWSTR(wide_local.as_mut_ptr());
+            let mut wide_remote: Vec<u16> = remote.encode_utf16().chain(std::iter::once(0)).collect();
             nr.lpRemoteName = PWSTR(wide_remote.as_mut_ptr());
 
-            let flags = if reconnect { CONNECT_UPDATE_PROFILE } else { NET_CONNECT_FLAGS(0) };
+            let mut wide_local: Vec<u16> = drive_letter
+                .as_deref()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| normalize_local_name(l).encode_utf16().chain(std::iter::once(0)).collect())
+                .unwrap_or_default();
+            if !wide_local.is_empty() {
+                nr.lpLocalName = PWSTR(wide_local.as_mut_ptr());
+            }
 
-            // WNetAddConnection2W returns a WIN32_ERROR (u32 wrapped), we check if it is 0 (NO_ERROR)
-            let result = WNetAddConnection2W(&nr, PCWSTR::null(), PCWSTR::null(), flags);
-            if result == WIN32_ERROR(0) {
-                return Ok(());
+            let (wide_user, wide_pass): (Vec<u16>, Vec<u16>) = match &credentials {
+                Some(c) => (
+                    c.user.encode_utf16().chain(std::iter::once(0)).collect(),
+                    c.pass.encode_utf16().chain(std::iter::once(0)).collect(),
+                ),
+                None => (Vec::new(), Vec::new()),
+            };
+            let (user, pass) = if credentials.is_some() {
+                (PCWSTR(wide_user.as_ptr()), PCWSTR(wide_pass.as_ptr()))
             } else {
-                return Err(format!("WNetAddConnection2W failed with code {:?}", result));
-            }
+                (PCWSTR::null(), PCWSTR::null())
+            };
+
+            let flags = if persistent { CONNECT_UPDATE_PROFILE } else { NET_CONNECT_FLAGS(0) };
+            WNetAddConnection2W(&nr, pass, user, flags)
+        }).await.map_err(|e| format!("Thread panic: {}", e))?;
+
+        if !result.is_ok() {
+            return Err(describe_wnet_error(result));
         }
+        let _ = app.emit("drives-changed", ());
+        Ok(())
     }
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (app, unc_path, drive_letter, persistent, credentials);
         Err("Network drive mapping is only available on Windows".to_string())
     }
 }
 
 #[tauri::command]
-pub async fn disconnect_network_drive(letter: String, force: bool) -> Result<(), String> {
+pub async fn disconnect_network_drive(app: AppHandle, drive_letter: String, force: bool) -> Result<(), String> {
     #[cfg(target_os = "windows")]
     {
-        unsafe {
-            let local_name = letter.trim_end_matches('\\').to_string();
-            let wide_local: Vec<u16> = local_name.encode_utf16().chain(std::iter::once(0)).collect();
+        let local_name = normalize_local_name(&drive_letter);
+        let wide_local: Vec<u16> = local_name.encode_utf16().chain(std::iter::once(0)).collect();
 
-            let result = WNetCancelConnection2W(PCWSTR(wide_local.as_ptr()), CONNECT_UPDATE_PROFILE, force);
-            if result == WIN32_ERROR(0) {
-                return Ok(());
-            } else {
-                return Err(format!("WNetCancelConnection2W failed with code {:?}", result));
-            }
+        let result = unsafe { WNetCancelConnection2W(PCWSTR(wide_local.as_ptr()), CONNECT_UPDATE_PROFILE, force) };
+        if !result.is_ok() {
+            return Err(describe_wnet_error(result));
         }
+        let _ = app.emit("drives-changed", ());
+        Ok(())
     }
     #[cfg(not(target_os = "windows"))]
     {
+        let _ = (app, drive_letter, force);
         Err("Network drive disconnect is only available on Windows".to_string())
     }
 }
-
@@ -1,7 +1,7 @@
-use crate::models::{get_file_entry_from_path, ConflictEntry, ConflictResponse, TrashEntry, CommandError, Transaction, TransactionType, TransactionDetails, HistoryManager, ProgressEvent};
-use crate::utils::path_security::validate_path;
+use crate::models::{get_file_entry_from_path, ConflictEntry, ConflictResponse, SizeEstimate, TrashEntry, CommandError, Transaction, TransactionType, TransactionDetails, HistoryManager, ProgressEvent, ActivityEntry, ActivityLog};
+use crate::utils::path_security::{extended_path, long_path, strip_extended_length, validate_path};
 use std::path::PathBuf;
-use tauri::{AppHandle, Emitter, State}; // Emitter needed for legacy progress emit
+use tauri::{AppHandle, Emitter, Manager, State}; // Emitter needed for legacy progress emit
 use std::sync::{Arc};
 use std::sync::atomic::{AtomicBool, Ordering};
 use log::{info, warn};
@@ -231,7 +231,7 @@ pub async fn purge_recycle_bin(paths: Vec<String>) -> Result<(), CommandError> {
 
             let mut buffer: Vec<u16> = Vec::new();
             for item in &items_to_purge {
-                let path_str = PathBuf::from(item.id.clone()).to_string_lossy().replace("/", "\\");
+                let path_str = strip_extended_length(&item.id).replace("/", "\\");
                 buffer.extend(path_str.encode_utf16());
                 buffer.push(0);
             }
@@ -334,19 +334,94 @@ pub async fn check_conflicts(
     })
 }
 
+/// Full-size follow-up to `check_conflicts` for when it reports `likely_large`: walks every
+/// source on a blocking thread and emits `size_estimate` with the exact totals. Cancelled
+/// through `cancel_folder_size` with the same `op_id`, e.g. when the copy dialog closes.
+#[tauri::command]
+pub async fn estimate_operation_size(
+    app: AppHandle,
+    state: State<'_, crate::commands::io::FolderSizeState>,
+    op_id: String,
+    paths: Vec<String>,
+) -> Result<SizeEstimate, CommandError> {
+    let sources = paths.iter().map(|p| validate_path(p)).collect::<Result<Vec<_>, _>>()?;
+
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    state.0.lock()
+        .map_err(|_| CommandError::SystemError("Failed to lock folder size state".to_string()))?
+        .insert(op_id.clone(), cancel_flag.clone());
+
+    let task_op_id = op_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut estimate = SizeEstimate { op_id: task_op_id, total_size: 0, total_files: 0, cancelled: false };
+        'sources: for source in &sources {
+            for entry in walkdir::WalkDir::new(source).into_iter().filter_map(|e| e.ok()) {
+                if cancel_flag.load(Ordering::Relaxed) {
+                    estimate.cancelled = true;
+                    break 'sources;
+                }
+                if entry.file_type().is_file() {
+                    estimate.total_size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    estimate.total_files += 1;
+                }
+            }
+        }
+        let _ = app.emit("size_estimate", estimate.clone());
+        estimate
+    }).await.map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)));
+
+    if let Ok(mut tokens) = state.0.lock() {
+        tokens.remove(&op_id);
+    }
+    result
+}
+
+
 
 
 
+/// Returns the real (non-archive) paths whose volume has no Recycle Bin.
+fn paths_without_trash(paths: &[PathBuf]) -> Vec<String> {
+    paths.iter()
+        .filter(|p| {
+            let is_virtual = crate::utils::archive::split_virtual_path(&p.to_string_lossy())
+                .map(|(_, internal)| !internal.is_empty())
+                .unwrap_or(false);
+            !is_virtual && !crate::utils::hardware::supports_recycle_bin(p)
+        })
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
 
+/// Lists which of `paths` would be deleted permanently instead of going to the Recycle Bin.
 #[tauri::command]
-pub async fn delete_items(app: AppHandle, manager: State<'_, FileOperationManager>, paths: Vec<String>, turbo: Option<bool>) -> Result<String, CommandError> {
+pub async fn check_recycle_bin_support(paths: Vec<String>) -> Result<Vec<String>, CommandError> {
+    let paths_validated = paths.iter()
+        .map(|p| validate_path(p))
+        .collect::<Result<Vec<PathBuf>, CommandError>>()?;
+    Ok(paths_without_trash(&paths_validated))
+}
+
+/// Moves items to the Recycle Bin. Fails with `RecycleBinUnavailable` if some of them can only be
+/// deleted permanently, unless `allow_permanent` is set. `password` unlocks encrypted archives
+/// entries are deleted from.
+#[tauri::command]
+pub async fn delete_items(app: AppHandle, manager: State<'_, FileOperationManager>, paths: Vec<String>, turbo: Option<bool>, allow_permanent: Option<bool>, password: Option<String>) -> Result<String, CommandError> {
     info!("Moving items to trash: {:?}", paths);
     let mut paths_validated = Vec::new();
     for p in paths {
         paths_validated.push(validate_path(&p)?);
     }
+
+    if !allow_permanent.unwrap_or(false) {
+        let no_trash = paths_without_trash(&paths_validated);
+        if !no_trash.is_empty() {
+            return Err(CommandError::RecycleBinUnavailable(no_trash.join("\n")));
+        }
+    }
     
     let mut op = FileOperation::new(FileOpType::Trash, paths_validated, None);
+    op.archive_password = password.filter(|p| !p.is_empty());
     if let Some(t) = turbo {
         op.turbo = t;
         op.turbo_flag.store(t, Ordering::Relaxed);
@@ -357,7 +432,7 @@ pub async fn delete_items(app: AppHandle, manager: State<'_, FileOperationManage
 }
 
 #[tauri::command]
-pub async fn purge_items(app: AppHandle, manager: State<'_, FileOperationManager>, paths: Vec<String>, turbo: Option<bool>) -> Result<String, CommandError> {
+pub async fn purge_items(app: AppHandle, manager: State<'_, FileOperationManager>, paths: Vec<String>, turbo: Option<bool>, password: Option<String>) -> Result<String, CommandError> {
     info!("Permanently deleting items: {:?}", paths);
     let mut paths_validated = Vec::new();
     for p in paths {
@@ -365,6 +440,7 @@ pub async fn purge_items(app: AppHandle, manager: State<'_, FileOperationManager
     }
     
     let mut op = FileOperation::new(FileOpType::Delete, paths_validated, None);
+    op.archive_password = password.filter(|p| !p.is_empty());
     if let Some(t) = turbo {
         op.turbo = t;
         op.turbo_flag.store(t, Ordering::Relaxed);
@@ -374,6 +450,61 @@ pub async fn purge_items(app: AppHandle, manager: State<'_, FileOperationManager
     Ok(id)
 }
 
+/// Quick capped size walk used when the caller has no pre-flight estimate. It may undercount
+/// very large trees, which only makes the free-space check more permissive.
+fn estimate_total_size(sources: &[PathBuf]) -> u64 {
+    let start_time = std::time::Instant::now();
+    let mut total_size = 0;
+    let mut visited = 0;
+    for source in sources {
+        for entry in walkdir::WalkDir::new(source).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_file() {
+                total_size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+            visited += 1;
+            if visited > 10_000 || start_time.elapsed().as_millis() > 250 {
+                return total_size;
+            }
+        }
+    }
+    total_size
+}
+
+/// Refuses to queue a copy/move that can't fit on the destination volume.
+/// Same-volume moves are plain renames and need no extra space.
+fn ensure_free_space(sources: &[PathBuf], target_dir: &std::path::Path, total_size: Option<u64>, is_move: bool) -> Result<(), CommandError> {
+    use crate::utils::hardware::{get_disk_space, volume_root};
+
+    let target_root = match volume_root(target_dir) {
+        Some(r) => r,
+        None => return Ok(()),
+    };
+    if is_move && sources.iter().all(|s| volume_root(s).is_some_and(|r| r.eq_ignore_ascii_case(&target_root))) {
+        return Ok(());
+    }
+
+    let available = match get_disk_space(target_dir) {
+        Some((free, _)) => free,
+        None => return Ok(()),
+    };
+    let required = match total_size {
+        Some(s) if s > 0 => s,
+        _ => estimate_total_size(sources),
+    };
+
+    if required > available {
+        const MB: u64 = 1024 * 1024;
+        return Err(CommandError::InsufficientSpace(format!(
+            "{} needs {} MB but only {} MB are free ({} MB short)",
+            target_root,
+            required.div_ceil(MB),
+            available / MB,
+            (required - available).div_ceil(MB)
+        )));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn copy_items(
     app: AppHandle, 
@@ -384,12 +515,15 @@ pub async fn copy_items(
     total_size: Option<u64>,
     total_files: Option<usize>,
     is_cross_volume: Option<bool>,
+    resume: Option<bool>,
 ) -> Result<String, CommandError> {
     let target_dir_validated = validate_path(&target_dir)?;
     let paths_validated: Vec<PathBuf> = paths.iter()
         .map(|p| validate_path(p))
         .collect::<Result<Vec<PathBuf>, CommandError>>()?;
 
+    ensure_free_space(&paths_validated, &target_dir_validated, total_size, false)?;
+
     let mut op = FileOperation::new(FileOpType::Copy, paths_validated, Some(target_dir_validated));
     if let Some(t) = turbo {
         op.turbo = t;
@@ -398,6 +532,7 @@ pub async fn copy_items(
     if let Some(s) = total_size { op.total_bytes = s; }
     if let Some(f) = total_files { op.total_files = f; }
     if let Some(cv) = is_cross_volume { op.is_cross_volume = cv; }
+    op.resume = resume.unwrap_or(false);
     
     let id = manager.queue_operation(app, op);
     
@@ -414,12 +549,15 @@ pub async fn move_items(
     total_size: Option<u64>,
     total_files: Option<usize>,
     is_cross_volume: Option<bool>,
+    resume: Option<bool>,
 ) -> Result<String, CommandError> {
     let target_dir_validated = validate_path(&target_dir)?;
     let paths_validated: Vec<PathBuf> = paths.iter()
         .map(|p| validate_path(p))
         .collect::<Result<Vec<PathBuf>, CommandError>>()?;
     
+    ensure_free_space(&paths_validated, &target_dir_validated, total_size, true)?;
+
     let mut op = FileOperation::new(FileOpType::Move, paths_validated, Some(target_dir_validated));
     if let Some(t) = turbo {
         op.turbo = t;
@@ -428,12 +566,86 @@ pub async fn move_items(
     if let Some(s) = total_size { op.total_bytes = s; }
     if let Some(f) = total_files { op.total_files = f; }
     if let Some(cv) = is_cross_volume { op.is_cross_volume = cv; }
+    op.resume = resume.unwrap_or(false);
     
     let id = manager.queue_operation(app, op);
 
     Ok(id)
 }
 
+/// Copies each item next to itself as "name - Copy.ext" (then " - Copy (2)", ...), as one
+/// queued copy operation recorded as a single undoable transaction.
+#[tauri::command]
+pub async fn duplicate_items(
+    app: AppHandle,
+    manager: State<'_, FileOperationManager>,
+    paths: Vec<String>,
+    turbo: Option<bool>,
+) -> Result<String, CommandError> {
+    let paths_validated: Vec<PathBuf> = paths.iter()
+        .map(|p| validate_path(p))
+        .collect::<Result<Vec<PathBuf>, CommandError>>()?;
+    let first_parent = paths_validated.first()
+        .and_then(|p| p.parent())
+        .map(|p| p.to_path_buf())
+        .ok_or(CommandError::PathError("Nothing to duplicate".to_string()))?;
+
+    let dest_paths: Vec<PathBuf> = paths_validated.iter().map(|p| crate::utils::duplicate_path(p)).collect();
+
+    ensure_free_space(&paths_validated, &first_parent, None, false)?;
+
+    let mut op = FileOperation::new(FileOpType::Copy, paths_validated, Some(first_parent));
+    op.dest_paths = Some(dest_paths);
+    if let Some(t) = turbo {
+        op.turbo = t;
+        op.turbo_flag.store(t, Ordering::Relaxed);
+    }
+
+    let id = manager.queue_operation(app, op);
+
+    Ok(id)
+}
+
+/// Creates `folder_name` next to the items (as "name (2)", ... if taken) and moves them into it
+/// as one queued move, undone as a whole. Items from several folders are grouped in the first
+/// item's folder. Returns the created folder; progress is reported through `file_op_event`.
+#[tauri::command]
+pub async fn group_into_new_folder(
+    app: AppHandle,
+    manager: State<'_, FileOperationManager>,
+    paths: Vec<String>,
+    folder_name: String,
+) -> Result<String, CommandError> {
+    let name = folder_name.trim();
+    if name.is_empty() || name == "." || name == ".." || name.contains(['\\', '/', ':', '*', '?', '"', '<', '>', '|']) {
+        return Err(CommandError::PathError(format!("Invalid folder name: {}", folder_name)));
+    }
+    let paths_validated: Vec<PathBuf> = paths.iter()
+        .map(|p| validate_path(p))
+        .collect::<Result<Vec<PathBuf>, CommandError>>()?;
+    let base_dir = paths_validated.first()
+        .and_then(|p| p.parent())
+        .map(|p| p.to_path_buf())
+        .ok_or(CommandError::PathError("Nothing to group".to_string()))?;
+
+    let folder = (1..)
+        .map(|n| match n {
+            1 => base_dir.join(name),
+            n => base_dir.join(format!("{} ({})", name, n)),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| base_dir.join(name));
+    ensure_free_space(&paths_validated, &base_dir, None, true)?;
+    std::fs::create_dir(&folder).map_err(|e| CommandError::IoError(e.to_string()))?;
+    info!("Grouping {} items into {:?}", paths_validated.len(), folder);
+
+    let mut op = FileOperation::new(FileOpType::Move, paths_validated, Some(folder.clone()));
+    op.created_dir = Some(folder.clone());
+    manager.queue_operation(app, op);
+
+    Ok(folder.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 pub async fn restore_items(paths: Vec<String>) -> Result<Vec<String>, CommandError> {
     info!("Restoring items: {:?}", paths);
@@ -683,8 +895,28 @@ pub async fn move_from_trash(app: AppHandle, paths: Vec<String>, target_dir: Str
 }
 
 #[tauri::command]
-pub fn get_history(history: State<'_, HistoryManager>) -> Result<crate::models::history::HistoryState, CommandError> {
-    Ok(history.get_state())
+pub fn get_history(history: State<'_, HistoryManager>) -> Result<crate::models::history::HistorySnapshot, CommandError> {
+    Ok(history.snapshot())
+}
+
+/// Forgets every undo and redo step.
+#[tauri::command]
+pub fn clear_history(app: AppHandle, history: State<'_, HistoryManager>) -> Result<(), CommandError> {
+    history.clear();
+    let _ = app.emit("history_update", ());
+    Ok(())
+}
+
+/// Most recent finished operations first (200 by default).
+#[tauri::command]
+pub fn get_activity_log(activity: State<'_, ActivityLog>, limit: Option<usize>) -> Vec<ActivityEntry> {
+    activity.recent(limit.unwrap_or(200))
+}
+
+/// Empties the activity log. Undo history is left alone.
+#[tauri::command]
+pub fn clear_activity_log(app: AppHandle, activity: State<'_, ActivityLog>) -> Result<(), CommandError> {
+    activity.clear(&app)
 }
 
 fn fast_trash(paths: Vec<PathBuf>) -> Result<(), CommandError> {
@@ -698,7 +930,7 @@ fn fast_trash(paths: Vec<PathBuf>) -> Result<(), CommandError> {
 
         let mut buffer: Vec<u16> = Vec::new();
         for src in paths {
-            let path_str = src.to_string_lossy().replace("/", "\\");
+            let path_str = strip_extended_length(&src.to_string_lossy()).replace("/", "\\");
             buffer.extend(path_str.encode_utf16());
             buffer.push(0);
         }
@@ -740,7 +972,13 @@ pub async fn undo_last_action(app: AppHandle, history: State<'_, HistoryManager>
         match tx.op_type {
             TransactionType::Copy => {
                 // Undo Copy = Delete the copied files at target
-                if let Some(ref target_dir) = tx.details.target_dir {
+                if let Some(ref created) = tx.details.created_files {
+                    // Copies made under another name (duplicates)
+                    let files_to_delete: Vec<PathBuf> = created.iter().map(PathBuf::from).filter(|p| p.exists()).collect();
+                    if !files_to_delete.is_empty() {
+                         fast_trash(files_to_delete)?;
+                    }
+                } else if let Some(ref target_dir) = tx.details.target_dir {
                     let mut files_to_delete = Vec::new();
                     for src_path in &tx.details.paths {
                         let path = PathBuf::from(&src_path);
@@ -789,6 +1027,10 @@ pub async fn undo_last_action(app: AppHandle, history: State<'_, HistoryManager>
                          perform_copy_with_progress(&app, files_to_copy_delete, total_size, "undo_move", true, cancel_flag)?;
                      }
                 }
+                // Folder created by "group into new folder": only removed if nothing else was put in it
+                for dir in tx.details.created_files.iter().flatten() {
+                    let _ = std::fs::remove_dir(dir);
+                }
             },
             TransactionType::Delete => {
                 // Undo Delete = Restore from Trash
@@ -804,8 +1046,8 @@ pub async fn undo_last_action(app: AppHandle, history: State<'_, HistoryManager>
                     }
                 }
             },
-            TransactionType::NewFolder => {
-                // Undo NewFolder = Delete the folder (move to trash)
+            TransactionType::NewFolder | TransactionType::NewFile => {
+                // Undo NewFolder/NewFile = Delete it (move to trash)
                 let mut files_to_delete = Vec::new();
                 for path_str in &tx.details.paths {
                     let path = PathBuf::from(path_str);
@@ -817,6 +1059,10 @@ pub async fn undo_last_action(app: AppHandle, history: State<'_, HistoryManager>
                     fast_trash(files_to_delete)?;
                 }
             },
+            TransactionType::Edit => {
+                // Undo Edit = Put the backed up content back (the edited one becomes the backup for redo)
+                swap_with_backup(tx)?;
+            },
             TransactionType::Restore => {
                 // Undo Restore = Delete the restored files (move back to trash)
                 if let Some(ref created) = tx.details.created_files {
@@ -860,7 +1106,29 @@ pub async fn redo_last_action(app: AppHandle, history: State<'_, HistoryManager>
                 }
             },
             TransactionType::Copy => {
-                if let Some(ref target_dir) = tx.details.target_dir {
+                if let Some(ref created) = tx.details.created_files {
+                    // Re-create duplicates under the names they had
+                    let mut files = Vec::new();
+                    let mut total_bytes = 0;
+                    for (src, dest) in tx.details.paths.iter().zip(created) {
+                        let (src, dest) = (PathBuf::from(src), PathBuf::from(dest));
+                        let parent = dest.parent().unwrap_or(&dest).to_path_buf();
+                        let Some(src_name) = src.file_name() else { continue; };
+                        let collected_root = parent.join(src_name);
+                        if let Ok((collected, size)) = collect_files(&[src.clone()], &parent) {
+                            // collect_files keeps the source name: swap in the duplicate's name
+                            files.extend(collected.into_iter().map(|(from, to)| match to.strip_prefix(&collected_root) {
+                                Ok(relative) if !relative.as_os_str().is_empty() => (from, dest.join(relative)),
+                                _ => (from, dest.clone()),
+                            }));
+                            total_bytes += size;
+                        }
+                    }
+                    if !files.is_empty() {
+                        let cancel_flag = Arc::new(AtomicBool::new(false));
+                        perform_copy_with_progress(&app, files, total_bytes, "redo_copy", false, cancel_flag)?;
+                    }
+                } else if let Some(ref target_dir) = tx.details.target_dir {
                     // Re-execute Copy
                     // Collect files from Source (paths) to Target
                      let target_base = PathBuf::from(target_dir);
@@ -872,6 +1140,9 @@ pub async fn redo_last_action(app: AppHandle, history: State<'_, HistoryManager>
                 }
             },
             TransactionType::Move => {
+                for dir in tx.details.created_files.iter().flatten() {
+                    std::fs::create_dir_all(dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+                }
                 if let Some(ref target_dir) = tx.details.target_dir {
                      // Re-execute Move
                      let mut files_to_copy_delete = Vec::new();
@@ -916,6 +1187,13 @@ pub async fn redo_last_action(app: AppHandle, history: State<'_, HistoryManager>
                     }
                 }
             },
+            TransactionType::NewFile => {
+                // Redo NewFile = Bring the file back from the Recycle Bin, its content isn't kept here
+                restore_items(tx.details.paths.clone()).await?;
+            },
+            TransactionType::Edit => {
+                swap_with_backup(tx)?;
+            },
             TransactionType::NewFolder => {
                 // Redo NewFolder = Re-create the folder
                 for path_str in &tx.details.paths {
@@ -934,6 +1212,18 @@ pub async fn redo_last_action(app: AppHandle, history: State<'_, HistoryManager>
     Ok(transaction)
 }
 
+/// Exchanges the content of an edited file with its backup, so the same call undoes and redoes.
+fn swap_with_backup(tx: &Transaction) -> Result<(), CommandError> {
+    let (Some(path), Some(backup)) = (tx.details.paths.first(), tx.details.old_path.as_ref()) else {
+        return Ok(());
+    };
+    let current = std::fs::read(path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let previous = std::fs::read(backup).map_err(|e| CommandError::IoError(format!("Backup is gone: {}", e)))?;
+    std::fs::write(path, previous).map_err(|e| CommandError::IoError(e.to_string()))?;
+    std::fs::write(backup, current).map_err(|e| CommandError::IoError(e.to_string()))?;
+    Ok(())
+}
+
 // Helper to collect files and calculate size recursively
 fn collect_files(paths: &[PathBuf], target_base: &std::path::Path) -> Result<(Vec<(PathBuf, PathBuf)>, u64), CommandError> {
     use walkdir::WalkDir;
@@ -947,11 +1237,12 @@ fn collect_files(paths: &[PathBuf], target_base: &std::path::Path) -> Result<(Ve
         let dest_root = target_base.join(file_name);
 
         if path.is_dir() {
-            for entry in WalkDir::new(path) {
+            let root = extended_path(path);
+            for entry in WalkDir::new(&root) {
                 let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
                 let entry_path = entry.path();
                 
-                let relative = entry_path.strip_prefix(path).map_err(|_| CommandError::PathError("Strip prefix failed".to_string()))?;
+                let relative = entry_path.strip_prefix(&root).map_err(|_| CommandError::PathError("Strip prefix failed".to_string()))?;
                 let dest_path = dest_root.join(relative);
 
                 if entry_path.is_dir() {
@@ -987,8 +1278,12 @@ fn perform_copy_with_progress(
     let mut processed_global: u64 = 0;
     let mut last_emit = Instant::now();
     let op_id = format!("{}_op", task_name);
+    let buffer_size = app.try_state::<crate::models::ConfigManager>()
+        .and_then(|c| c.0.lock().ok().map(|cfg| cfg.copy_buffer_bytes(false)))
+        .unwrap_or(512 * 1024);
 
     for (source, dest) in &files {
+         let (source, dest) = (&long_path(source), &long_path(dest));
          if cancel_flag.load(Ordering::Relaxed) {
              let _ = app.emit("progress", ProgressEvent {
                 id: op_id.clone(),
@@ -1018,7 +1313,7 @@ fn perform_copy_with_progress(
          let mut file_in = fs::File::open(&source).map_err(|e| CommandError::IoError(e.to_string()))?;
          let mut file_out = fs::File::create(&dest).map_err(|e| CommandError::IoError(e.to_string()))?;
          
-         let mut buffer = [0u8; 81920]; 
+         let mut buffer = vec![0u8; buffer_size];
          loop {
              if cancel_flag.load(Ordering::Relaxed) {
                  // Clean up partial destination file to avoid leaving corrupted data
@@ -8,12 +8,16 @@ use std::time::SystemTime;
 use walkdir::WalkDir;
 use tauri::{AppHandle, State, Emitter, Manager};
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read};
-use crate::utils::archive::{ArchiveFormat, is_archive};
+use crate::utils::archive::{ArchiveFormat, detect_format, is_archive};
 use crate::utils::hardware::{get_physical_disk_id, is_ssd};
+use crate::utils::IgnoreList;
+use crate::utils::file_category::FileCategory;
+use crate::utils::text_encoding;
+use crate::systems::search_index::{SearchIndex, SearchIndexManager};
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use iso9660_core::iso9660entry::{IsISO9660Record, ISO9660Record};
@@ -31,7 +35,7 @@ struct SearchEvent {
 }
 
 #[derive(Clone)]
-enum SearchPattern {
+pub(crate) enum SearchPattern {
     Glob(Pattern, bool),    // (pattern, ignore_accents)
     Regex(Regex, bool),   // (regex, ignore_accents)
     Literal(String, bool, bool), // (query, case_sensitive, ignore_accents)
@@ -39,7 +43,34 @@ enum SearchPattern {
 
 static DISK_IO_LOCKS: Lazy<DashMap<u64, Arc<Mutex<()>>>> = Lazy::new(|| DashMap::new());
 impl SearchPattern {
-    fn matches(&self, text: &str) -> bool {
+    /// Case- and accent-insensitive name pattern for the panel quick filter:
+    /// a glob when `query` contains `*` or `?`, a substring match otherwise.
+    pub(crate) fn name_filter(query: &str) -> Self {
+        if query.contains('*') || query.contains('?') {
+            if let Ok(p) = Pattern::new(&crate::utils::remove_accents(query).to_lowercase()) {
+                return SearchPattern::Glob(p, true);
+            }
+        }
+        SearchPattern::Literal(query.to_string(), false, true)
+    }
+
+    /// Whole-name pattern for selecting entries: a regex when `regex` is set, a glob otherwise
+    /// (so `report` only matches a file named exactly that). Both are case-insensitive.
+    pub(crate) fn selection(pattern: &str, regex: bool, ignore_accents: bool) -> Result<Self, String> {
+        let pattern_str = if ignore_accents { crate::utils::remove_accents(pattern) } else { pattern.to_string() };
+        if regex {
+            let r = RegexBuilder::new(&pattern_str)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("Invalid regex: {}", e))?;
+            Ok(SearchPattern::Regex(r, ignore_accents))
+        } else {
+            let p = Pattern::new(&pattern_str.to_lowercase()).map_err(|e| format!("Invalid pattern: {}", e))?;
+            Ok(SearchPattern::Glob(p, ignore_accents))
+        }
+    }
+
+    pub(crate) fn matches(&self, text: &str) -> bool {
         match self {
             SearchPattern::Glob(p, ia) => {
                 let target = if *ia { crate::utils::remove_accents(text).to_lowercase() } else { text.to_lowercase() };
@@ -69,21 +100,59 @@ impl SearchPattern {
     }
 }
 
+/// Which kind of entries a search returns, from `start_search`'s `kind_filter`.
+#[derive(Clone, Copy, PartialEq)]
+enum KindFilter {
+    Both,
+    Files,
+    Folders,
+}
+
+impl KindFilter {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.unwrap_or("both") {
+            "both" => Ok(KindFilter::Both),
+            "files" => Ok(KindFilter::Files),
+            "folders" => Ok(KindFilter::Folders),
+            other => Err(format!("Invalid kind filter: {}", other)),
+        }
+    }
+
+    fn allows(self, is_dir: bool) -> bool {
+        match self {
+            KindFilter::Both => true,
+            KindFilter::Files => !is_dir,
+            KindFilter::Folders => is_dir,
+        }
+    }
+}
+
 struct SearchParams {
     pattern: SearchPattern,
+    kind: KindFilter,
+    /// When not empty, only files in one of these categories match
+    categories: Vec<FileCategory>,
     min_size: Option<u64>,
     max_size: Option<u64>,
     min_date: Option<u64>,
     max_date: Option<u64>,
 }
 
+impl SearchParams {
+    /// Kind and category rules, checked before the size, date and content filters.
+    fn accepts_kind(&self, name: &str, is_dir: bool) -> bool {
+        self.kind.allows(is_dir)
+            && (self.categories.is_empty() || (!is_dir && self.categories.iter().any(|c| c.contains(name))))
+    }
+}
+
 fn search_in_archive(
     archive_path: &std::path::Path,
     params: &SearchParams,
     cancellation: &Arc<AtomicBool>,
 ) -> Vec<FileEntry> {
     let mut results = Vec::new();
-    let format = match ArchiveFormat::from_path(archive_path) {
+    let format = match detect_format(archive_path) {
         Some(f) => f,
         None => return results,
     };
@@ -103,6 +172,8 @@ fn search_in_archive(
                                 let is_dir = file.is_dir();
                                 let size = if is_dir { 0 } else { file.size() };
                                 
+                                if !params.accepts_kind(last_part, is_dir) { continue; }
+
                                 // Basic size filter
                                 if !is_dir {
                                     if let Some(min) = params.min_size { if size < min { continue; } }
@@ -149,6 +220,8 @@ fn search_in_archive(
                                 let is_dir = entry.is_directory();
                                 let size = entry.size();
 
+                                if !params.accepts_kind(last_part, is_dir) { return Ok(true); }
+
                                 if !is_dir {
                                     if let Some(min) = params.min_size { if size < min { return Ok(true); } }
                                     if let Some(max) = params.max_size { if size > max { return Ok(true); } }
@@ -196,6 +269,8 @@ fn search_in_archive(
                                 let is_dir = entry.header().entry_type().is_dir();
                                 let size = entry.header().size().unwrap_or(0);
 
+                                if !params.accepts_kind(last_part, is_dir) { continue; }
+
                                 if !is_dir {
                                     if let Some(min) = params.min_size { if size < min { continue; } }
                                     if let Some(max) = params.max_size { if size > max { continue; } }
@@ -277,6 +352,7 @@ fn search_in_iso(
             };
 
             // Filters
+            if !params.accepts_kind(display_name, is_dir) { continue; }
             if !is_dir {
                 if let Some(min) = params.min_size { if size < min { continue; } }
                 if let Some(max) = params.max_size { if size > max { continue; } }
@@ -318,25 +394,19 @@ fn is_binary_file(path: &std::path::Path) -> bool {
         Err(_) => return true,
     };
 
-    let mut buffer = [0u8; 1024];
+    let mut buffer = [0u8; text_encoding::SNIFF_LEN];
     match file.read(&mut buffer) {
         Ok(n) => {
-            buffer[..n].iter().any(|&b| b == 0)
+            // UTF-16 text is full of NUL bytes but isn't binary
+            !text_encoding::is_utf16(&buffer[..n]) && buffer[..n].iter().any(|&b| b == 0)
         }
         Err(_) => true,
     }
 }
 
-fn office_file_contains_content(path: &std::path::Path, pattern: &Regex, ignore_accents: bool) -> bool {
-    let file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
-    };
-
-    let mut archive = match zip::ZipArchive::new(file) {
-        Ok(a) => a,
-        Err(_) => return false,
-    };
+fn office_file_contains_content(path: &std::path::Path, pattern: &Regex, ignore_accents: bool) -> Option<ContentMatch> {
+    let file = File::open(path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
 
     // Target files for content
     // docx: word/document.xml
@@ -387,22 +457,21 @@ fn office_file_contains_content(path: &std::path::Path, pattern: &Regex, ignore_
                     }
                 }
 
-                let check_text = if ignore_accents { crate::utils::remove_accents(&stripped) } else { stripped };
-                for line in check_text.lines() {
-                    if pattern.is_match(line) {
-                        return true;
-                    }
+                let found = stripped.lines().enumerate()
+                    .find_map(|(i, line)| match_line(i, line, pattern, ignore_accents));
+                if found.is_some() {
+                    return found;
                 }
             }
         }
     }
 
-    false
+    None
 }
 
-fn file_contains_content(path: &std::path::Path, pattern: &Regex, ignore_accents: bool, ssd_hint: bool) -> bool {
+fn file_contains_content(path: &std::path::Path, pattern: &Regex, ignore_accents: bool, ssd_hint: bool) -> Option<ContentMatch> {
     if is_binary_file(path) {
-        return false;
+        return None;
     }
 
     let is_office = is_office_doc(path);
@@ -426,157 +495,230 @@ fn file_contains_content(path: &std::path::Path, pattern: &Regex, ignore_accents
     }
 }
 
-fn read_file_and_check(path: &std::path::Path, pattern: &Regex, ignore_accents: bool) -> bool {
-    let file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
-    };
-    
-    let reader = BufReader::new(file);
-    for line in reader.lines().map_while(Result::ok) {
-        if ignore_accents {
-            if pattern.is_match(&crate::utils::remove_accents(&line)) {
-                return true;
-            }
-        } else {
-            if pattern.is_match(&line) {
-                return true;
+fn read_file_and_check(path: &std::path::Path, pattern: &Regex, ignore_accents: bool) -> Option<ContentMatch> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+    if text_encoding::is_utf16(reader.fill_buf().ok()?) {
+        return utf16_file_check(reader, pattern, ignore_accents);
+    }
+
+    reader.lines().map_while(Result::ok).enumerate().find_map(|(i, line)| {
+        let line = if i == 0 { line.trim_start_matches('\u{feff}') } else { line.as_str() };
+        match_line(i, line, pattern, ignore_accents)
+    })
+}
+
+/// `read_file_and_check` for UTF-16 text, which can't be split into lines before decoding:
+/// decoded one buffer at a time, matching each complete line as it comes.
+fn utf16_file_check(mut reader: BufReader<File>, pattern: &Regex, ignore_accents: bool) -> Option<ContentMatch> {
+    let (encoding, bom_len) = text_encoding::detect(reader.fill_buf().ok()?)?;
+    reader.consume(bom_len);
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut pending = String::new();
+    let mut index = 0;
+
+    loop {
+        let bytes = reader.fill_buf().ok()?;
+        let last = bytes.is_empty();
+        pending.reserve(decoder.max_utf8_buffer_length(bytes.len())?);
+        let (_, read, _) = decoder.decode_to_string(bytes, &mut pending, last);
+        reader.consume(read);
+
+        // Complete lines are matched and dropped; a partial one waits for the next buffer
+        let mut start = 0;
+        while let Some(end) = pending[start..].find('\n') {
+            let line = &pending[start..start + end];
+            if let Some(found) = match_line(index, line.strip_suffix('\r').unwrap_or(line), pattern, ignore_accents) {
+                return Some(found);
             }
+            index += 1;
+            start += end + 1;
+        }
+        pending.drain(..start);
+
+        if last {
+            return if pending.is_empty() { None } else { match_line(index, &pending, pattern, ignore_accents) };
         }
     }
-    false
 }
 
+/// Longest `match_context` snippet, in characters.
+const MAX_SNIPPET_CHARS: usize = 160;
+/// Characters kept before the match when a long line has to be cut.
+const SNIPPET_LEAD_CHARS: usize = 40;
 
-#[tauri::command]
-pub async fn start_search(
-    app: AppHandle,
-    state: State<'_, SessionManager>,
-    config_state: State<'_, ConfigManager>,
+/// First place a content search matched in a file, shown as a grep-like snippet.
+struct ContentMatch {
+    line: u32,
+    context: String,
+}
+
+/// `line` (the `index`-th, 0-based) as a `ContentMatch` if `pattern` matches it. Long lines
+/// are cut around the match; with `ignore_accents` the match position isn't known, so they
+/// are cut from the start.
+fn match_line(index: usize, line: &str, pattern: &Regex, ignore_accents: bool) -> Option<ContentMatch> {
+    let start = if ignore_accents {
+        pattern.is_match(&crate::utils::remove_accents(line)).then_some(0)?
+    } else {
+        pattern.find(line)?.start()
+    };
+
+    let lead = line[..start].chars().rev().take(SNIPPET_LEAD_CHARS).map(char::len_utf8).sum::<usize>();
+    let from = start - lead;
+    let text = line[from..].trim_end();
+    let mut context: String = text.chars().take(MAX_SNIPPET_CHARS).collect();
+    if context.len() < text.len() {
+        context.push('…');
+    }
+    if from > 0 {
+        context.insert(0, '…');
+    }
+    Some(ContentMatch { line: index as u32 + 1, context: context.trim_start().to_string() })
+}
+
+
+/// Shared, read-only settings for the walker threads of one search.
+struct SearchWorker {
+    app_handle: AppHandle,
     panel_id: String,
-    query: String,
-    search_root: Option<String>,
-    regex: Option<bool>,
-    case_sensitive: Option<bool>,
-    recursive: Option<bool>,
-    min_size: Option<u64>,
-    max_size: Option<u64>,
-    min_date: Option<u64>,
-    max_date: Option<u64>,
-    content_query: Option<String>,
-    content_regex: Option<bool>,
-    ignore_accents: Option<bool>,
-    search_in_archives: Option<bool>
-) -> Result<(), String> {
-    let cancellation = Arc::new(AtomicBool::new(false));
-    let cancel_thread = cancellation.clone();
+    params: Arc<SearchParams>,
+    content_pattern: Option<Regex>,
+    ignore_accents: bool,
+    is_turbo: bool,
+    show_hidden: bool,
+    show_system: bool,
+    recursive: bool,
+    search_archives: bool,
+    search_limit: usize,
+    ignore: IgnoreList,
+    /// Results found so far across all roots, to enforce `search_limit` globally
+    found: AtomicUsize,
+    cancel: Arc<AtomicBool>,
+}
 
-    // 1. Setup Session Context
-    let root_path = {
-        let mut session = state.0.lock().map_err(|e| e.to_string())?;
-        let panel = if panel_id == "left" { &mut session.left_panel } else { &mut session.right_panel };
-        
-        let mut path_to_search = if let Some(root) = search_root {
-            std::path::PathBuf::from(root)
-        } else {
-            panel.tabs.iter()
-                .find(|t| t.id == panel.active_tab_id)
-                .map(|t| t.path.clone())
-                .unwrap_or_else(|| std::path::PathBuf::from("C:\\"))
-        };
+impl SearchWorker {
+    /// Reserves a result slot; returns false once the global limit is reached.
+    fn reserve_slot(&self) -> bool {
+        self.found.fetch_add(1, Ordering::Relaxed) < self.search_limit
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.found.load(Ordering::Relaxed) >= self.search_limit
+    }
 
-        if path_to_search.is_absolute() && path_to_search.to_string_lossy().len() == 2 && path_to_search.to_string_lossy().ends_with(':') {
-            path_to_search = std::path::PathBuf::from(format!("{}\\", path_to_search.to_string_lossy()));
+    /// Kind, category, size, date and folder rules shared by the live walk and the index lookup.
+    fn passes_filters(&self, name: &str, is_dir: bool, size: u64, modified: u64) -> bool {
+        let search_params = &self.params;
+        if !search_params.accepts_kind(name, is_dir) {
+            return false;
+        }
+        // Folders have no size of their own, so size filters rule them out, unless only
+        // folders were asked for: then the size filters can't apply and are ignored
+        let size_filtered = search_params.kind != KindFilter::Folders
+            && (search_params.min_size.is_some() || search_params.max_size.is_some());
+        if is_dir && (size_filtered || self.content_pattern.is_some()) {
+            return false;
         }
 
-        panel.search_context = Some(SearchContext {
-            query: query.clone(),
-            results: Vec::new(),
-            is_searching: true,
-            cancellation_token: Some(cancellation),
+        if !is_dir {
+            if let Some(min) = search_params.min_size { if size < min { return false; } }
+            if let Some(max) = search_params.max_size { if size > max { return false; } }
+        }
+
+        if let Some(min) = search_params.min_date { if modified < min { return false; } }
+        if let Some(max) = search_params.max_date { if modified > max { return false; } }
+        true
+    }
+
+    fn emit_batch(&self, results: &[FileEntry]) {
+        let _ = self.app_handle.emit("search_event", SearchEvent {
+            panel_id: self.panel_id.clone(),
+            results: results.to_vec(),
+            completed: false
         });
-        
-        path_to_search
-    };
+    }
 
-    info!("Starting advanced search in {:?} for '{}'", root_path, query);
-    
-    if !root_path.exists() {
-        return Err(format!("Search root path does not exist: {:?}", root_path));
+    /// Answers the search from a filename index. Returns None if the index can't be read,
+    /// in which case the caller falls back to a live walk.
+    fn search_index(&self, index: &SearchIndex, root_path: &std::path::Path) -> Option<Vec<FileEntry>> {
+        let mut total_results = Vec::new();
+        let mut batch_start_idx: usize = 0;
+        let mut last_emit = std::time::Instant::now();
+
+        let outcome = index.for_each_row(root_path, |row| {
+            if self.cancel.load(Ordering::Relaxed) || self.limit_reached() { return false; }
+            if !self.show_hidden && (row.is_hidden || row.under_hidden) { return true; }
+            if !self.show_system && row.is_system { return true; }
+            if self.ignore.excludes(root_path, std::path::Path::new(&row.path), row.is_dir) { return true; }
+            if !self.params.pattern.matches(row.name()) { return true; }
+            if !self.passes_filters(row.name(), row.is_dir, row.size, row.modified) { return true; }
+            if !self.reserve_slot() { return false; }
+
+            total_results.push(FileEntry {
+                name: row.name().to_string(),
+                is_dir: row.is_dir,
+                is_hidden: row.is_hidden,
+                is_system: row.is_system,
+                size: row.size,
+                modified: row.modified,
+                path: row.path,
+                ..Default::default()
+            });
+
+            let batch_len = total_results.len() - batch_start_idx;
+            if batch_len >= 500 || last_emit.elapsed().as_millis() > 500 {
+                self.emit_batch(&total_results[batch_start_idx..]);
+                batch_start_idx = total_results.len();
+                last_emit = std::time::Instant::now();
+            }
+            true
+        });
+
+        if let Err(e) = outcome {
+            // Nothing reached the UI yet: drop the partial results and walk instead. Past the first
+            // batch they are kept, so `found` still counts them.
+            if batch_start_idx == 0 {
+                log::warn!("Search index unreadable for {:?}, walking instead: {}", root_path, e);
+                self.found.fetch_sub(total_results.len(), Ordering::Relaxed);
+                return None;
+            }
+            log::warn!("Search index for {:?} failed midway, keeping partial results: {}", root_path, e);
+        }
+
+        if batch_start_idx < total_results.len() {
+            self.emit_batch(&total_results[batch_start_idx..]);
+        }
+        Some(total_results)
     }
 
-    // 2. Prep Patterns and Filters
-    let is_regex = regex.unwrap_or(false);
-    let is_case_sensitive = case_sensitive.unwrap_or(false);
-    
-    let should_ignore_accents = ignore_accents.unwrap_or(false);
-    
-    let search_pattern = if is_regex {
-        let pattern_str = if should_ignore_accents { crate::utils::remove_accents(&query) } else { query.clone() };
-        let r = RegexBuilder::new(&pattern_str)
-            .case_insensitive(!is_case_sensitive)
-            .build()
-            .map_err(|e| format!("Invalid regex: {}", e))?;
-        SearchPattern::Regex(r, should_ignore_accents)
-    } else if query.contains('*') || query.contains('?') {
-        let pattern_str = if should_ignore_accents { crate::utils::remove_accents(&query).to_lowercase() } else { query.to_lowercase() };
-        let p = Pattern::new(&pattern_str).map_err(|e| e.to_string())?;
-        SearchPattern::Glob(p, should_ignore_accents)
-    } else {
-        SearchPattern::Literal(query.clone(), is_case_sensitive, should_ignore_accents)
-    };
+    /// Walks a single root, streaming batches through `search_event`, and returns its results.
+    /// A fresh filename index is used instead when the search doesn't need file contents.
+    fn search_root(&self, root_path: &std::path::Path) -> Vec<FileEntry> {
+        if self.recursive && self.content_pattern.is_none() && !self.search_archives {
+            let index = self.app_handle.try_state::<SearchIndexManager>().and_then(|m| m.find(root_path));
+            if let Some(index) = index {
+                info!("Searching {:?} from index {:?}", root_path, index.root);
+                if let Some(results) = self.search_index(&index, root_path) {
+                    return results;
+                }
+            }
+        }
 
-    let content_regex_pattern = if let Some(cq) = content_query {
-        let is_content_regex = content_regex.unwrap_or(false);
-        let pattern = if is_content_regex {
-            cq
-        } else {
-            regex::escape(&cq)
-        };
-        Some(RegexBuilder::new(&pattern)
-            .case_insensitive(!is_case_sensitive)
-            .build()
-            .map_err(|e| format!("Invalid content pattern: {}", e))?)
-    } else {
-        None
-    };
+        let is_target_ssd = is_ssd(root_path);
+        let search_params = &self.params;
+        let show_hidden = self.show_hidden;
+        let show_system = self.show_system;
+        let ignore = &self.ignore;
 
-    // 3. Spawn Thread
-    let panel_id_clone = panel_id.clone();
-    let app_handle = app.clone();
-    let (search_limit, is_turbo, show_hidden, show_system) = {
-        let config = config_state.0.lock().unwrap();
-        (
-            config.search_limit as usize, 
-            config.default_turbo_mode,
-            config.show_hidden,
-            config.show_system
-        )
-    };
-    let is_recursive = recursive.unwrap_or(true);
-    let should_search_archives = search_in_archives.unwrap_or(false);
-    
-    let search_params = Arc::new(SearchParams {
-        pattern: search_pattern,
-        min_size,
-        max_size,
-        min_date,
-        max_date,
-    });
-    
-    let root_path_for_hardware = root_path.clone();
-    thread::spawn(move || {
-        let is_target_ssd = is_ssd(&root_path_for_hardware);
-        
         #[cfg(target_os = "windows")]
-        if !is_turbo {
+        if !self.is_turbo {
             unsafe {
                 let _ = SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_BEGIN);
             }
         }
 
-        let mut walker = WalkDir::new(&root_path);
-        if !is_recursive {
+        let mut walker = WalkDir::new(root_path);
+        if !self.recursive {
             walker = walker.max_depth(1);
         }
         
@@ -587,6 +729,9 @@ pub async fn start_search(
 
         let filtered_walker = walker.into_iter().filter_entry(move |e| {
             if e.depth() == 0 { return true; }
+            if e.file_type().is_dir() && ignore.matches(&e.file_name().to_string_lossy()) {
+                return false;
+            }
             // If the user wants to see hidden files, we don't prune hidden directories
             if !show_hidden {
                 if let Ok(metadata) = e.metadata() {
@@ -599,11 +744,11 @@ pub async fn start_search(
         });
 
         for entry in filtered_walker.filter_map(|e| e.ok()) {
-            if cancel_thread.load(Ordering::Relaxed) { break; }
+            if self.cancel.load(Ordering::Relaxed) || self.limit_reached() { break; }
             if entry.depth() == 0 { continue; } // Skip the root directory itself
             
             files_processed += 1;
-            if !is_turbo && files_processed % 100 == 0 {
+            if !self.is_turbo && files_processed % 100 == 0 {
                 std::thread::sleep(std::time::Duration::from_millis(1));
             }
 
@@ -631,30 +776,25 @@ pub async fn start_search(
                     if !show_hidden && is_hidden_attr { continue; }
                     if !show_system && is_system_attr { continue; }
 
-                    if is_dir && (search_params.min_size.is_some() || search_params.max_size.is_some() || content_regex_pattern.is_some()) {
-                        continue;
-                    }
-
-                    // 2. Size Filter
-                    if !is_dir {
-                        let size = metadata.len();
-                        if let Some(min) = search_params.min_size { if size < min { continue; } }
-                        if let Some(max) = search_params.max_size { if size > max { continue; } }
-                    }
-
-                    // 3. Date Filter
+                    // 2-3. Size & Date Filters
                     let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)
                         .duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
-                    if let Some(min) = search_params.min_date { if modified < min { continue; } }
-                    if let Some(max) = search_params.max_date { if modified > max { continue; } }
+                    if !self.passes_filters(&name, is_dir, metadata.len(), modified) { continue; }
 
                     // 4. Content Filter
-                    if let Some(ref c_reg) = content_regex_pattern {
-                        if is_dir || !file_contains_content(path, c_reg, should_ignore_accents, is_target_ssd) {
-                            continue;
+                    let content_match = match &self.content_pattern {
+                        Some(c_reg) => {
+                            if is_dir { continue; }
+                            match file_contains_content(path, c_reg, self.ignore_accents, is_target_ssd) {
+                                Some(found) => Some(found),
+                                None => continue,
+                            }
                         }
-                    }
+                        None => None,
+                    };
+                    let (line_number, snippet) = content_match.map(|m| (m.line, m.context)).unzip();
 
+                    if !self.reserve_slot() { break; }
                     total_results.push(FileEntry {
                         name,
                         path: path.to_string_lossy().to_string(),
@@ -670,35 +810,33 @@ pub async fn start_search(
                         is_protected: false,
                         original_path: None,
                         deleted_time: None,
+                        group_key: None,
+                        is_favorite: false,
+                        match_context: snippet,
+                        match_line: line_number,
                     });
-
-                    if total_results.len() >= search_limit { break; }
                 }
             }
             
             // 5. Archive Search (independent of filename match)
-            if should_search_archives && is_archive(path) {
+            if self.search_archives && is_archive(path) {
                 if let Ok(metadata) = entry.metadata() {
                     if !metadata.is_dir() {
-                        let internal_results = search_in_archive(path, &search_params, &cancel_thread);
+                        let internal_results = search_in_archive(path, search_params, &self.cancel);
                         for res in internal_results {
+                            if !self.reserve_slot() { break; }
                             total_results.push(res);
-                            if total_results.len() >= search_limit { break; }
                         }
                     }
                 }
             }
 
-            if total_results.len() >= search_limit { break; }
+            if self.limit_reached() { break; }
 
             // Emit batch using index slice (no per-item clone)
             let batch_len = total_results.len() - batch_start_idx;
             if (batch_len >= 500 || last_emit.elapsed().as_millis() > 500) && batch_len > 0 {
-                let _ = app_handle.emit("search_event", SearchEvent {
-                    panel_id: panel_id_clone.clone(),
-                    results: total_results[batch_start_idx..].to_vec(),
-                    completed: false
-                });
+                self.emit_batch(&total_results[batch_start_idx..]);
                 batch_start_idx = total_results.len();
                 last_emit = std::time::Instant::now();
             }
@@ -706,13 +844,177 @@ pub async fn start_search(
 
         // Emit remaining unsent results before moving into session
         if batch_start_idx < total_results.len() {
-            let _ = app_handle.emit("search_event", SearchEvent {
-                panel_id: panel_id_clone.clone(),
-                results: total_results[batch_start_idx..].to_vec(),
-                completed: false
-            });
+            self.emit_batch(&total_results[batch_start_idx..]);
+        }
+
+        #[cfg(target_os = "windows")]
+        if !self.is_turbo {
+            unsafe {
+                let _ = SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_END);
+            }
         }
 
+        total_results
+    }
+}
+
+/// Turns "C:" into "C:\\" so drive roots are walked from their root directory.
+fn normalize_search_root(path: std::path::PathBuf) -> std::path::PathBuf {
+    std::path::PathBuf::from(crate::utils::path_security::normalize_drive_root(&path.to_string_lossy()))
+}
+
+#[tauri::command]
+pub async fn start_search(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    config_state: State<'_, ConfigManager>,
+    panel_id: String,
+    query: String,
+    search_root: Option<String>,
+    search_roots: Option<Vec<String>>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    recursive: Option<bool>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    min_date: Option<u64>,
+    max_date: Option<u64>,
+    content_query: Option<String>,
+    content_regex: Option<bool>,
+    ignore_accents: Option<bool>,
+    search_in_archives: Option<bool>,
+    kind_filter: Option<String>,
+    category_filter: Option<Vec<String>>,
+) -> Result<(), String> {
+    let kind = KindFilter::parse(kind_filter.as_deref())?;
+    let categories = category_filter.unwrap_or_default().iter()
+        .map(|c| FileCategory::parse(c).ok_or_else(|| format!("Unknown file category: {}", c)))
+        .collect::<Result<Vec<_>, String>>()?;
+    if kind == KindFilter::Folders && content_query.is_some() {
+        return Err("Content search only finds files and can't be combined with the folders filter".to_string());
+    }
+    let cancellation = Arc::new(AtomicBool::new(false));
+    let cancel_thread = cancellation.clone();
+
+    // 1. Setup Session Context
+    let root_paths: Vec<std::path::PathBuf> = {
+        let mut session = state.0.lock().map_err(|e| e.to_string())?;
+        let panel = if panel_id == "left" { &mut session.left_panel } else { &mut session.right_panel };
+        
+        let paths_to_search: Vec<std::path::PathBuf> = match (search_roots, search_root) {
+            (Some(roots), _) if !roots.is_empty() => roots.into_iter().map(std::path::PathBuf::from).collect(),
+            (_, Some(root)) => vec![std::path::PathBuf::from(root)],
+            _ => vec![panel.tabs.iter()
+                .find(|t| t.id == panel.active_tab_id)
+                .map(|t| t.path.clone())
+                .unwrap_or_else(|| std::path::PathBuf::from("C:\\"))],
+        };
+
+        panel.search_context = Some(SearchContext {
+            query: query.clone(),
+            results: Vec::new(),
+            is_searching: true,
+            cancellation_token: Some(cancellation),
+        });
+        
+        paths_to_search.into_iter().map(normalize_search_root).collect()
+    };
+
+    info!("Starting advanced search in {:?} for '{}'", root_paths, query);
+    
+    if let Some(missing) = root_paths.iter().find(|p| !p.exists()) {
+        return Err(format!("Search root path does not exist: {:?}", missing));
+    }
+
+    // 2. Prep Patterns and Filters
+    let is_regex = regex.unwrap_or(false);
+    let is_case_sensitive = case_sensitive.unwrap_or(false);
+    
+    let should_ignore_accents = ignore_accents.unwrap_or(false);
+    
+    let search_pattern = if is_regex {
+        let pattern_str = if should_ignore_accents { crate::utils::remove_accents(&query) } else { query.clone() };
+        let r = RegexBuilder::new(&pattern_str)
+            .case_insensitive(!is_case_sensitive)
+            .build()
+            .map_err(|e| format!("Invalid regex: {}", e))?;
+        SearchPattern::Regex(r, should_ignore_accents)
+    } else if query.contains('*') || query.contains('?') {
+        let pattern_str = if should_ignore_accents { crate::utils::remove_accents(&query).to_lowercase() } else { query.to_lowercase() };
+        let p = Pattern::new(&pattern_str).map_err(|e| e.to_string())?;
+        SearchPattern::Glob(p, should_ignore_accents)
+    } else {
+        SearchPattern::Literal(query.clone(), is_case_sensitive, should_ignore_accents)
+    };
+
+    let content_regex_pattern = if let Some(cq) = content_query {
+        let is_content_regex = content_regex.unwrap_or(false);
+        let pattern = if is_content_regex {
+            cq
+        } else {
+            regex::escape(&cq)
+        };
+        Some(RegexBuilder::new(&pattern)
+            .case_insensitive(!is_case_sensitive)
+            .build()
+            .map_err(|e| format!("Invalid content pattern: {}", e))?)
+    } else {
+        None
+    };
+
+    // 3. Spawn Threads (one walker per root)
+    let (search_limit, is_turbo, show_hidden, show_system, ignore) = {
+        let config = config_state.0.lock().unwrap();
+        (
+            config.search_limit as usize, 
+            config.default_turbo_mode,
+            config.show_hidden,
+            config.show_system,
+            IgnoreList::new(&config.ignore_patterns)
+        )
+    };
+    
+    let worker = Arc::new(SearchWorker {
+        app_handle: app.clone(),
+        panel_id: panel_id.clone(),
+        params: Arc::new(SearchParams {
+            pattern: search_pattern,
+            kind,
+            categories,
+            min_size,
+            max_size,
+            min_date,
+            max_date,
+        }),
+        content_pattern: content_regex_pattern,
+        ignore_accents: should_ignore_accents,
+        is_turbo,
+        show_hidden,
+        show_system,
+        recursive: recursive.unwrap_or(true),
+        search_archives: search_in_archives.unwrap_or(false),
+        search_limit,
+        ignore,
+        found: AtomicUsize::new(0),
+        cancel: cancel_thread,
+    });
+
+    thread::spawn(move || {
+        let handles: Vec<_> = root_paths.into_iter().map(|root| {
+            let worker = worker.clone();
+            thread::spawn(move || worker.search_root(&root))
+        }).collect();
+
+        let mut total_results: Vec<FileEntry> = Vec::new();
+        for handle in handles {
+            if let Ok(results) = handle.join() {
+                total_results.extend(results);
+            }
+        }
+
+        let app_handle = worker.app_handle.clone();
+        let panel_id_clone = worker.panel_id.clone();
+
         // Save results to session
         if let Some(state_manager) = app_handle.try_state::<SessionManager>() {
             if let Ok(mut session) = state_manager.0.lock() {
@@ -730,13 +1032,6 @@ pub async fn start_search(
         }
 
         // Signal search completion
-        #[cfg(target_os = "windows")]
-        if !is_turbo {
-            unsafe {
-                let _ = SetThreadPriority(GetCurrentThread(), THREAD_MODE_BACKGROUND_END);
-            }
-        }
-
         let _ = app_handle.emit("search_event", SearchEvent {
             panel_id: panel_id_clone,
             results: Vec::new(),
@@ -764,3 +1059,111 @@ pub async fn cancel_search(
     }
     Ok(())
 }
+
+#[derive(serde::Serialize)]
+struct SearchExportRow<'a> {
+    name: &'a str,
+    path: &'a str,
+    size: u64,
+    modified: String,
+    is_dir: bool,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_search_export(results: &[FileEntry], path: &std::path::Path, format: &str) -> std::io::Result<usize> {
+    use std::io::Write;
+
+    let is_json = match format.to_ascii_lowercase().as_str() {
+        "json" => true,
+        "csv" => false,
+        other => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("Unsupported export format: {}", other))),
+    };
+    let mut writer = std::io::BufWriter::new(File::create(path)?);
+
+    if is_json {
+        writer.write_all(b"[\n")?;
+    } else {
+        writeln!(writer, "name,path,size,modified,is_dir")?;
+    }
+
+    for (i, entry) in results.iter().enumerate() {
+        let modified = chrono::DateTime::from_timestamp_millis(entry.modified as i64)
+            .map(|dt| dt.to_rfc3339())
+            .unwrap_or_default();
+
+        if is_json {
+            if i > 0 {
+                writer.write_all(b",\n")?;
+            }
+            let row = SearchExportRow {
+                name: &entry.name,
+                path: &entry.path,
+                size: entry.size,
+                modified,
+                is_dir: entry.is_dir,
+            };
+            serde_json::to_writer(&mut writer, &row)?;
+        } else {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                csv_field(&entry.name),
+                csv_field(&entry.path),
+                entry.size,
+                modified,
+                entry.is_dir
+            )?;
+        }
+    }
+
+    if is_json {
+        writer.write_all(b"\n]\n")?;
+    }
+    writer.flush()?;
+    Ok(results.len())
+}
+
+/// Writes the results of the panel's last search to `path` as CSV or JSON.
+/// Returns the number of rows written.
+#[tauri::command]
+pub async fn export_search_results(
+    app: AppHandle,
+    panel_id: String,
+    path: String,
+    format: String,
+) -> Result<usize, String> {
+    let target = crate::utils::path_security::validate_path(&path).map_err(|e| e.to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        // Streamed under the session lock: copying the results out first would hold them twice
+        let state = app.state::<SessionManager>();
+        let mut session = state.0.lock().map_err(|e| e.to_string())?;
+        match &session.get_panel_mut(&panel_id).search_context {
+            Some(ctx) if !ctx.is_searching => write_search_export(&ctx.results, &target, &format).map_err(|e| e.to_string()),
+            Some(_) => Err("Search is still running".to_string()),
+            None => Err("No search results to export".to_string()),
+        }
+    }).await.map_err(|e| format!("Thread panic: {}", e))?
+}
+
+/// Walks `root` once and stores a filename index that `start_search` uses while it is fresh.
+/// Returns the number of indexed entries.
+#[tauri::command]
+pub async fn build_search_index(app: AppHandle, root: String) -> Result<usize, String> {
+    let root_path = normalize_search_root(crate::utils::path_security::validate_path(&root).map_err(|e| e.to_string())?);
+    if !root_path.is_dir() {
+        return Err(format!("Not a directory: {:?}", root_path));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let manager = app.state::<SearchIndexManager>();
+        manager.build(&app, &root_path).map_err(|e| e.to_string())
+    }).await.map_err(|e| format!("Thread panic: {}", e))?
+}
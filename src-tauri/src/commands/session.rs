@@ -1,7 +1,7 @@
-use crate::models::{CommandError, SessionManager, SessionState, Tab};
+use crate::models::{CommandError, ConfigManager, SessionManager, SessionState, Tab, WorkspacePanel};
 use crate::models::session::PanelState;
 use tauri::{AppHandle, Emitter, State};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::MutexGuard;
 use uuid::Uuid;
 
@@ -31,6 +31,8 @@ pub fn create_tab(
         id: new_id.clone(),
         path: PathBuf::from(&path),
         version: 0,
+        color: None,
+        pinned: false,
     };
 
     let panel = session.get_panel_mut(&panel_id);
@@ -52,18 +54,25 @@ pub fn create_tab(
     Ok(new_id)
 }
 
+/// Closes a tab. Pinned tabs are only closed when `force` is set.
 #[tauri::command]
 pub fn close_tab(
     app: AppHandle,
     state: State<'_, SessionManager>,
     tab_id: String,
+    force: Option<bool>,
 ) -> Result<(), CommandError> {
     let mut session = lock_session(&state)?;
+
+    let pinned = session.left_panel.tabs.iter().chain(&session.right_panel.tabs).any(|t| t.id == tab_id && t.pinned);
+    if pinned && !force.unwrap_or(false) {
+        return Err(CommandError::Other("Tab is pinned".to_string()));
+    }
     
     // Helper to remove tab from a panel
-    let remove_from_panel = |panel: &mut PanelState| -> bool {
+    let remove_from_panel = |panel: &mut PanelState| -> Option<Tab> {
         if let Some(pos) = panel.tabs.iter().position(|t| t.id == tab_id) {
-            panel.tabs.remove(pos);
+            let closed = panel.tabs.remove(pos);
             // If we closed the active tab, switch to the nearest one (or create default)
             if panel.active_tab_id == tab_id {
                 let new_pos = pos.min(panel.tabs.len().saturating_sub(1));
@@ -76,18 +85,24 @@ pub fn close_tab(
                         id: default_id.clone(),
                         path: PathBuf::from("C:\\"),
                         version: 0,
+                        color: None,
+                        pinned: false,
                     });
                     panel.active_tab_id = default_id;
                 }
             }
-            true
+            Some(closed)
         } else {
-            false
+            None
         }
     };
 
-    if !remove_from_panel(&mut session.left_panel) {
-        remove_from_panel(&mut session.right_panel);
+    let closed = match remove_from_panel(&mut session.left_panel) {
+        Some(tab) => Some(("left", tab)),
+        None => remove_from_panel(&mut session.right_panel).map(|tab| ("right", tab)),
+    };
+    if let Some((panel_id, tab)) = closed {
+        session.remember_closed_tab(panel_id, tab);
     }
     
     // Update watchers for both panels just in case (active tab might have changed)
@@ -139,9 +154,10 @@ pub fn active_tab_navigate(
 ) -> Result<(), CommandError> {
     let mut session = lock_session(&state)?;
     
-    {
+    let moved = {
         let panel = session.get_panel_mut(&panel_id);
         if let Some(tab) = panel.tabs.iter_mut().find(|t| t.id == panel.active_tab_id) {
+            let old_path = tab.path.clone();
             // Only update if the incoming version is newer or if no version is provided (legacy/internal)
             if let Some(v) = version {
                 if v > tab.version {
@@ -159,11 +175,26 @@ pub fn active_tab_navigate(
                 tab.path = PathBuf::from(path);
                 tab.version += 1;
             }
+            (tab.path != old_path).then(|| (old_path, tab.path.clone()))
+        } else {
+            None
         }
-    }
+    };
 
     // Update watcher for the affected panel
     session.get_panel_mut(&panel_id).update_watcher(&app);
+
+    // Follow along in the other panel when navigation is synchronized; skipped if the folder is missing there
+    if let Some((old_path, new_path)) = moved.filter(|_| session.sync_navigation) {
+        let other = session.get_panel_mut(if panel_id == "right" { "left" } else { "right" });
+        if let Some(tab) = other.tabs.iter_mut().find(|t| t.id == other.active_tab_id) {
+            if let Some(target) = mirrored_path(&old_path, &new_path, &tab.path).filter(|t| t.is_dir()) {
+                tab.path = target;
+                tab.version += 1;
+            }
+        }
+        other.update_watcher(&app);
+    }
     
     app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
     drop(session);
@@ -171,6 +202,41 @@ pub fn active_tab_navigate(
     Ok(())
 }
 
+/// Applies the step from `old` to `new` (up to their common folder, then down) to `other`.
+/// `None` when `other` doesn't end with the part of `old` being left.
+fn mirrored_path(old: &Path, new: &Path, other: &Path) -> Option<PathBuf> {
+    let common = old.components().zip(new.components()).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+    let up: PathBuf = old.components().skip(common).collect();
+    let down: PathBuf = new.components().skip(common).collect();
+    if !other.ends_with(&up) {
+        return None;
+    }
+    let mut target = other.to_path_buf();
+    for _ in up.components() {
+        target.pop();
+    }
+    Some(target.join(down))
+}
+
+/// Turns synchronized navigation between the two panels on or off.
+#[tauri::command]
+pub fn set_sync_navigation(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    let mut session = lock_session(&state)?;
+    session.sync_navigation = enabled;
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(())
+}
+
 #[tauri::command]
 pub fn set_active_panel(
     app: AppHandle,
@@ -206,6 +272,8 @@ pub fn duplicate_tab(
                 id: Uuid::new_v4().to_string(),
                 path: tab.path.clone(),
                 version: tab.version,
+                color: tab.color.clone(),
+                pinned: false,
             };
             // Insert after current
             panel.tabs.insert(pos + 1, new_tab.clone());
@@ -238,20 +306,27 @@ pub fn close_other_tabs(
 ) -> Result<(), CommandError> {
     let mut session = lock_session(&state)?;
 
-    let handle_panel = |panel: &mut PanelState| -> bool {
+    let handle_panel = |panel: &mut PanelState| -> Option<Vec<Tab>> {
         // Check if tab exists in this panel
-        if let Some(target_tab) = panel.tabs.iter().find(|t| t.id == tab_id).cloned() {
-            // Replace all tabs with just this one
-            panel.tabs = vec![target_tab];
+        if panel.tabs.iter().any(|t| t.id == tab_id) {
+            // Keep just this one, plus any pinned tabs
+            let (kept, closed) = std::mem::take(&mut panel.tabs).into_iter().partition(|t| t.pinned || t.id == tab_id);
+            panel.tabs = kept;
             panel.active_tab_id = tab_id.clone();
-            true
+            Some(closed)
         } else {
-            false
+            None
         }
     };
 
-    if !handle_panel(&mut session.left_panel) {
-        handle_panel(&mut session.right_panel);
+    let closed = match handle_panel(&mut session.left_panel) {
+        Some(tabs) => Some(("left", tabs)),
+        None => handle_panel(&mut session.right_panel).map(|tabs| ("right", tabs)),
+    };
+    if let Some((panel_id, tabs)) = closed {
+        for tab in tabs {
+            session.remember_closed_tab(panel_id, tab);
+        }
     }
 
     session.left_panel.update_watcher(&app);
@@ -279,6 +354,7 @@ pub fn reorder_tabs(
     if source_index < panel.tabs.len() && target_index < panel.tabs.len() {
         let tab = panel.tabs.remove(source_index);
         panel.tabs.insert(target_index, tab);
+        pinned_first(panel);
     } else {
         return Err(CommandError::Other("Index out of bounds".to_string()));
     }
@@ -298,10 +374,338 @@ pub fn update_sort_config(
 ) -> Result<(), CommandError> {
     let mut session = lock_session(&state)?;
     
-    session.get_panel_mut(&panel_id).sort_config = sort_config;
+    session.get_panel_mut(&panel_id).sort_config = sort_config.normalized();
     
     app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
     drop(session);
     state.save(&app)?;
     Ok(())
 }
+
+/// Sets (or clears, when `query` is empty) the quick name filter applied by `list_dir`
+/// to the panel's cached listing.
+#[tauri::command]
+pub fn set_panel_filter(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    panel_id: String,
+    query: String,
+) -> Result<(), CommandError> {
+    let mut session = lock_session(&state)?;
+
+    let query = query.trim();
+    session.get_panel_mut(&panel_id).filter = if query.is_empty() { None } else { Some(query.to_string()) };
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    Ok(())
+}
+
+/// Navigates the active tab of `panel_id` to the folder containing `path` (a directory inside the
+/// archive for virtual archive paths) and returns the file name the frontend should select.
+#[tauri::command]
+pub fn reveal_in_panel(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    panel_id: String,
+    path: String,
+) -> Result<String, CommandError> {
+    let target = crate::utils::path_security::validate_path(&path)?;
+    let exists = match crate::utils::archive::split_virtual_path(&target.to_string_lossy()) {
+        Some((archive_path, internal_path)) if !internal_path.is_empty() => archive_path.is_file(),
+        _ => target.exists(),
+    };
+    if !exists {
+        return Err(CommandError::PathError(format!("Path not found: {}", path)));
+    }
+
+    let parent = target.parent()
+        .ok_or_else(|| CommandError::PathError(format!("No parent folder for {}", path)))?
+        .to_path_buf();
+    let file_name = target.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut session = lock_session(&state)?;
+    {
+        let panel = session.get_panel_mut(&panel_id);
+        if let Some(tab) = panel.tabs.iter_mut().find(|t| t.id == panel.active_tab_id) {
+            tab.path = parent;
+            tab.version += 1;
+        }
+    }
+    session.get_panel_mut(&panel_id).update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(file_name)
+}
+
+/// Navigates the active tab of `panel_id` to the folder bound to bookmark `slot` and returns it.
+/// Fails for empty slots and for bookmarks whose folder no longer exists.
+#[tauri::command]
+pub fn go_to_bookmark(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    config_state: State<'_, ConfigManager>,
+    panel_id: String,
+    slot: u8,
+) -> Result<String, CommandError> {
+    let path = {
+        let config = config_state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+        config.bookmarks.get(&slot).cloned()
+    }.ok_or_else(|| CommandError::Other(format!("No bookmark in slot {}", slot)))?;
+    if !std::path::Path::new(&path).is_dir() {
+        return Err(CommandError::PathError(format!("Bookmarked folder no longer exists: {}", path)));
+    }
+
+    let mut session = lock_session(&state)?;
+    {
+        let panel = session.get_panel_mut(&panel_id);
+        if let Some(tab) = panel.tabs.iter_mut().find(|t| t.id == panel.active_tab_id) {
+            tab.path = PathBuf::from(&path);
+            tab.version += 1;
+        }
+    }
+    session.get_panel_mut(&panel_id).update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(path)
+}
+
+/// Replaces the panel's tabs with fresh ones for the saved paths; an empty list gets a `C:\` tab.
+fn restore_panel(panel: &mut PanelState, saved: &WorkspacePanel) {
+    let paths: Vec<PathBuf> = if saved.tabs.is_empty() {
+        vec![PathBuf::from("C:\\")]
+    } else {
+        saved.tabs.iter().map(PathBuf::from).collect()
+    };
+    panel.tabs = paths.into_iter()
+        .map(|path| Tab { id: Uuid::new_v4().to_string(), path, version: 0, color: None, pinned: false })
+        .collect();
+    let active = saved.active_tab.min(panel.tabs.len() - 1);
+    panel.active_tab_id = panel.tabs[active].id.clone();
+    panel.search_context = None;
+    panel.cached_results = None;
+    panel.filter = None;
+}
+
+/// Rebuilds both panels' tabs from the workspace saved under `name`.
+#[tauri::command]
+pub fn load_workspace(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    config_state: State<'_, ConfigManager>,
+    name: String,
+) -> Result<(), CommandError> {
+    let workspace = {
+        let config = config_state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
+        config.workspaces.iter().find(|w| w.name == name).cloned()
+    }.ok_or_else(|| CommandError::Other(format!("Workspace not found: {}", name)))?;
+
+    let mut session = lock_session(&state)?;
+    restore_panel(&mut session.left_panel, &workspace.left);
+    restore_panel(&mut session.right_panel, &workspace.right);
+    session.active_panel = if workspace.active_panel == "right" { "right" } else { "left" }.to_string();
+
+    session.left_panel.update_watcher(&app);
+    session.right_panel.update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(())
+}
+
+/// Moves pinned tabs in front of the others, keeping the order within each group.
+fn pinned_first(panel: &mut PanelState) {
+    panel.tabs.sort_by_key(|t| !t.pinned);
+}
+
+/// Sets the tab's color, or clears it when `color` is missing or empty.
+#[tauri::command]
+pub fn set_tab_color(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    panel_id: String,
+    tab_id: String,
+    color: Option<String>,
+) -> Result<(), CommandError> {
+    let mut session = lock_session(&state)?;
+
+    let tab = session.get_panel_mut(&panel_id).tabs.iter_mut().find(|t| t.id == tab_id)
+        .ok_or_else(|| CommandError::Other("Tab not found".to_string()))?;
+    tab.color = color.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(())
+}
+
+/// Pins or unpins a tab and returns its new state. Pinned tabs move to the front of the panel.
+#[tauri::command]
+pub fn toggle_tab_pin(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    panel_id: String,
+    tab_id: String,
+) -> Result<bool, CommandError> {
+    let mut session = lock_session(&state)?;
+
+    let panel = session.get_panel_mut(&panel_id);
+    let tab = panel.tabs.iter_mut().find(|t| t.id == tab_id)
+        .ok_or_else(|| CommandError::Other("Tab not found".to_string()))?;
+    tab.pinned = !tab.pinned;
+    let pinned = tab.pinned;
+    pinned_first(panel);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(pinned)
+}
+
+/// Reopens the most recently closed tab in the panel it was closed from and makes it active.
+/// Returns the new tab's id, or `None` when there is nothing to reopen.
+#[tauri::command]
+pub fn reopen_closed_tab(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+) -> Result<Option<String>, CommandError> {
+    let mut session = lock_session(&state)?;
+    let Some(closed) = session.closed_tabs.pop() else {
+        return Ok(None);
+    };
+
+    let new_id = Uuid::new_v4().to_string();
+    let panel_id = if closed.panel_id == "right" { "right" } else { "left" };
+    {
+        let panel = session.get_panel_mut(panel_id);
+        panel.tabs.push(Tab { id: new_id.clone(), version: 0, ..closed.tab });
+        panel.active_tab_id = new_id.clone();
+        pinned_first(panel);
+    }
+    session.active_panel = panel_id.to_string();
+    session.get_panel_mut(panel_id).update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(Some(new_id))
+}
+
+/// Moves a tab to `index` in the other panel and makes it active there. Reordering within a
+/// panel goes through `reorder_tabs`, so moving to the same panel does nothing.
+/// A panel left without tabs gets a default `C:\` tab, as when unmounting a disk image.
+#[tauri::command]
+pub fn move_tab_to_panel(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    from_panel: String,
+    tab_id: String,
+    to_panel: String,
+    index: usize,
+) -> Result<(), CommandError> {
+    for id in [&from_panel, &to_panel] {
+        if id != "left" && id != "right" {
+            return Err(CommandError::Other("Invalid panel ID".to_string()));
+        }
+    }
+    if from_panel == to_panel {
+        return Ok(());
+    }
+    let mut session = lock_session(&state)?;
+
+    let tab = {
+        let source = session.get_panel_mut(&from_panel);
+        let pos = source.tabs.iter().position(|t| t.id == tab_id)
+            .ok_or_else(|| CommandError::Other("Tab not found".to_string()))?;
+        let tab = source.tabs.remove(pos);
+        if source.tabs.is_empty() {
+            let default_id = Uuid::new_v4().to_string();
+            source.tabs.push(Tab {
+                id: default_id.clone(),
+                path: PathBuf::from("C:\\"),
+                version: 0,
+                color: None,
+                pinned: false,
+            });
+            source.active_tab_id = default_id;
+        } else if source.active_tab_id == tab_id {
+            source.active_tab_id = source.tabs[pos.min(source.tabs.len() - 1)].id.clone();
+        }
+        tab
+    };
+
+    {
+        let target = session.get_panel_mut(&to_panel);
+        let index = index.min(target.tabs.len());
+        target.tabs.insert(index, tab);
+        target.active_tab_id = tab_id;
+        pinned_first(target);
+    }
+    session.active_panel = to_panel;
+
+    session.left_panel.update_watcher(&app);
+    session.right_panel.update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(())
+}
+
+/// Exchanges the left and right panels (tabs, sort and filter included). Focus stays on the same side.
+#[tauri::command]
+pub fn swap_panels(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+) -> Result<(), CommandError> {
+    let mut session = lock_session(&state)?;
+
+    let SessionState { left_panel, right_panel, .. } = &mut *session;
+    std::mem::swap(left_panel, right_panel);
+    session.left_panel.update_watcher(&app);
+    session.right_panel.update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(())
+}
+
+/// Opens the folder of `from_panel`'s active tab in a new, active tab of the other panel
+/// and returns its id. Focus stays on `from_panel`.
+#[tauri::command]
+pub fn copy_path_to_other_panel(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    from_panel: String,
+) -> Result<String, CommandError> {
+    let mut session = lock_session(&state)?;
+
+    let path = {
+        let source = session.get_panel_mut(&from_panel);
+        source.tabs.iter().find(|t| t.id == source.active_tab_id).map(|t| t.path.clone())
+    }.ok_or_else(|| CommandError::Other("Tab not found".to_string()))?;
+
+    let new_id = Uuid::new_v4().to_string();
+    let target = session.get_panel_mut(if from_panel == "right" { "left" } else { "right" });
+    target.tabs.push(Tab {
+        id: new_id.clone(),
+        path,
+        version: 0,
+        color: None,
+        pinned: false,
+    });
+    target.active_tab_id = new_id.clone();
+    target.update_watcher(&app);
+
+    app.emit("session_changed", session.clone()).map_err(|e| CommandError::SystemError(e.to_string()))?;
+    drop(session);
+    state.save(&app)?;
+    Ok(new_id)
+}
@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+use log::info;
+use crate::models::{CommandError, ShadowVersion};
+use crate::utils::path_security::validate_path;
+
+/// A Volume Shadow Copy of the volume holding a path, as reported by `Win32_ShadowCopy`.
+#[cfg(target_os = "windows")]
+#[derive(serde::Deserialize)]
+struct ShadowCopy {
+    device: String,
+    volume: String,
+    created: i64,
+}
+
+/// Snapshots of `path`'s volume that still contain it, newest first. Empty when VSS is
+/// unavailable or there are no shadow copies; querying them requires administrator rights.
+#[tauri::command]
+pub async fn list_shadow_versions(path: String) -> Result<Vec<ShadowVersion>, CommandError> {
+    let path = validate_path(&path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut versions: Vec<ShadowVersion> = shadow_copies_of(&path)?
+            .into_iter()
+            .filter_map(|(created, snapshot)| {
+                let metadata = std::fs::metadata(&snapshot).ok()?;
+                let modified = metadata.modified().ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                Some(ShadowVersion {
+                    timestamp: created,
+                    snapshot_path: snapshot.to_string_lossy().to_string(),
+                    is_dir: metadata.is_dir(),
+                    size: if metadata.is_dir() { 0 } else { metadata.len() },
+                    modified,
+                })
+            })
+            .collect();
+        versions.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(versions)
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+/// Copies `path` as it was in the snapshot taken at `timestamp` (from `list_shadow_versions`)
+/// to `target`, which must not exist yet. Folders are copied with their whole content.
+#[tauri::command]
+pub async fn restore_shadow_version(path: String, timestamp: i64, target: String) -> Result<(), CommandError> {
+    let path = validate_path(&path)?;
+    let target = validate_path(&target)?;
+    if target.exists() {
+        return Err(CommandError::PathError(format!("{} already exists", target.display())));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let snapshot = shadow_copies_of(&path)?
+            .into_iter()
+            .find(|(created, _)| *created == timestamp)
+            .map(|(_, snapshot)| snapshot)
+            .filter(|snapshot| snapshot.exists())
+            .ok_or_else(|| CommandError::PathError(format!("No previous version of {} from that date", path.display())))?;
+
+        if snapshot.is_dir() {
+            for entry in walkdir::WalkDir::new(&snapshot) {
+                let entry = entry.map_err(|e| CommandError::IoError(e.to_string()))?;
+                let relative = entry.path().strip_prefix(&snapshot)
+                    .map_err(|_| CommandError::PathError("Strip prefix failed".to_string()))?;
+                let dest = target.join(relative);
+                if entry.file_type().is_dir() {
+                    std::fs::create_dir_all(&dest)?;
+                } else {
+                    std::fs::copy(entry.path(), &dest)?;
+                }
+            }
+        } else {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(&snapshot, &target)?;
+        }
+        info!("Restored {:?} from shadow copy to {:?}", path, target);
+        Ok(())
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+/// Creation time (ms) and location of `path` inside each shadow copy of its volume.
+#[cfg(target_os = "windows")]
+fn shadow_copies_of(path: &Path) -> Result<Vec<(i64, PathBuf)>, CommandError> {
+    use std::os::windows::process::CommandExt;
+
+    let Some(root) = crate::utils::hardware::volume_root(path) else {
+        return Ok(Vec::new());
+    };
+    let Some(volume) = volume_guid_path(&root) else {
+        return Ok(Vec::new());
+    };
+    if !crate::commands::system::is_elevated() {
+        return Err(CommandError::SystemError("Previous versions require running as administrator".to_string()));
+    }
+
+    let script = "
+        $ErrorActionPreference = 'Stop'
+        try {
+            @(Get-CimInstance Win32_ShadowCopy | ForEach-Object {
+                [PSCustomObject]@{
+                    device = $_.DeviceObject;
+                    volume = $_.VolumeName;
+                    created = ([DateTimeOffset]$_.InstallDate).ToUnixTimeMilliseconds();
+                }
+            }) | ConvertTo-Json -Compress
+        } catch {
+            '[]'
+        }
+    ";
+    let output = std::process::Command::new("powershell")
+        .arg("-NoProfile")
+        .arg("-Command")
+        .arg(script)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output()
+        .map_err(|e| CommandError::SystemError(e.to_string()))?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    // PowerShell unwraps single-element arrays into a bare object
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let copies: Vec<ShadowCopy> = if stdout.starts_with('{') {
+        serde_json::from_str(&stdout).map(|c| vec![c]).unwrap_or_default()
+    } else {
+        serde_json::from_str(&stdout).unwrap_or_default()
+    };
+
+    let path_str = crate::utils::path_security::strip_extended_length(&path.to_string_lossy());
+    let relative = path_str.get(root.len()..).unwrap_or("").trim_start_matches('\\').to_string();
+    Ok(copies
+        .into_iter()
+        .filter(|c| c.volume.eq_ignore_ascii_case(&volume))
+        .map(|c| (c.created, PathBuf::from(format!("{}\\{}", c.device.trim_end_matches('\\'), relative))))
+        .collect())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shadow_copies_of(_path: &Path) -> Result<Vec<(i64, PathBuf)>, CommandError> {
+    Ok(Vec::new())
+}
+
+/// `\\?\Volume{GUID}\` for a mount point such as `C:\`, the form `Win32_ShadowCopy` uses.
+#[cfg(target_os = "windows")]
+fn volume_guid_path(root: &str) -> Option<String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetVolumeNameForVolumeMountPointW;
+
+    let wide: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut buffer = [0u16; 64];
+    unsafe { GetVolumeNameForVolumeMountPointW(PCWSTR(wide.as_ptr()), &mut buffer).ok()? };
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    Some(String::from_utf16_lossy(&buffer[..len]))
+}
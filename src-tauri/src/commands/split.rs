@@ -0,0 +1,209 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use log::info;
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::models::{CommandError, ConfigManager, ProgressEvent};
+use crate::utils::archive::{find_volumes, volume_path};
+use crate::utils::path_security::validate_path;
+
+/// Cancel flag shared by `split_file` and `join_files`.
+#[derive(Default)]
+pub struct SplitJoinState(pub Arc<AtomicBool>);
+
+/// Emits `progress` events for a split or join at most every 100 ms.
+struct SplitProgress<'a> {
+    app: &'a AppHandle,
+    task: &'static str,
+    total: u64,
+    current: u64,
+    last_emit: Instant,
+}
+
+impl SplitProgress<'_> {
+    fn emit(&self, status: &str, filename: Option<String>) {
+        let _ = self.app.emit("progress", ProgressEvent {
+            id: format!("{}_op", self.task),
+            task: self.task.to_string(),
+            current: self.current,
+            total: self.total,
+            status: status.to_string(),
+            filename,
+        });
+    }
+
+    fn advance(&mut self, bytes: u64, file: &Path) {
+        self.current += bytes;
+        if self.last_emit.elapsed().as_millis() > 100 {
+            self.emit("running", file.file_name().map(|n| n.to_string_lossy().to_string()));
+            self.last_emit = Instant::now();
+        }
+    }
+}
+
+fn buffer_size(app: &AppHandle) -> usize {
+    app.try_state::<ConfigManager>()
+        .and_then(|c| c.0.lock().ok().map(|cfg| cfg.copy_buffer_bytes(false)))
+        .unwrap_or(512 * 1024)
+}
+
+/// Cuts `path` into `path.001`, `path.002`, ... of `part_size_bytes` each (the last one may be
+/// smaller). Refuses to overwrite existing parts; partial output is removed on error or cancel.
+#[tauri::command]
+pub async fn split_file(
+    app: AppHandle,
+    state: State<'_, SplitJoinState>,
+    path: String,
+    part_size_bytes: u64,
+) -> Result<Vec<String>, CommandError> {
+    let source = validate_path(&path)?;
+    if part_size_bytes == 0 {
+        return Err(CommandError::Other("Part size must be greater than zero".to_string()));
+    }
+    let total = fs::metadata(&source)?.len();
+    if !source.is_file() {
+        return Err(CommandError::PathError(format!("Not a file: {}", source.display())));
+    }
+    let part_count = total.div_ceil(part_size_bytes).max(1) as usize;
+    if let Some(existing) = (0..part_count).map(|i| volume_path(&source, i)).find(|p| p.exists()) {
+        return Err(CommandError::PathError(format!("{} already exists", existing.display())));
+    }
+
+    state.0.store(false, Ordering::Relaxed);
+    let cancel = state.0.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut parts = Vec::with_capacity(part_count);
+        let mut progress = SplitProgress { app: &app, task: "split", total, current: 0, last_emit: Instant::now() };
+        let result = write_parts(&source, part_size_bytes, part_count, &cancel, &mut parts, &mut progress);
+
+        if result.is_err() {
+            for part in &parts {
+                let _ = fs::remove_file(part);
+            }
+        }
+        let status = match &result {
+            Ok(_) => "completed",
+            Err(CommandError::Other(m)) if m == "Cancelled" => "cancelled",
+            Err(_) => "error",
+        };
+        progress.emit(status, None);
+        result?;
+
+        info!("Split {:?} into {} parts", source, parts.len());
+        Ok(parts.iter().map(|p| p.to_string_lossy().to_string()).collect())
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+fn write_parts(
+    source: &Path,
+    part_size: u64,
+    part_count: usize,
+    cancel: &AtomicBool,
+    parts: &mut Vec<PathBuf>,
+    progress: &mut SplitProgress,
+) -> Result<(), CommandError> {
+    let mut input = File::open(source)?;
+    let mut buffer = vec![0u8; buffer_size(progress.app)];
+
+    for index in 0..part_count {
+        let part = volume_path(source, index);
+        let mut output = File::create(&part)?;
+        parts.push(part.clone());
+
+        let mut remaining = part_size;
+        while remaining > 0 {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(CommandError::Other("Cancelled".to_string()));
+            }
+            let want = buffer.len().min(remaining as usize);
+            let n = input.read(&mut buffer[..want])?;
+            if n == 0 {
+                break;
+            }
+            output.write_all(&buffer[..n])?;
+            remaining -= n as u64;
+            progress.advance(n as u64, &part);
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds the original file from `name.001` and the parts following it, next to them.
+/// The result must be exactly as long as all parts together, otherwise it is removed.
+#[tauri::command]
+pub async fn join_files(
+    app: AppHandle,
+    state: State<'_, SplitJoinState>,
+    first_part_path: String,
+) -> Result<String, CommandError> {
+    let first = validate_path(&first_part_path)?;
+    let (target, parts) = find_volumes(&first)
+        .filter(|(_, parts)| !parts.is_empty())
+        .ok_or_else(|| CommandError::PathError(format!("Not the first part of a split file (.001): {}", first.display())))?;
+    if target.exists() {
+        return Err(CommandError::PathError(format!("{} already exists", target.display())));
+    }
+    let total = parts.iter().map(|p| fs::metadata(p).map(|m| m.len())).sum::<std::io::Result<u64>>()?;
+
+    state.0.store(false, Ordering::Relaxed);
+    let cancel = state.0.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut progress = SplitProgress { app: &app, task: "join", total, current: 0, last_emit: Instant::now() };
+        let result = concat_parts(&parts, &target, &cancel, &mut progress).and_then(|_| {
+            let written = fs::metadata(&target)?.len();
+            if written == total {
+                Ok(())
+            } else {
+                Err(CommandError::IoError(format!("Joined file is {} bytes, expected {}", written, total)))
+            }
+        });
+
+        if result.is_err() {
+            let _ = fs::remove_file(&target);
+        }
+        let status = match &result {
+            Ok(_) => "completed",
+            Err(CommandError::Other(m)) if m == "Cancelled" => "cancelled",
+            Err(_) => "error",
+        };
+        progress.emit(status, None);
+        result?;
+
+        info!("Joined {} parts into {:?}", parts.len(), target);
+        Ok(target.to_string_lossy().to_string())
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+fn concat_parts(parts: &[PathBuf], target: &Path, cancel: &AtomicBool, progress: &mut SplitProgress) -> Result<(), CommandError> {
+    let mut output = File::create(target)?;
+    let mut buffer = vec![0u8; buffer_size(progress.app)];
+
+    for part in parts {
+        let mut input = File::open(part)?;
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(CommandError::Other("Cancelled".to_string()));
+            }
+            let n = input.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            output.write_all(&buffer[..n])?;
+            progress.advance(n as u64, part);
+        }
+    }
+    output.flush()?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn cancel_split_join(state: State<'_, SplitJoinState>) {
+    state.0.store(true, Ordering::Relaxed);
+}
@@ -1,4 +1,4 @@
-use crate::models::{DriveInfo, WinMenuItem, QuickAccessItem, CommandError, SessionManager, SnapRect};
+use crate::models::{BreadcrumbSegment, DriveInfo, LockingProcess, MailSendResult, WinMenuItem, QuickAccessItem, CommandError, SessionManager, SnapRect, WallpaperStyle};
 use crate::WindowState;
 use crate::utils::path_security::validate_path;
 use log::info;
@@ -201,6 +201,75 @@ pub fn get_drives(skip_hardware_info: Option<bool>) -> Vec<DriveInfo> {
     }
 }
 
+/// Address bar segments for `path`, from the drive root down. Drives show their volume label
+/// and folders their shell display name ("Documents" rather than the on-disk name). Paths
+/// inside an archive continue with the archive file and its internal folders.
+#[tauri::command]
+pub fn get_breadcrumbs(path: String) -> Result<Vec<BreadcrumbSegment>, CommandError> {
+    let path = validate_path(&path)?;
+    let drives = get_drives(Some(true));
+    let mut ancestors: Vec<&std::path::Path> = path.ancestors().collect();
+    ancestors.reverse();
+
+    let mut inside_archive = false;
+    let mut segments = Vec::with_capacity(ancestors.len());
+    for ancestor in ancestors {
+        let full_path = ancestor.to_string_lossy().to_string();
+        let display_name = match ancestor.file_name() {
+            None => drive_display_name(&drives, &full_path),
+            // Entries inside an archive don't exist on disk, so the shell knows nothing about them
+            Some(name) if inside_archive => name.to_string_lossy().to_string(),
+            Some(name) => {
+                inside_archive = ancestor.is_file();
+                shell_display_name(ancestor).unwrap_or_else(|| name.to_string_lossy().to_string())
+            }
+        };
+        segments.push(BreadcrumbSegment { display_name, full_path });
+    }
+    Ok(segments)
+}
+
+/// "Label (C:)" like Explorer, or just "C:" for unlabelled volumes and UNC shares.
+fn drive_display_name(drives: &[DriveInfo], root: &str) -> String {
+    let letter = root.trim_end_matches(['\\', '/']);
+    match drives.iter().find(|d| d.path.eq_ignore_ascii_case(root)) {
+        Some(drive) if !drive.label.is_empty() => format!("{} ({})", drive.label, letter),
+        _ if letter.is_empty() => root.to_string(),
+        _ => letter.to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn shell_display_name(path: &std::path::Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::FILE_FLAGS_AND_ATTRIBUTES;
+    use windows::Win32::UI::Shell::{SHGetFileInfoW, SHFILEINFOW, SHGFI_DISPLAYNAME};
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut info = SHFILEINFOW::default();
+    let result = unsafe {
+        SHGetFileInfoW(
+            PCWSTR(wide.as_ptr()),
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            Some(&mut info),
+            std::mem::size_of::<SHFILEINFOW>() as u32,
+            SHGFI_DISPLAYNAME,
+        )
+    };
+    if result == 0 {
+        return None;
+    }
+    let len = info.szDisplayName.iter().position(|&c| c == 0).unwrap_or(info.szDisplayName.len());
+    let name = String::from_utf16_lossy(&info.szDisplayName[..len]);
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_display_name(_path: &std::path::Path) -> Option<String> {
+    None
+}
+
 #[tauri::command]
 pub fn get_accent_color() -> String {
     #[cfg(target_os = "windows")]
@@ -254,14 +323,27 @@ pub fn get_accent_color() -> String {
     "#0078d7".to_string() // Default Windows Blue
 }
 
+/// Schemes an Internet shortcut may open directly; anything else (file:, custom handlers)
+/// is left to the shell so it gets its usual security prompt.
+const URL_SHORTCUT_SCHEMES: &[&str] = &["http://", "https://", "ftp://", "mailto:"];
+
 #[tauri::command]
-pub async fn open_item(path: String) -> Result<(), CommandError> {
+pub async fn open_item(app: AppHandle, path: String) -> Result<(), CommandError> {
     let normalized_path = if path.starts_with("::{") || path.starts_with("?") {
         path
     } else {
         validate_path(&path)?.to_string_lossy().into_owned()
     };
     info!("Opening item: {}", normalized_path);
+
+    if normalized_path.to_lowercase().ends_with(".url") {
+        let url = crate::commands::io::read_url_shortcut(std::path::Path::new(&normalized_path))?;
+        if URL_SHORTCUT_SCHEMES.iter().any(|s| url.to_lowercase().starts_with(s)) {
+            use tauri_plugin_opener::OpenerExt;
+            return app.opener().open_url(url, None::<&str>)
+                .map_err(|e| CommandError::SystemError(e.to_string()));
+        }
+    }
     #[cfg(target_os = "windows")]
     {
         if normalized_path.starts_with("::{") || normalized_path.starts_with("?") {
@@ -821,6 +903,101 @@ pub async fn mount_disk_image(app: AppHandle, path: String) -> Result<(), Comman
 }
 
 
+/// Closes every session tab located on one of `affected_drives` ("e:" style, lowercase) and releases
+/// the panel watchers so the volume can be removed.
+#[cfg(target_os = "windows")]
+fn close_tabs_on_drives(app: &AppHandle, state: &State<'_, SessionManager>, affected_drives: &[String]) -> Result<(), CommandError> {
+    // The lock is released on return, before the caller runs the slow eject command
+    let mut session = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session state".to_string()))?;
+    
+    let clean_panel = |panel: &mut crate::models::session::PanelState| {
+        let mut tabs_to_keep = Vec::new();
+        let mut active_id_invalidated = false;
+
+        for tab in &panel.tabs {
+            let tab_path_lower = tab.path.to_string_lossy().to_lowercase();
+            if affected_drives.iter().any(|d| tab_path_lower.starts_with(d)) {
+                if tab.id == panel.active_tab_id {
+                    active_id_invalidated = true;
+                }
+            } else {
+                tabs_to_keep.push(tab.clone());
+            }
+        }
+
+        if tabs_to_keep.is_empty() {
+            // Panel becomes empty, must add fallback
+            let new_id = uuid::Uuid::new_v4().to_string();
+            tabs_to_keep.push(crate::models::Tab {
+                id: new_id.clone(),
+                path: PathBuf::from("C:\\"),
+                version: 0,
+                color: None,
+                pinned: false,
+            });
+            panel.active_tab_id = new_id;
+        } else if active_id_invalidated {
+            // Active tab closed, switch to another one
+            if let Some(last) = tabs_to_keep.last() {
+                panel.active_tab_id = last.id.clone();
+            }
+        }
+        panel.tabs = tabs_to_keep;
+    };
+
+    clean_panel(&mut session.left_panel);
+    clean_panel(&mut session.right_panel);
+
+    // CRITICAL: Release file watchers on the drive before unmounting/ejecting
+    session.left_panel.update_watcher(app);
+    session.right_panel.update_watcher(app);
+
+    let _ = app.emit("session_changed", session.clone());
+    Ok(())
+}
+
+/// Runs the Shell "Eject" verb on a drive (dismounting it if it is a disk image) and waits
+/// for the volume to disappear. Returns false if it is still present afterwards.
+#[cfg(target_os = "windows")]
+fn eject_volume_via_shell(drive_letter: char) -> Result<bool, CommandError> {
+    let cmd = format!(
+        "$OutputEncoding = [Console]::OutputEncoding = [System.Text.Encoding]::UTF8; \
+        $driveLetter = '{}:'; \
+        $sa = New-Object -ComObject Shell.Application; \
+        $ns = $sa.NameSpace(17); \
+        $item = $ns.ParseName($driveLetter); \
+        if ($item) {{ \
+            $verbs = $item.Verbs() | Where-Object {{ $_.Name.Replace('&','') -match 'Eject|Éjecter|Ejection|Auswerfen|Ejectar' }}; \
+            if ($verbs) {{ \
+                foreach ($v in $verbs) {{ $v.DoIt(); break; }} \
+            }} else {{ \
+                $item.InvokeVerb('Eject'); \
+            }} \
+        }} \
+        if (Get-DiskImage -DevicePath \"\\\\.\\$driveLetter\" -ErrorAction SilentlyContinue) {{ \
+            Dismount-DiskImage -DevicePath \"\\\\.\\$driveLetter\" -ErrorAction SilentlyContinue; \
+        }} \
+        $timeout = 20; \
+        while ($timeout -gt 0) {{ \
+            if (!(Get-PSDrive $driveLetter.Replace(':','') -ErrorAction SilentlyContinue)) {{ \
+                exit 0; \
+            }} \
+            Start-Sleep -Milliseconds 100; \
+            $timeout--; \
+        }} \
+        exit 1;",
+        drive_letter
+    );
+
+    let output = Command::new("powershell")
+        .arg("-Command")
+        .arg(cmd)
+        .output()
+        .map_err(|e| CommandError::SystemError(e.to_string()))?;
+
+    Ok(output.status.success())
+}
+
 #[tauri::command]
 pub async fn unmount_disk_image(
     app: AppHandle,
@@ -879,91 +1056,11 @@ pub async fn unmount_disk_image(
         }
 
         // 2. Handle Session State (Close tabs on these drives)
-        {
-            // Scope the lock so it releases before we run the slow unmount command
-            let mut session = state.0.lock().map_err(|_| CommandError::SystemError("Failed to lock session state".to_string()))?;
-            
-            let clean_panel = |panel: &mut crate::models::session::PanelState| {
-                let mut tabs_to_keep = Vec::new();
-                let mut active_id_invalidated = false;
-
-                for tab in &panel.tabs {
-                    let tab_path_lower = tab.path.to_string_lossy().to_lowercase();
-                    if affected_drives.iter().any(|d| tab_path_lower.starts_with(d)) {
-                        if tab.id == panel.active_tab_id {
-                            active_id_invalidated = true;
-                        }
-                    } else {
-                        tabs_to_keep.push(tab.clone());
-                    }
-                }
-
-                if tabs_to_keep.is_empty() {
-                    // Panel becomes empty, must add fallback
-                    let new_id = uuid::Uuid::new_v4().to_string();
-                    tabs_to_keep.push(crate::models::Tab {
-                        id: new_id.clone(),
-                        path: PathBuf::from("C:\\"),
-                        version: 0,
-                    });
-                    panel.active_tab_id = new_id;
-                } else if active_id_invalidated {
-                    // Active tab closed, switch to another one
-                    if let Some(last) = tabs_to_keep.last() {
-                        panel.active_tab_id = last.id.clone();
-                    }
-                }
-                panel.tabs = tabs_to_keep;
-            };
-
-            clean_panel(&mut session.left_panel);
-            clean_panel(&mut session.right_panel);
-
-            // CRITICAL: Release file watchers on the drive before unmounting/ejecting
-            session.left_panel.update_watcher(&app);
-            session.right_panel.update_watcher(&app);
-
-            let _ = app.emit("session_changed", session.clone());
-        } // session lock released here
+        close_tabs_on_drives(&app, &state, &affected_drives)?;
 
         if path.len() <= 3 && path.contains(':') {
             let drive_letter = path.chars().next().ok_or(CommandError::PathError("Empty drive path".to_string()))?;
-            let cmd = format!(
-                "$OutputEncoding = [Console]::OutputEncoding = [System.Text.Encoding]::UTF8; \
-                $driveLetter = '{}:'; \
-                $sa = New-Object -ComObject Shell.Application; \
-                $ns = $sa.NameSpace(17); \
-                $item = $ns.ParseName($driveLetter); \
-                if ($item) {{ \
-                    $verbs = $item.Verbs() | Where-Object {{ $_.Name.Replace('&','') -match 'Eject|Éjecter|Ejection|Auswerfen|Ejectar' }}; \
-                    if ($verbs) {{ \
-                        foreach ($v in $verbs) {{ $v.DoIt(); break; }} \
-                    }} else {{ \
-                        $item.InvokeVerb('Eject'); \
-                    }} \
-                }} \
-                if (Get-DiskImage -DevicePath \"\\\\.\\$driveLetter\" -ErrorAction SilentlyContinue) {{ \
-                    Dismount-DiskImage -DevicePath \"\\\\.\\$driveLetter\" -ErrorAction SilentlyContinue; \
-                }} \
-                $timeout = 20; \
-                while ($timeout -gt 0) {{ \
-                    if (!(Get-PSDrive $driveLetter.Replace(':','') -ErrorAction SilentlyContinue)) {{ \
-                        exit 0; \
-                    }} \
-                    Start-Sleep -Milliseconds 100; \
-                    $timeout--; \
-                }} \
-                exit 1;",
-                drive_letter
-            );
-
-            let output = Command::new("powershell")
-                .arg("-Command")
-                .arg(cmd)
-                .output()
-                .map_err(|e| CommandError::SystemError(e.to_string()))?;
-
-            if !output.status.success() {
+            if !eject_volume_via_shell(drive_letter)? {
                 return Err(CommandError::SystemError("Failed to eject. The drive might be in use by another program.".to_string()));
             }
         } else {
@@ -1331,6 +1428,86 @@ pub fn restart_app(app: tauri::AppHandle) {
     }
 }
 
+/// Whether the app runs with administrator rights (elevated token), for the UI badge and to gate
+/// privileged actions.
+#[tauri::command]
+pub fn is_elevated() -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::{CloseHandle, HANDLE};
+        use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+        use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+        unsafe {
+            let mut token = HANDLE::default();
+            if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+                return false;
+            }
+            let mut elevation = TOKEN_ELEVATION::default();
+            let mut len = 0u32;
+            let result = GetTokenInformation(
+                token,
+                TokenElevation,
+                Some(&mut elevation as *mut _ as *mut _),
+                std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+                &mut len,
+            );
+            let _ = CloseHandle(token);
+            result.is_ok() && elevation.TokenIsElevated != 0
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+/// Starts a new instance as administrator (UAC prompt) and closes this one. The session is saved
+/// first so the elevated instance reopens the same tabs. Nothing happens if the prompt is declined.
+#[tauri::command]
+pub fn relaunch_as_admin(app: AppHandle, session: State<'_, SessionManager>) -> Result<(), CommandError> {
+    if is_elevated() {
+        return Err(CommandError::Other("Already running as administrator".to_string()));
+    }
+    session.save(&app)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::{HSTRING, PCWSTR};
+        use windows::Win32::UI::Shell::ShellExecuteW;
+        use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+        let current_exe = std::env::current_exe()?;
+        let wide_exe = HSTRING::from(current_exe.as_os_str());
+        unsafe {
+            let res = ShellExecuteW(
+                None,
+                windows::core::w!("runas"),
+                PCWSTR(wide_exe.as_ptr()),
+                PCWSTR::null(),
+                PCWSTR::null(),
+                SW_SHOWNORMAL,
+            );
+            let inst = res.0 as usize;
+            // SE_ERR_ACCESSDENIED is also what a declined UAC prompt gives: keep this instance open
+            if inst == 5 {
+                info!("Elevation was declined");
+                return Ok(());
+            }
+            if inst <= 32 {
+                return Err(CommandError::SystemError(format!("ShellExecuteW failed: {}", inst)));
+            }
+        }
+        info!("Relaunched as administrator");
+        app.exit(0);
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(CommandError::Other("Relaunching as administrator is only available on Windows".to_string()))
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
 pub struct PeekStatus {
     pub installed: bool,
@@ -1457,3 +1634,450 @@ pub async fn open_peek(path: String) -> Result<(), String> {
         Err("Peek is only available on Windows".to_string())
     }
 }
+
+/// Processes that have `path` open, as reported by the Restart Manager, so a "file in use" error
+/// can name the culprit. Empty when nothing holds the file.
+#[tauri::command]
+pub async fn get_locking_processes(path: String) -> Result<Vec<LockingProcess>, CommandError> {
+    let pb = validate_path(&path)?;
+    tauri::async_runtime::spawn_blocking(move || locking_processes(&pb))
+        .await
+        .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+#[cfg(target_os = "windows")]
+fn locking_processes(path: &std::path::Path) -> Result<Vec<LockingProcess>, CommandError> {
+    use windows::core::{HSTRING, PCWSTR, PWSTR};
+    use windows::Win32::Foundation::ERROR_MORE_DATA;
+    use windows::Win32::System::RestartManager::{
+        RmEndSession, RmGetList, RmRegisterResources, RmStartSession, CCH_RM_SESSION_KEY, RM_PROCESS_INFO,
+    };
+
+    let wide_path = HSTRING::from(path.as_os_str());
+    unsafe {
+        let mut session = 0u32;
+        let mut session_key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+        RmStartSession(&mut session, None, PWSTR(session_key.as_mut_ptr()))
+            .ok()
+            .map_err(|e| CommandError::SystemError(format!("Restart Manager unavailable: {}", e)))?;
+
+        let result = (|| -> windows::core::Result<Vec<RM_PROCESS_INFO>> {
+            RmRegisterResources(session, Some(&[PCWSTR(wide_path.as_ptr())]), None, None).ok()?;
+
+            // The list can grow between the size query and the actual call, hence the loop
+            let mut processes: Vec<RM_PROCESS_INFO> = Vec::new();
+            loop {
+                let mut needed = 0u32;
+                let mut count = processes.len() as u32;
+                let mut reboot_reasons = 0u32;
+                let status = RmGetList(
+                    session,
+                    &mut needed,
+                    &mut count,
+                    if processes.is_empty() { None } else { Some(processes.as_mut_ptr()) },
+                    &mut reboot_reasons,
+                );
+                if status == ERROR_MORE_DATA {
+                    processes.resize(needed as usize, RM_PROCESS_INFO::default());
+                    continue;
+                }
+                status.ok()?;
+                processes.truncate(count as usize);
+                return Ok(processes);
+            }
+        })();
+        let _ = RmEndSession(session);
+
+        let processes = result.map_err(|e| CommandError::SystemError(e.to_string()))?;
+        Ok(processes.iter().map(|p| {
+            let name = String::from_utf16_lossy(&p.strAppName);
+            let name = name.trim_end_matches('\0');
+            LockingProcess {
+                pid: p.Process.dwProcessId,
+                start_time: filetime_ticks(&p.Process.ProcessStartTime),
+                name: if name.is_empty() { format!("PID {}", p.Process.dwProcessId) } else { name.to_string() },
+            }
+        }).collect())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn locking_processes(_path: &std::path::Path) -> Result<Vec<LockingProcess>, CommandError> {
+    Ok(Vec::new())
+}
+
+/// Terminates a process found by `get_locking_processes`, given its `pid` and `start_time`.
+/// The start time guards against the PID having been reused by another process since.
+/// Unsaved work in it is lost.
+#[tauri::command]
+pub fn close_locking_process(pid: u32, start_time: u64) -> Result<(), CommandError> {
+    if pid == std::process::id() {
+        return Err(CommandError::Other("The file is held by this app itself".to_string()));
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Foundation::{CloseHandle, FILETIME};
+        use windows::Win32::System::Threading::{
+            GetProcessTimes, OpenProcess, TerminateProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+        };
+
+        unsafe {
+            let handle = OpenProcess(PROCESS_TERMINATE | PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+                .map_err(|e| CommandError::SystemError(format!("Cannot open process {}: {}", pid, e)))?;
+            let (mut created, mut exited, mut kernel, mut user) =
+                (FILETIME::default(), FILETIME::default(), FILETIME::default(), FILETIME::default());
+            let same_process = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user).is_ok()
+                && filetime_ticks(&created) == start_time;
+            let result = if same_process {
+                TerminateProcess(handle, 1)
+                    .map_err(|e| CommandError::SystemError(format!("Cannot terminate process {}: {}", pid, e)))
+            } else {
+                Err(CommandError::Other(format!("Process {} has already exited", pid)))
+            };
+            let _ = CloseHandle(handle);
+            result?;
+        }
+        info!("Terminated process {} holding a file", pid);
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = start_time;
+        Err(CommandError::Other("Closing processes is only available on Windows".to_string()))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn filetime_ticks(time: &windows::Win32::Foundation::FILETIME) -> u64 {
+    ((time.dwHighDateTime as u64) << 32) | time.dwLowDateTime as u64
+}
+
+/// Most mail providers reject messages above ~25 MB.
+const MAIL_ATTACHMENT_LIMIT_BYTES: u64 = 25 * 1024 * 1024;
+
+/// "Send to > Mail recipient": opens a new message in the default mail client (Simple MAPI)
+/// with `paths` attached. Only files can be attached; the call returns once the compose
+/// window is closed or sent. Attachments over the 25 MB limit fail before any window opens,
+/// unless `force` is set once the user has confirmed.
+#[tauri::command]
+pub async fn send_to_mail(paths: Vec<String>, force: Option<bool>) -> Result<MailSendResult, CommandError> {
+    if paths.is_empty() {
+        return Err(CommandError::Other("No files to send".to_string()));
+    }
+    let mut files = Vec::with_capacity(paths.len());
+    let mut total_bytes = 0u64;
+    for path in &paths {
+        let pb = validate_path(path)?;
+        let metadata = std::fs::metadata(&pb).map_err(|e| CommandError::IoError(format!("{}: {}", pb.display(), e)))?;
+        if !metadata.is_file() {
+            return Err(CommandError::PathError(format!("Only files can be attached: {}", pb.display())));
+        }
+        total_bytes += metadata.len();
+        files.push(pb);
+    }
+
+    let oversized = total_bytes > MAIL_ATTACHMENT_LIMIT_BYTES;
+    if oversized && !force.unwrap_or(false) {
+        return Err(CommandError::Other(format!(
+            "Attachments total {} MB, many mail servers reject messages over 25 MB",
+            total_bytes / (1024 * 1024)
+        )));
+    }
+    let warning = oversized.then(|| format!("Sent {} MB of attachments, past the usual 25 MB limit", total_bytes / (1024 * 1024)));
+    let attached = files.len();
+    tauri::async_runtime::spawn_blocking(move || mapi_send_mail(&files))
+        .await
+        .map_err(|e| CommandError::SystemError(e.to_string()))??;
+
+    Ok(MailSendResult { attached, total_bytes, warning })
+}
+
+#[cfg(target_os = "windows")]
+fn mapi_send_mail(files: &[PathBuf]) -> Result<(), CommandError> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::{s, w, PWSTR};
+    use windows::Win32::Foundation::FreeLibrary;
+    use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+    use windows::Win32::System::Mapi::{MapiFileDescW, MapiMessageW};
+
+    type MapiSendMailW = unsafe extern "system" fn(usize, usize, *const MapiMessageW, u32, u32) -> u32;
+    const MAPI_LOGON_UI: u32 = 0x1;
+    const MAPI_DIALOG: u32 = 0x8;
+    const SUCCESS_SUCCESS: u32 = 0;
+    const MAPI_E_USER_ABORT: u32 = 1;
+    let no_client = || CommandError::SystemError("No MAPI mail client is installed or set as default".to_string());
+
+    let to_wide = |s: &std::ffi::OsStr| s.encode_wide().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let mut paths: Vec<Vec<u16>> = files.iter().map(|f| to_wide(f.as_os_str())).collect();
+    let mut names: Vec<Vec<u16>> = files.iter().map(|f| to_wide(f.file_name().unwrap_or_default())).collect();
+    let mut descs: Vec<MapiFileDescW> = paths
+        .iter_mut()
+        .zip(names.iter_mut())
+        .map(|(path, name)| MapiFileDescW {
+            nPosition: u32::MAX,
+            lpszPathName: PWSTR(path.as_mut_ptr()),
+            lpszFileName: PWSTR(name.as_mut_ptr()),
+            ..Default::default()
+        })
+        .collect();
+    let message = MapiMessageW {
+        nFileCount: descs.len() as u32,
+        lpFiles: descs.as_mut_ptr(),
+        ..Default::default()
+    };
+
+    unsafe {
+        let module = LoadLibraryW(w!("MAPI32.DLL")).map_err(|_| no_client())?;
+        let result = match GetProcAddress(module, s!("MAPISendMailW")) {
+            Some(proc) => {
+                let send: MapiSendMailW = std::mem::transmute(proc);
+                Ok(send(0, 0, &message, MAPI_LOGON_UI | MAPI_DIALOG, 0))
+            }
+            None => Err(no_client()),
+        };
+        let _ = FreeLibrary(module);
+
+        match result? {
+            SUCCESS_SUCCESS | MAPI_E_USER_ABORT => Ok(()),
+            // MAPI_E_FAILURE, MAPI_E_LOGIN_FAILURE, MAPI_E_NOT_SUPPORTED: no usable client behind the stub
+            2 | 3 | 26 => Err(no_client()),
+            code => Err(CommandError::SystemError(format!("MAPISendMailW failed with code {}", code))),
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn mapi_send_mail(_files: &[PathBuf]) -> Result<(), CommandError> {
+    Err(CommandError::SystemError("Sending mail is only available on Windows".to_string()))
+}
+
+/// Image kinds (see `detect_file`) Windows takes directly as a wallpaper; others are converted to JPEG.
+const NATIVE_WALLPAPER_KINDS: &[&str] = &["jpeg", "bmp"];
+
+/// Sets `path` as the desktop wallpaper with the given layout. The file must be an image by
+/// content; formats Windows can't use directly are converted to a JPEG in the app cache first.
+#[tauri::command]
+pub async fn set_as_wallpaper(app: AppHandle, path: String, style: WallpaperStyle) -> Result<(), CommandError> {
+    use tauri::Manager;
+
+    let source = validate_path(&path)?;
+    let kind = crate::utils::file_type::detect_file(&source)?
+        .filter(|signature| signature.mime.starts_with("image/"))
+        .ok_or_else(|| CommandError::Other(format!("Not an image: {}", source.display())))?
+        .kind;
+    let cache_dir = app.path().app_cache_dir().map_err(|e| CommandError::IoError(e.to_string()))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let image = if NATIVE_WALLPAPER_KINDS.contains(&kind) { source } else { convert_wallpaper(&source, &cache_dir)? };
+        info!("Setting wallpaper to {:?} ({:?})", image, style);
+        apply_wallpaper(&image, style)
+    })
+    .await
+    .map_err(|e| CommandError::SystemError(format!("Task join error: {}", e)))?
+}
+
+fn convert_wallpaper(source: &std::path::Path, cache_dir: &std::path::Path) -> Result<PathBuf, CommandError> {
+    let img = image::open(source).map_err(|e| CommandError::Other(format!("Failed to open image: {}", e)))?;
+    std::fs::create_dir_all(cache_dir)?;
+    // Kept after the call: Windows reads the file again when it re-applies the wallpaper
+    let target = cache_dir.join("wallpaper.jpg");
+    img.to_rgb8()
+        .save_with_format(&target, image::ImageFormat::Jpeg)
+        .map_err(|e| CommandError::Other(format!("Failed to convert image: {}", e)))?;
+    Ok(target)
+}
+
+#[cfg(target_os = "windows")]
+fn apply_wallpaper(image: &std::path::Path, style: WallpaperStyle) -> Result<(), CommandError> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegCloseKey, RegOpenKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_SET_VALUE, REG_SZ};
+    use windows::Win32::UI::WindowsAndMessaging::{SystemParametersInfoW, SPI_SETDESKWALLPAPER, SPIF_SENDCHANGE, SPIF_UPDATEINIFILE};
+
+    // Control Panel\Desktop values read by Explorer: (WallpaperStyle, TileWallpaper)
+    let (wallpaper_style, tile) = match style {
+        WallpaperStyle::Fill => ("10", "0"),
+        WallpaperStyle::Fit => ("6", "0"),
+        WallpaperStyle::Stretch => ("2", "0"),
+        WallpaperStyle::Tile => ("0", "1"),
+        WallpaperStyle::Center => ("0", "0"),
+    };
+    let reg_sz = |value: &str| value.encode_utf16().chain(std::iter::once(0)).flat_map(u16::to_le_bytes).collect::<Vec<u8>>();
+
+    unsafe {
+        let mut hkey = HKEY::default();
+        let opened = RegOpenKeyExW(HKEY_CURRENT_USER, w!("Control Panel\\Desktop"), Some(0), KEY_SET_VALUE, &mut hkey);
+        if opened.is_err() {
+            return Err(CommandError::SystemError(format!("Failed to open desktop settings: {:?}", opened)));
+        }
+        let styled = RegSetValueExW(hkey, w!("WallpaperStyle"), Some(0), REG_SZ, Some(&reg_sz(wallpaper_style)));
+        let tiled = RegSetValueExW(hkey, w!("TileWallpaper"), Some(0), REG_SZ, Some(&reg_sz(tile)));
+        let _ = RegCloseKey(hkey);
+        if styled.is_err() || tiled.is_err() {
+            return Err(CommandError::SystemError("Failed to save the wallpaper style".to_string()));
+        }
+
+        let mut wide: Vec<u16> = image.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+        SystemParametersInfoW(SPI_SETDESKWALLPAPER, 0, Some(wide.as_mut_ptr() as *mut _), SPIF_UPDATEINIFILE | SPIF_SENDCHANGE)
+            .map_err(|e| CommandError::SystemError(format!("Failed to set wallpaper: {}", e)))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn apply_wallpaper(_image: &std::path::Path, _style: WallpaperStyle) -> Result<(), CommandError> {
+    Err(CommandError::SystemError("Setting the wallpaper is only available on Windows".to_string()))
+}
+
+/// Launch order for a requested terminal: the requested one first, then the built-in fallbacks.
+#[cfg(target_os = "windows")]
+fn terminal_candidates(kind: &str) -> Vec<&'static str> {
+    match kind {
+        "cmd" => vec!["cmd"],
+        "powershell" => vec!["powershell", "cmd"],
+        _ => vec!["wt", "powershell", "cmd"],
+    }
+}
+
+#[tauri::command]
+pub async fn open_terminal(path: String, kind: Option<String>) -> Result<(), CommandError> {
+    let dir = validate_path(&path)?;
+    if !dir.is_dir() {
+        return Err(CommandError::PathError(format!("Not a directory: {}", dir.display())));
+    }
+    let kind = kind.unwrap_or_else(|| "wt".to_string()).to_lowercase();
+    info!("Opening terminal ({}) in {:?}", kind, dir);
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NEW_CONSOLE: give the shell its own visible window instead of inheriting ours
+        const CREATE_NEW_CONSOLE: u32 = 0x00000010;
+
+        let mut last_error = String::new();
+        for candidate in terminal_candidates(&kind) {
+            let mut cmd = Command::new(candidate);
+            match candidate {
+                "wt" => { cmd.arg("-d").arg(&dir); }
+                "powershell" => { cmd.arg("-NoExit").creation_flags(CREATE_NEW_CONSOLE); }
+                _ => { cmd.arg("/K").creation_flags(CREATE_NEW_CONSOLE); }
+            }
+            match cmd.current_dir(&dir).spawn() {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    info!("Terminal '{}' unavailable: {}", candidate, e);
+                    last_error = format!("{}: {}", candidate, e);
+                }
+            }
+        }
+        Err(CommandError::SystemError(format!("Failed to open a terminal ({})", last_error)))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        Command::new("open")
+            .args(["-a", "Terminal"])
+            .arg(&dir)
+            .spawn()
+            .map_err(|e| CommandError::SystemError(e.to_string()))?;
+        Ok(())
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        for candidate in ["x-terminal-emulator", "gnome-terminal", "konsole", "xterm"] {
+            if Command::new(candidate).current_dir(&dir).spawn().is_ok() {
+                return Ok(());
+            }
+        }
+        Err(CommandError::SystemError("No terminal emulator found".to_string()))
+    }
+}
+
+/// Safely removes a removable (USB) drive: closes tabs on it, then ejects it through the Shell,
+/// which flushes pending writes before releasing the device.
+#[tauri::command]
+pub async fn eject_drive(
+    app: AppHandle,
+    state: State<'_, SessionManager>,
+    drive_letter: String
+) -> Result<(), CommandError> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::core::PCWSTR;
+        use windows::Win32::Storage::FileSystem::GetDriveTypeW;
+
+        let letter = drive_letter.trim().chars().next()
+            .filter(|c| c.is_ascii_alphabetic())
+            .ok_or_else(|| CommandError::PathError(format!("Invalid drive letter: {}", drive_letter)))?
+            .to_ascii_uppercase();
+
+        let root: Vec<u16> = format!("{}:\\", letter).encode_utf16().chain(std::iter::once(0)).collect();
+        // DRIVE_REMOVABLE, the same value get_drives reports as "removable"
+        if unsafe { GetDriveTypeW(PCWSTR(root.as_ptr())) } != 2 {
+            return Err(CommandError::Other(format!("{}: is not a removable drive", letter)));
+        }
+
+        info!("Ejecting removable drive {}:", letter);
+        close_tabs_on_drives(&app, &state, &[format!("{}:", letter.to_ascii_lowercase())])?;
+
+        if !eject_volume_via_shell(letter)? {
+            return Err(CommandError::SystemError(format!("Device {}: is in use by another program and cannot be ejected.", letter)));
+        }
+
+        unsafe {
+            use windows::Win32::UI::Shell::{SHChangeNotify, SHCNE_DRIVEREMOVED, SHCNF_PATHW};
+            SHChangeNotify(SHCNE_DRIVEREMOVED, SHCNF_PATHW, Some(root.as_ptr() as *const _), None);
+        }
+
+        let _ = app.emit("drives-changed", ());
+        Ok(())
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = (app, state, drive_letter);
+        Err(CommandError::SystemError("Drive ejection is only supported on Windows".to_string()))
+    }
+}
+
+/// Starts polling free space on the active tabs' drives. Events are sent as `drive_space_changed`
+/// whenever a drive's free space moves by `threshold_mb` or more.
+#[tauri::command]
+pub fn start_drive_space_monitor(
+    app: AppHandle,
+    monitor: State<'_, crate::systems::drive_space::DriveSpaceMonitor>,
+    interval_ms: Option<u64>,
+    threshold_mb: Option<u64>
+) {
+    let interval = std::time::Duration::from_millis(interval_ms.unwrap_or(5000).max(500));
+    let threshold = threshold_mb.unwrap_or(100) * 1024 * 1024;
+    monitor.start(app.clone(), interval, threshold);
+}
+
+#[tauri::command]
+pub fn stop_drive_space_monitor(monitor: State<'_, crate::systems::drive_space::DriveSpaceMonitor>) {
+    monitor.stop();
+}
+
+/// Sends `file_changed` (new size and modification time) whenever `path` is written, debounced,
+/// e.g. to refresh the preview of a growing log. Watching an already watched file is a no-op.
+#[tauri::command]
+pub fn watch_file(
+    app: AppHandle,
+    watchers: State<'_, crate::systems::file_watcher::FileWatchManager>,
+    path: String,
+) -> Result<(), CommandError> {
+    let pb = validate_path(&path)?;
+    if !pb.is_file() {
+        return Err(CommandError::PathError(format!("Not a file: {}", pb.display())));
+    }
+    watchers.watch(app, pb)
+        .map_err(|e| CommandError::SystemError(format!("Failed to watch file: {}", e)))
+}
+
+#[tauri::command]
+pub fn unwatch_file(
+    watchers: State<'_, crate::systems::file_watcher::FileWatchManager>,
+    path: String,
+) -> Result<(), CommandError> {
+    let pb = validate_path(&path)?;
+    watchers.unwatch(&pb);
+    Ok(())
+}
@@ -33,6 +33,39 @@ pub async fn get_office_thumbnail(
     }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
 }
 
+/// Shell-provided thumbnail for any file type (see `get_shell_thumbnail_cached`); `size` is in pixels.
+#[tauri::command]
+pub async fn get_shell_thumbnail(
+    app: AppHandle,
+    path: String,
+    size: u32,
+) -> Result<String> {
+    let cache_dir = app.path().app_cache_dir()
+        .map_err(|e| CommandError::IoError(e.to_string()))?
+        .join("thumbnails");
+
+    tokio::task::spawn_blocking(move || {
+        crate::utils::thumbnails::get_shell_thumbnail_cached(path, size, cache_dir)
+    }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
+}
+
+/// Montage of the first images of a folder, for use as its cover in grid view (see
+/// `get_folder_preview_cached`). Returns the JPEG bytes.
+#[tauri::command]
+pub async fn get_folder_preview(
+    app: AppHandle,
+    path: String,
+    max_images: usize,
+) -> Result<Vec<u8>> {
+    let cache_dir = app.path().app_cache_dir()
+        .map_err(|e| CommandError::IoError(e.to_string()))?
+        .join("thumbnails");
+
+    tokio::task::spawn_blocking(move || {
+        crate::utils::thumbnails::get_folder_preview_cached(path, max_images, cache_dir)
+    }).await.map_err(|e| CommandError::Other(format!("Thread panic: {}", e)))?
+}
+
 #[tauri::command]
 pub async fn get_office_text_preview(
     path: String,
@@ -96,18 +96,39 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
 
         .manage(ArchiveState(AtomicBool::new(false)))
+        .manage(commands::archive::ArchiveTempFiles::default())
         .manage(systems::file_ops::FileOperationManager::new())
         .manage(models::SessionManager::default())
         .manage(models::ConfigManager::new())
         .manage(models::HistoryManager::default())
+        .manage(models::ActivityLog::default())
+        .manage(models::FavoriteFiles::default())
         .manage(commands::duplicates::DuplicateSearchState::new())
+        .manage(commands::io::AttributeOpState::default())
+        .manage(commands::split::SplitJoinState::default())
+        .manage(commands::image::ImageBatchState::default())
+        .manage(commands::io::FolderSizeState::default())
+        .manage(commands::io::FolderCountState::default())
+        .manage(commands::io::DirStreamState::default())
+        .manage(systems::drive_space::DriveSpaceMonitor::default())
+        .manage(systems::file_watcher::FileWatchManager::default())
+        .manage(systems::search_index::SearchIndexManager::default())
         .invoke_handler(tauri::generate_handler![
             commands::io::list_dir,
+            commands::io::list_dir_streaming,
+            commands::io::cancel_dir_stream,
+            commands::io::match_entries,
+            commands::io::toggle_favorite,
+            commands::io::list_favorite_files,
             commands::system::get_drives,
+            commands::system::get_breadcrumbs,
             commands::system::open_item,
             commands::ops::delete_items,
+            commands::ops::check_recycle_bin_support,
             commands::ops::copy_items,
             commands::ops::move_items,
+            commands::ops::duplicate_items,
+            commands::ops::group_into_new_folder,
             commands::ops::cancel_file_operation,
             commands::ops::pause_file_operation,
             commands::ops::resume_file_operation,
@@ -116,12 +137,16 @@ pub fn run() {
             commands::system::get_accent_color,
             commands::io::get_file_properties,
             commands::io::get_files_summary,
+            commands::io::summarize_selection_by_type,
             commands::io::show_system_properties,
             commands::search::start_search,
             commands::search::cancel_search,
+            commands::search::export_search_results,
+            commands::search::build_search_index,
             commands::io::rename_item,
             commands::io::create_dir,
             commands::ops::check_conflicts,
+            commands::ops::estimate_operation_size,
             commands::ops::restore_items,
             commands::ops::list_trash,
             commands::ops::empty_trash,
@@ -129,6 +154,12 @@ pub fn run() {
             commands::ops::purge_recycle_bin,
             commands::ops::move_from_trash,
             commands::ops::get_history,
+            commands::ops::clear_history,
+            commands::ops::get_activity_log,
+            commands::split::split_file,
+            commands::split::join_files,
+            commands::split::cancel_split_join,
+            commands::ops::clear_activity_log,
             commands::ops::undo_last_action,
             commands::ops::redo_last_action,
             commands::clipboard::get_clipboard_files,
@@ -136,7 +167,13 @@ pub fn run() {
             commands::clipboard::set_clipboard_from_trash,
             commands::clipboard::get_clipboard_text,
             commands::clipboard::set_clipboard_text,
+            commands::clipboard::begin_drag,
             commands::io::calculate_folder_size,
+            commands::io::calculate_folder_size_live,
+            commands::io::cancel_folder_size,
+            commands::io::index_folder_counts,
+            commands::io::cancel_folder_counts,
+            commands::io::count_dir_entries,
             commands::system::set_webview_background,
             commands::system::show_native_context_menu,
             commands::system::get_native_context_menu_items,
@@ -150,16 +187,43 @@ pub fn run() {
             commands::system::remove_from_quick_access,
             commands::system::clear_app_cache,
             commands::system::restart_app,
+            commands::system::is_elevated,
+            commands::system::relaunch_as_admin,
             commands::io::set_shortcut_info,
+            commands::io::create_shortcut,
+            commands::io::get_url_shortcut,
+            commands::io::get_tree_listing,
+            commands::io::set_file_attributes,
+            commands::io::probe_path,
+            commands::io::detect_file_type,
+            commands::io::set_file_times,
+            commands::io::set_attributes_recursive,
+            commands::io::cancel_set_attributes,
+            commands::io::set_ntfs_compression,
+            commands::io::get_file_owner,
+            commands::io::take_ownership,
+            commands::io::grant_full_control,
 
             commands::icons::get_file_icon,
+            commands::icons::get_file_icons,
+            commands::icons::get_default_app,
             commands::icons::purge_icon_cache,
             commands::thumbnails::get_image_thumbnail,
             commands::thumbnails::get_office_thumbnail,
+            commands::thumbnails::get_shell_thumbnail,
+            commands::thumbnails::get_folder_preview,
             commands::thumbnails::get_office_text_preview,
             commands::archive::list_archive_contents,
             commands::archive::extract_archive,
+            commands::archive::extract_archive_entries,
+            commands::archive::read_archive_entry,
+            commands::archive::open_archive_entry,
+            commands::archive::get_modified_archive_entries,
+            commands::archive::save_archive_entry,
+            commands::archive::test_archive,
+            commands::archive::verify_archive_against_folder,
             commands::archive::compress_to_archive,
+            commands::archive::estimate_compression,
             commands::archive::add_to_archive,
             commands::archive::cancel_archive_operation,
             // Session Commands
@@ -168,6 +232,17 @@ pub fn run() {
             commands::session::close_tab,
             commands::session::switch_tab,
             commands::session::active_tab_navigate,
+            commands::session::reveal_in_panel,
+            commands::session::go_to_bookmark,
+            commands::session::load_workspace,
+            commands::session::set_tab_color,
+            commands::session::toggle_tab_pin,
+            commands::session::reopen_closed_tab,
+            commands::session::move_tab_to_panel,
+            commands::session::set_sync_navigation,
+            commands::session::swap_panels,
+            commands::session::copy_path_to_other_panel,
+            commands::session::set_panel_filter,
             commands::session::duplicate_tab,
             commands::session::close_other_tabs,
             commands::session::reorder_tabs,
@@ -176,14 +251,50 @@ pub fn run() {
             // Config Commands
             commands::config::get_config,
             commands::config::set_config_value,
+            commands::config::get_config_schema,
             commands::config::reset_config_to_default,
+            commands::config::export_config,
+            commands::config::import_config,
+            commands::config::save_search,
+            commands::config::list_saved_searches,
+            commands::config::delete_saved_search,
+            commands::config::add_favorite,
+            commands::config::remove_favorite,
+            commands::config::reorder_favorites,
+            commands::config::list_favorites,
+            commands::config::set_bookmark,
+            commands::config::remove_bookmark,
+            commands::config::get_bookmarks,
+            commands::config::save_workspace,
+            commands::config::list_workspaces,
+            commands::config::delete_workspace,
+            commands::config::save_folder_view,
+            commands::config::clear_folder_view,
             commands::sidebar::get_sidebar_nodes,
             commands::sidebar::get_subtree_nodes,
             commands::duplicates::find_duplicates,
+            commands::duplicates::find_similar_images,
             commands::duplicates::cancel_find_duplicates,
+            commands::duplicates::files_equal,
             commands::system::get_peek_status,
             commands::system::open_peek,
+            commands::system::send_to_mail,
+            commands::system::set_as_wallpaper,
+            commands::system::get_locking_processes,
+            commands::system::close_locking_process,
+            commands::shadow::list_shadow_versions,
+            commands::shadow::restore_shadow_version,
+            commands::image::transform_image,
+            commands::image::batch_convert_images,
+            commands::image::cancel_image_batch,
+            commands::system::open_terminal,
+            commands::system::eject_drive,
+            commands::system::start_drive_space_monitor,
+            commands::system::stop_drive_space_monitor,
+            commands::system::watch_file,
+            commands::system::unwatch_file,
             commands::network::get_network_resources,
+            commands::network::list_network_resources,
             commands::network::map_network_drive,
             commands::network::disconnect_network_drive,
         ])
@@ -195,6 +306,9 @@ pub fn run() {
             if let Err(e) = config_manager.load(app.handle()) {
                 eprintln!("Failed to load config: {:?}", e);
             }
+            if let Ok(config) = config_manager.0.lock() {
+                app.state::<models::HistoryManager>().set_max_depth(config.max_undo_depth);
+            }
 
             let session_manager = app.state::<models::SessionManager>();
             println!("DEBUG: loading session...");
@@ -203,6 +317,14 @@ pub fn run() {
             }
             println!("DEBUG: session loaded");
 
+            if let Err(e) = app.state::<models::ActivityLog>().load(app.handle()) {
+                eprintln!("Failed to load activity log: {:?}", e);
+            }
+
+            if let Err(e) = app.state::<models::FavoriteFiles>().load(app.handle()) {
+                eprintln!("Failed to load favorite files: {:?}", e);
+            }
+
             // Register WindowState
             let window_state = WindowState::default();
             app.manage(window_state);
@@ -223,11 +345,23 @@ pub fn run() {
             }
             }
 
+            let search_index = app.state::<systems::search_index::SearchIndexManager>();
+            if let Err(e) = search_index.load(app.handle()) {
+                eprintln!("Failed to load search indexes: {:?}", e);
+            }
+
             // Start Quick Access Watcher
             systems::quick_access_watcher::setup_quick_access_watcher(app.handle().clone());
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<commands::archive::ArchiveTempFiles>().cleanup();
+                commands::image::clear_edit_backups();
+                app.state::<systems::file_watcher::FileWatchManager>().clear();
+            }
+        });
 }
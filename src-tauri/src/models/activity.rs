@@ -0,0 +1,118 @@
+use std::fs;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::models::CommandError;
+
+/// Entries kept in `activity_log.json`; the oldest are dropped past this.
+pub const MAX_ACTIVITY_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    Copy,
+    Move,
+    Delete,
+    Trash,
+    Extract,
+    Compress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "status", content = "message", rename_all = "camelCase")]
+pub enum ActivityOutcome {
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+/// One finished operation in the activity panel. Unlike `Transaction`s these are only a
+/// record: they can't be undone and aren't affected by the undo depth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub id: String,
+    pub timestamp: i64,
+    pub kind: ActivityKind,
+    pub sources: Vec<String>,
+    pub destination: Option<String>,
+    pub item_count: usize,
+    pub total_bytes: u64,
+    pub outcome: ActivityOutcome,
+}
+
+impl ActivityEntry {
+    pub fn new(kind: ActivityKind, sources: Vec<String>, destination: Option<String>, total_bytes: u64, outcome: ActivityOutcome) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            kind,
+            item_count: sources.len(),
+            sources,
+            destination,
+            total_bytes,
+            outcome,
+        }
+    }
+}
+
+/// Chronological log of completed operations, persisted across sessions.
+#[derive(Default)]
+pub struct ActivityLog(pub Mutex<Vec<ActivityEntry>>);
+
+impl ActivityLog {
+    /// Appends `entry`, saves the log and emits `activity_log_update`.
+    pub fn record(&self, app: &AppHandle, entry: ActivityEntry) {
+        if let Ok(mut entries) = self.0.lock() {
+            entries.push(entry);
+            if entries.len() > MAX_ACTIVITY_ENTRIES {
+                let excess = entries.len() - MAX_ACTIVITY_ENTRIES;
+                entries.drain(..excess);
+            }
+        }
+        if let Err(e) = self.save(app) {
+            log::error!("Failed to save activity log: {}", e);
+        }
+        let _ = app.emit("activity_log_update", ());
+    }
+
+    /// Newest entries first, at most `limit` of them.
+    pub fn recent(&self, limit: usize) -> Vec<ActivityEntry> {
+        self.0.lock()
+            .map(|entries| entries.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self, app: &AppHandle) -> Result<(), CommandError> {
+        self.0.lock().map_err(|_| CommandError::SystemError("Failed to lock activity log".to_string()))?.clear();
+        self.save(app)?;
+        let _ = app.emit("activity_log_update", ());
+        Ok(())
+    }
+
+    pub fn save(&self, app_handle: &AppHandle) -> Result<(), CommandError> {
+        let entries = self.0.lock().map_err(|_| CommandError::SystemError("Failed to lock activity log".to_string()))?;
+        let config_dir = app_handle.path().app_config_dir().map_err(|e| CommandError::IoError(e.to_string()))?;
+
+        if !config_dir.exists() {
+            fs::create_dir_all(&config_dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+
+        let json = serde_json::to_string(&*entries).map_err(|e| CommandError::Other(e.to_string()))?;
+        fs::write(config_dir.join("activity_log.json"), json).map_err(|e| CommandError::IoError(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn load(&self, app_handle: &AppHandle) -> Result<(), CommandError> {
+        let config_dir = app_handle.path().app_config_dir().map_err(|e| CommandError::IoError(e.to_string()))?;
+        let log_path = config_dir.join("activity_log.json");
+
+        if log_path.exists() {
+            let content = fs::read_to_string(log_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+            match serde_json::from_str::<Vec<ActivityEntry>>(&content) {
+                Ok(loaded) => *self.0.lock().map_err(|_| CommandError::SystemError("Failed to lock activity log".to_string()))? = loaded,
+                Err(e) => log::error!("Failed to parse activity_log.json: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
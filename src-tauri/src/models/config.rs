@@ -1,14 +1,78 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::sync::Mutex;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Emitter, Manager};
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Registry::{RegOpenKeyExW, RegQueryValueExW, HKEY_CURRENT_USER, KEY_READ};
 #[cfg(target_os = "windows")]
 use windows::core::PCWSTR;
 use crate::models::CommandError;
+use crate::models::session::SortConfig;
+
+/// Search form values stored in a saved search (mirrors the `start_search` arguments).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchPreset {
+    pub query: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub recursive: bool,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub min_date: Option<u64>,
+    pub max_date: Option<u64>,
+    pub content_query: Option<String>,
+    pub content_regex: bool,
+    pub ignore_accents: bool,
+    pub search_in_archives: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    #[serde(flatten)]
+    pub params: SearchPreset,
+}
+
+/// A folder pinned to the app's own favorites list, independent of Windows Quick Access.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Favorite {
+    pub path: String,
+    pub name: String,
+}
+
+/// Tab folders of one panel in a saved workspace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspacePanel {
+    pub tabs: Vec<String>,
+    /// Index into `tabs` of the tab that was active
+    pub active_tab: usize,
+}
+
+/// A named tab layout of both panels, restored by `load_workspace`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    pub left: WorkspacePanel,
+    pub right: WorkspacePanel,
+    pub active_panel: String,
+}
+
+/// Highest numbered bookmark slot; slots run from 1 to this.
+pub const MAX_BOOKMARK_SLOT: u8 = 9;
+
+/// A numbered bookmark as shown to the UI. `exists` is false when the folder is gone,
+/// so it can be greyed out instead of silently disappearing.
+#[derive(Debug, Clone, Serialize)]
+pub struct Bookmark {
+    pub slot: u8,
+    pub path: String,
+    pub exists: bool,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
     pub theme: String,
     pub language: String,
@@ -27,6 +91,29 @@ pub struct AppConfig {
     pub show_grid_thumbnails: bool,
     pub show_checkboxes: bool,
     pub show_network: bool,
+    pub default_archive_format: String,
+    pub default_archive_level: String,
+    pub saved_searches: Vec<SavedSearch>,
+    pub favorites: Vec<Favorite>,
+    /// Copy buffer size used in turbo mode (normal mode uses half)
+    pub copy_buffer_kb: u32,
+    /// Read large same-volume files with FILE_FLAG_NO_BUFFERING (Windows only)
+    pub copy_unbuffered_io: bool,
+    /// Extra attempts on a file that is briefly locked (antivirus, indexer) during copy or delete
+    pub io_retry_attempts: u32,
+    /// Wait before the first retry, doubled on each following one
+    pub io_retry_delay_ms: u64,
+    /// Folder-name globs skipped by search and duplicate scans
+    pub ignore_patterns: Vec<String>,
+    /// Extract every file's own icon instead of sharing one per extension
+    pub exact_file_icons: bool,
+    /// Number of undoable actions kept; older ones are dropped
+    pub max_undo_depth: usize,
+    /// Sort remembered per folder, keyed by `folder_view_key`
+    pub folder_view_overrides: HashMap<String, SortConfig>,
+    /// Folders bound to the numbered bookmark slots (1 to `MAX_BOOKMARK_SLOT`)
+    pub bookmarks: BTreeMap<u8, String>,
+    pub workspaces: Vec<Workspace>,
 }
 
 impl Default for AppConfig {
@@ -99,10 +186,236 @@ impl Default for AppConfig {
             show_grid_thumbnails: false,
             show_checkboxes: false,
             show_network: true,
+            default_archive_format: "zip".to_string(),
+            default_archive_level: "normal".to_string(),
+            saved_searches: Vec::new(),
+            favorites: Vec::new(),
+            copy_buffer_kb: 1024,
+            copy_unbuffered_io: false,
+            io_retry_attempts: 3,
+            io_retry_delay_ms: 100,
+            ignore_patterns: default_ignore_patterns(),
+            exact_file_icons: false,
+            max_undo_depth: crate::models::history::DEFAULT_MAX_UNDO_DEPTH,
+            folder_view_overrides: HashMap::new(),
+            bookmarks: BTreeMap::new(),
+            workspaces: Vec::new(),
         }
     }
 }
 
+fn default_ignore_patterns() -> Vec<String> {
+    ["node_modules", ".git", "$Recycle.Bin", "System Volume Information"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
+/// Folder paths are compared without trailing separators, and case-insensitively on Windows.
+pub fn folder_view_key(path: &str) -> String {
+    let trimmed = path.trim_end_matches(['\\', '/']);
+    let key = match trimmed {
+        "" => path.to_string(),
+        // Drive roots keep their separator: "C:" and "C:\" are both "C:\"
+        t if t.ends_with(':') => format!("{}\\", t),
+        t => t.to_string(),
+    };
+    if cfg!(target_os = "windows") { key.to_lowercase() } else { key }
+}
+
+impl AppConfig {
+    /// Copy buffer size in bytes, clamped to 64 KB..64 MB. Normal (non-turbo) mode uses half.
+    pub fn copy_buffer_bytes(&self, turbo: bool) -> usize {
+        let kb = self.copy_buffer_kb.clamp(64, 64 * 1024) as usize;
+        let kb = if turbo { kb } else { (kb / 2).max(64) };
+        kb * 1024
+    }
+
+    /// Stored sort for `path`, if the user saved one for that folder.
+    pub fn folder_view_override(&self, path: &str) -> Option<&SortConfig> {
+        self.folder_view_overrides.get(&folder_view_key(path))
+    }
+
+    /// Falls back to "zip"/"normal" when the stored archive defaults are not supported.
+    pub fn normalize_archive_defaults(&mut self) {
+        use crate::utils::archive::{is_compression_format_supported, COMPRESSION_LEVELS};
+
+        if !is_compression_format_supported(&self.default_archive_format) {
+            self.default_archive_format = "zip".to_string();
+        }
+        if !COMPRESSION_LEVELS.contains(&self.default_archive_level.as_str()) {
+            self.default_archive_level = "normal".to_string();
+        }
+    }
+}
+
+/// Type and allowed values of a key accepted by `set_config_value`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigValueType {
+    Bool,
+    Integer { min: u64, max: u64 },
+    Choice { options: Vec<String> },
+    Text,
+    /// JSON array, or a `;`/newline separated list
+    List,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigKeySchema {
+    pub key: &'static str,
+    #[serde(flatten)]
+    pub value_type: ConfigValueType,
+    pub default: serde_json::Value,
+}
+
+fn choice(options: &[&str]) -> ConfigValueType {
+    ConfigValueType::Choice { options: options.iter().map(|o| o.to_string()).collect() }
+}
+
+/// Every user-settable config key. Lists, favorites, bookmarks, workspaces and folder views have their own commands and aren't here.
+pub fn config_schema() -> Vec<ConfigKeySchema> {
+    use crate::utils::archive::{COMPRESSION_FORMATS, COMPRESSION_LEVELS};
+    use ConfigValueType::*;
+
+    let entries: Vec<(&'static str, ConfigValueType)> = vec![
+        ("theme", Text),
+        ("language", choice(&["en", "fr"])),
+        ("layout", choice(&["standard", "dual"])),
+        ("show_hidden", Bool),
+        ("show_system", Bool),
+        ("use_system_icons", Bool),
+        ("date_format", choice(&["US", "European", "ISO"])),
+        ("show_previews", Bool),
+        ("zip_quality", choice(COMPRESSION_LEVELS)),
+        ("seven_zip_quality", choice(COMPRESSION_LEVELS)),
+        ("zstd_quality", choice(COMPRESSION_LEVELS)),
+        ("font_size", Integer { min: 8, max: 32 }),
+        ("search_limit", Integer { min: 1, max: 1_000_000 }),
+        ("default_turbo_mode", Bool),
+        ("show_grid_thumbnails", Bool),
+        ("show_checkboxes", Bool),
+        ("show_network", Bool),
+        ("default_archive_format", choice(COMPRESSION_FORMATS)),
+        ("default_archive_level", choice(COMPRESSION_LEVELS)),
+        ("copy_buffer_kb", Integer { min: 64, max: 64 * 1024 }),
+        ("copy_unbuffered_io", Bool),
+        ("io_retry_attempts", Integer { min: 0, max: 10 }),
+        ("io_retry_delay_ms", Integer { min: 0, max: 10_000 }),
+        ("exact_file_icons", Bool),
+        ("ignore_patterns", List),
+        ("max_undo_depth", Integer { min: 1, max: 10_000 }),
+    ];
+
+    let defaults = serde_json::to_value(AppConfig::default()).unwrap_or_default();
+    entries
+        .into_iter()
+        .map(|(key, value_type)| ConfigKeySchema { key, value_type, default: defaults[key].clone() })
+        .collect()
+}
+
+impl ConfigValueType {
+    /// Checks a value against this type and converts it to its JSON form.
+    pub fn validate(&self, key: &str, value: &serde_json::Value) -> Result<serde_json::Value, CommandError> {
+        let invalid = |expected: String| CommandError::InvalidConfig(format!("{}: expected {}, got {}", key, expected, value));
+        // Values coming from set_config_value are strings: accept "true", "42", ... as well
+        let text = value.as_str().map(|s| s.trim().to_string());
+
+        match self {
+            ConfigValueType::Bool => value.as_bool()
+                .or_else(|| text.as_deref().and_then(|t| t.parse().ok()))
+                .map(serde_json::Value::from)
+                .ok_or_else(|| invalid("true or false".to_string())),
+            ConfigValueType::Integer { min, max } => value.as_u64()
+                .or_else(|| text.as_deref().and_then(|t| t.parse().ok()))
+                .filter(|n| (*min..=*max).contains(n))
+                .map(serde_json::Value::from)
+                .ok_or_else(|| invalid(format!("an integer between {} and {}", min, max))),
+            ConfigValueType::Choice { options } => text
+                .and_then(|t| options.iter().find(|o| o.eq_ignore_ascii_case(&t)).cloned())
+                .map(serde_json::Value::from)
+                .ok_or_else(|| invalid(format!("one of {}", options.join(", ")))),
+            ConfigValueType::Text => text
+                .filter(|t| !t.is_empty())
+                .map(serde_json::Value::from)
+                .ok_or_else(|| invalid("a non-empty string".to_string())),
+            ConfigValueType::List => {
+                let items: Vec<String> = match (value, text) {
+                    (serde_json::Value::Array(_), _) => serde_json::from_value(value.clone()).map_err(|_| invalid("a list of strings".to_string()))?,
+                    (_, Some(t)) => serde_json::from_str::<Vec<String>>(&t).unwrap_or_else(|_| {
+                        t.split([';', '\n'])
+                            .map(|p| p.trim().to_string())
+                            .filter(|p| !p.is_empty())
+                            .collect()
+                    }),
+                    _ => return Err(invalid("a list of strings".to_string())),
+                };
+                Ok(serde_json::Value::from(items))
+            }
+        }
+    }
+}
+
+impl AppConfig {
+    /// Validates `value` against the schema and stores it. Unknown keys and out-of-range values
+    /// are rejected without touching the config.
+    pub fn set_value(&mut self, key: &str, value: &serde_json::Value) -> Result<(), CommandError> {
+        let schema = config_schema().into_iter().find(|s| s.key == key)
+            .ok_or_else(|| CommandError::InvalidConfig(format!("Unknown config key: {}", key)))?;
+        let value = schema.value_type.validate(key, value)?;
+
+        let mut json = serde_json::to_value(&*self).map_err(|e| CommandError::Other(e.to_string()))?;
+        json[key] = value;
+        *self = serde_json::from_value(json).map_err(|e| CommandError::InvalidConfig(format!("{}: {}", key, e)))?;
+        Ok(())
+    }
+}
+
+/// Keys from an imported config file that could not be taken as-is.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigImportReport {
+    /// Keys this version doesn't know (removed or renamed settings)
+    pub ignored: Vec<String>,
+    /// Keys that were missing or invalid and fell back to their default
+    pub defaulted: Vec<String>,
+}
+
+impl AppConfig {
+    /// Builds a config from an exported JSON object, validating each key against the schema.
+    /// Bad values fall back to their default instead of failing the whole import.
+    pub fn from_import(json: &serde_json::Value) -> Result<(AppConfig, ConfigImportReport), CommandError> {
+        let object = json.as_object()
+            .ok_or_else(|| CommandError::InvalidConfig("Expected a JSON object".to_string()))?;
+        let schema = config_schema();
+        let mut config = AppConfig::default();
+        let mut report = ConfigImportReport::default();
+
+        for (key, value) in object {
+            let applied = match key.as_str() {
+                "favorites" => serde_json::from_value(value.clone()).map(|f| config.favorites = f).is_ok(),
+                "saved_searches" => serde_json::from_value(value.clone()).map(|s| config.saved_searches = s).is_ok(),
+                "folder_view_overrides" => serde_json::from_value(value.clone()).map(|o| config.folder_view_overrides = o).is_ok(),
+                "workspaces" => serde_json::from_value(value.clone()).map(|w| config.workspaces = w).is_ok(),
+                "bookmarks" => serde_json::from_value::<BTreeMap<u8, String>>(value.clone())
+                    .map(|b| config.bookmarks = b.into_iter().filter(|(slot, _)| (1..=MAX_BOOKMARK_SLOT).contains(slot)).collect())
+                    .is_ok(),
+                _ if schema.iter().any(|s| s.key == key) => config.set_value(key, value).is_ok(),
+                _ => {
+                    report.ignored.push(key.clone());
+                    continue;
+                }
+            };
+            if !applied {
+                report.defaulted.push(key.clone());
+            }
+        }
+        report.defaulted.extend(schema.iter().filter(|s| !object.contains_key(s.key)).map(|s| s.key.to_string()));
+
+        config.normalize_archive_defaults();
+        Ok((config, report))
+    }
+}
+
 pub struct ConfigManager(pub Mutex<AppConfig>);
 
 impl Default for ConfigManager {
@@ -121,6 +434,7 @@ impl ConfigManager {
         self.save_config(app_handle, &config)
     }
 
+    /// Persists `config` and emits `config_changed` with it.
     pub fn save_config(&self, app_handle: &AppHandle, config: &AppConfig) -> Result<(), CommandError> {
         let config_dir = app_handle.path().app_config_dir().map_err(|e| CommandError::IoError(e.to_string()))?;
         
@@ -132,6 +446,9 @@ impl ConfigManager {
         let json = serde_json::to_string_pretty(config).map_err(|e| CommandError::Other(e.to_string()))?;
         
         fs::write(config_path, json).map_err(|e| CommandError::IoError(e.to_string()))?;
+
+        // Every change goes through here, so other windows and components stay in sync
+        let _ = app_handle.emit("config_changed", config);
         Ok(())
     }
 
@@ -141,7 +458,8 @@ impl ConfigManager {
 
         if config_path.exists() {
             let content = fs::read_to_string(config_path).map_err(|e| CommandError::IoError(e.to_string()))?;
-            let loaded_config: AppConfig = serde_json::from_str(&content).map_err(|e| CommandError::Other(e.to_string()))?;
+            let mut loaded_config: AppConfig = serde_json::from_str(&content).map_err(|e| CommandError::Other(e.to_string()))?;
+            loaded_config.normalize_archive_defaults();
             
             let mut config = self.0.lock().map_err(|_| CommandError::SystemError("Failed to lock config".to_string()))?;
             *config = loaded_config;
@@ -8,6 +8,12 @@ pub enum CommandError {
     SystemError(String),
     ArchiveError(String),
     TrashError(String),
+    /// Destination volume is too small for a copy/move; the message carries the shortfall
+    InsufficientSpace(String),
+    /// Items can't go to the Recycle Bin and would be deleted permanently
+    RecycleBinUnavailable(String),
+    /// Unknown config key or value outside the schema
+    InvalidConfig(String),
     Other(String),
 }
 
@@ -21,6 +27,9 @@ impl fmt::Display for CommandError {
             CommandError::SystemError(msg) => write!(f, "System Error: {}", msg),
             CommandError::ArchiveError(msg) => write!(f, "Archive Error: {}", msg),
             CommandError::TrashError(msg) => write!(f, "Trash Error: {}", msg),
+            CommandError::InsufficientSpace(msg) => write!(f, "Insufficient Space: {}", msg),
+            CommandError::RecycleBinUnavailable(msg) => write!(f, "Recycle Bin Unavailable: {}", msg),
+            CommandError::InvalidConfig(msg) => write!(f, "Invalid Config: {}", msg),
             CommandError::Other(msg) => write!(f, "Error: {}", msg),
         }
     }
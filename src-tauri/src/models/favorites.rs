@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use crate::models::CommandError;
+
+/// Starred files and folders, independent of the sidebar favorites and of Quick Access.
+/// Persisted in `favorite_files.json`.
+#[derive(Default)]
+pub struct FavoriteFiles(pub Mutex<BTreeSet<String>>);
+
+impl FavoriteFiles {
+    /// Stars `path`, or unstars it if it already was. Returns the new state.
+    pub fn toggle(&self, app: &AppHandle, path: String) -> Result<bool, CommandError> {
+        let starred = {
+            let mut favorites = self.lock()?;
+            if favorites.remove(&path) {
+                false
+            } else {
+                favorites.insert(path);
+                true
+            }
+        };
+        self.save(app)?;
+        Ok(starred)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.0.lock().map(|f| f.contains(path)).unwrap_or(false)
+    }
+
+    /// Current favorites; those that were deleted, renamed or moved since are dropped from the store.
+    pub fn live(&self, app: &AppHandle) -> Result<Vec<String>, CommandError> {
+        let (live, pruned) = {
+            let mut favorites = self.lock()?;
+            let before = favorites.len();
+            favorites.retain(|p| std::path::Path::new(p).exists());
+            (favorites.iter().cloned().collect::<Vec<_>>(), favorites.len() != before)
+        };
+        if pruned {
+            self.save(app)?;
+        }
+        Ok(live)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, BTreeSet<String>>, CommandError> {
+        self.0.lock().map_err(|_| CommandError::SystemError("Failed to lock favorite files".to_string()))
+    }
+
+    pub fn save(&self, app_handle: &AppHandle) -> Result<(), CommandError> {
+        let favorites = self.lock()?;
+        let config_dir = app_handle.path().app_config_dir().map_err(|e| CommandError::IoError(e.to_string()))?;
+
+        if !config_dir.exists() {
+            fs::create_dir_all(&config_dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+
+        let json = serde_json::to_string(&*favorites).map_err(|e| CommandError::Other(e.to_string()))?;
+        fs::write(config_dir.join("favorite_files.json"), json).map_err(|e| CommandError::IoError(e.to_string()))?;
+        Ok(())
+    }
+
+    pub fn load(&self, app_handle: &AppHandle) -> Result<(), CommandError> {
+        let config_dir = app_handle.path().app_config_dir().map_err(|e| CommandError::IoError(e.to_string()))?;
+        let favorites_path = config_dir.join("favorite_files.json");
+
+        if favorites_path.exists() {
+            let content = fs::read_to_string(favorites_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+            match serde_json::from_str::<BTreeSet<String>>(&content) {
+                Ok(loaded) => *self.lock()? = loaded,
+                Err(e) => log::error!("Failed to parse favorite_files.json: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
@@ -3,6 +3,15 @@ use std::path::Path;
 use std::time::SystemTime;
 use crate::models::CommandError;
 
+/// Header of a group in a grouped listing: a translation key for the fixed buckets (dates,
+/// sizes, folders), or the extension itself when grouping by type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupKey {
+    Label(String),
+    Extension(String),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct FileEntry {
     pub name: String,
@@ -19,6 +28,18 @@ pub struct FileEntry {
     pub is_calculated: bool,
     pub original_path: Option<String>,
     pub deleted_time: Option<i64>,
+    /// Group header this entry falls under when `list_dir` is called with `group_by`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_key: Option<GroupKey>,
+    /// Starred with `toggle_favorite`; only filled in by `list_dir`
+    #[serde(default)]
+    pub is_favorite: bool,
+    /// Start of the first line matching a content search, trimmed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_context: Option<String>,
+    /// 1-based number of that line
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_line: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +53,20 @@ pub struct ShortcutInfo {
     pub run_window: i32,
 }
 
+/// Optional settings for a shortcut made by `create_shortcut`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutOptions {
+    pub arguments: String,
+    /// Defaults to the target's folder
+    pub working_dir: Option<String>,
+    pub icon_location: String,
+    pub icon_index: i32,
+    pub description: String,
+    /// SW_* show command, normal window when unset
+    pub run_window: Option<i32>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileProperties {
     pub name: String,
@@ -39,6 +74,8 @@ pub struct FileProperties {
     pub parent: String,
     pub is_dir: bool,
     pub size: u64,
+    /// Cluster-rounded, compressed size; None for folders (see `calculate_folder_size`) and shell items
+    pub size_on_disk: Option<u64>,
     pub is_calculated: bool,
     pub created: u64,
     pub modified: u64,
@@ -77,9 +114,51 @@ pub struct FileSummary {
     pub parent_path: Option<String>,
 }
 
+/// One bucket of `summarize_selection_by_type`: an extension, `<no extension>` or `<folders>`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypeSummary {
+    pub count: usize,
+    pub total_size: u64,
+}
+
+/// Attribute flags to change; `None` leaves the current value untouched.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FileAttributeChanges {
+    pub read_only: Option<bool>,
+    pub hidden: Option<bool>,
+    pub system: Option<bool>,
+    pub archive: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FileAttributes {
+    pub read_only: bool,
+    pub hidden: bool,
+    pub system: bool,
+    pub archive: bool,
+}
+
+/// Timestamps to change, in epoch milliseconds; `None` leaves the current value untouched.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FileTimestampChanges {
+    pub created: Option<u64>,
+    pub modified: Option<u64>,
+    pub accessed: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FileTimestamps {
+    pub created: u64,
+    pub modified: u64,
+    pub accessed: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FolderSizeResult {
     pub size: u64,
+    /// Cluster-rounded, compressed size of the files, as Explorer's "Size on disk"
+    #[serde(default)]
+    pub size_on_disk: u64,
     pub folders_count: u64,
     pub files_count: u64,
 }
@@ -107,6 +186,13 @@ pub struct DriveInfo {
     pub remote_path: Option<String>,
 }
 
+/// One address bar segment, from the drive root down to the current folder.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreadcrumbSegment {
+    pub display_name: String,
+    pub full_path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuickAccessItem {
     pub name: String,
@@ -122,6 +208,68 @@ pub struct WinMenuItem {
     pub children: Vec<WinMenuItem>,
 }
 
+/// Lossless-in-geometry edits applied by `transform_image`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageTransform {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipH,
+    FlipV,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Formats `batch_convert_images` can write.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageOutputFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Bmp,
+    Tiff,
+}
+
+/// Options of `batch_convert_images`. Images are only ever scaled down, keeping their aspect
+/// ratio, to fit `max_width` x `max_height`; `quality` (1-100) applies to JPEG.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageConvertOptions {
+    pub target_format: ImageOutputFormat,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    pub quality: Option<u8>,
+}
+
+/// How `set_as_wallpaper` lays the image out on the desktop.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum WallpaperStyle {
+    Fill,
+    Fit,
+    Stretch,
+    Tile,
+    Center,
+}
+
+/// What to do when a file being written already exists at the destination.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    #[default]
+    Overwrite,
+    Skip,
+    /// Write next to it as `name (2).ext`
+    Rename,
+    /// Overwrite only when the incoming file is newer
+    KeepNewer,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConflictEntry {
     pub name: String,
@@ -138,6 +286,15 @@ pub struct ConflictResponse {
     pub likely_large: bool,
 }
 
+/// Exact totals from `estimate_operation_size`, replacing `ConflictResponse`'s capped ones.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizeEstimate {
+    pub op_id: String,
+    pub total_size: u64,
+    pub total_files: usize,
+    pub cancelled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrashEntry {
     pub name: String,
@@ -149,6 +306,34 @@ pub struct TrashEntry {
     pub modified: u64,
 }
 
+/// A shadow copy holding an earlier state of a file or folder, from `list_shadow_versions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShadowVersion {
+    /// When the snapshot was taken (ms since the epoch); identifies it for `restore_shadow_version`
+    pub timestamp: i64,
+    pub snapshot_path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: u64,
+}
+
+/// A process holding a file open, from `get_locking_processes`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockingProcess {
+    pub pid: u32,
+    /// Process creation time (FILETIME ticks), to pass back to `close_locking_process`
+    pub start_time: u64,
+    pub name: String,
+}
+
+/// Result of `send_to_mail`. `warning` is set when oversized attachments were sent anyway.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailSendResult {
+    pub attached: usize,
+    pub total_bytes: u64,
+    pub warning: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct SnapRect {
     pub x: f64,
@@ -167,6 +352,8 @@ pub struct NetResource {
     pub provider: Option<String>,
     pub is_media_device: Option<bool>,
     pub has_web_page: Option<bool>,
+    /// Set on shares that refused access with the current credentials
+    pub requires_auth: Option<bool>,
 }
 
 pub fn get_file_entry_from_path(path: &Path) -> Result<FileEntry, CommandError> {
@@ -220,5 +407,9 @@ pub fn get_file_entry_from_path(path: &Path) -> Result<FileEntry, CommandError>
         is_calculated: false,
         original_path: None,
         deleted_time: None,
+        group_key: None,
+        is_favorite: false,
+        match_context: None,
+        match_line: None,
     })
 }
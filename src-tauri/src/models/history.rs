@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
 use crate::models::transaction::Transaction;
@@ -8,11 +9,25 @@ pub struct HistoryState {
     pub redo_stack: Vec<Transaction>,
 }
 
-pub struct HistoryManager(pub Mutex<HistoryState>);
+/// `get_history` payload: the stacks plus what the undo/redo buttons need.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistorySnapshot {
+    #[serde(flatten)]
+    pub state: HistoryState,
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub undo_depth: usize,
+    pub redo_depth: usize,
+}
+
+pub const DEFAULT_MAX_UNDO_DEPTH: usize = 100;
+
+/// Undo/redo stacks. The undo stack keeps at most `max_depth` transactions (`AppConfig::max_undo_depth`).
+pub struct HistoryManager(pub Mutex<HistoryState>, AtomicUsize);
 
 impl Default for HistoryManager {
     fn default() -> Self {
-        Self(Mutex::new(HistoryState::default()))
+        Self(Mutex::new(HistoryState::default()), AtomicUsize::new(DEFAULT_MAX_UNDO_DEPTH))
     }
 }
 
@@ -21,15 +36,29 @@ impl HistoryManager {
         Self::default()
     }
 
+    /// Sets the undo stack cap, dropping the oldest transactions if it is already over it.
+    pub fn set_max_depth(&self, max_depth: usize) {
+        self.1.store(max_depth.max(1), Ordering::Relaxed);
+        if let Ok(mut state) = self.0.lock() {
+            self.trim(&mut state);
+        }
+    }
+
+    fn trim(&self, state: &mut HistoryState) {
+        let max_depth = self.1.load(Ordering::Relaxed);
+        if state.undo_stack.len() > max_depth {
+            let excess = state.undo_stack.len() - max_depth;
+            state.undo_stack.drain(..excess);
+        }
+    }
+
     pub fn push(&self, transaction: Transaction) {
         if let Ok(mut state) = self.0.lock() {
             state.undo_stack.push(transaction);
             state.redo_stack.clear(); // New action clears redo history
             
             // Cap history to prevent memory leaks
-            if state.undo_stack.len() > 100 {
-                state.undo_stack.remove(0);
-            }
+            self.trim(&mut state);
         }
     }
 
@@ -59,12 +88,24 @@ impl HistoryManager {
     pub fn push_undo_raw(&self, transaction: Transaction) {
         if let Ok(mut state) = self.0.lock() {
             state.undo_stack.push(transaction);
+            self.trim(&mut state);
         }
     }
 
     pub fn get_state(&self) -> HistoryState {
         self.0.lock().map(|s| (*s).clone()).unwrap_or_default()
     }
+
+    pub fn snapshot(&self) -> HistorySnapshot {
+        let state = self.get_state();
+        HistorySnapshot {
+            can_undo: !state.undo_stack.is_empty(),
+            can_redo: !state.redo_stack.is_empty(),
+            undo_depth: state.undo_stack.len(),
+            redo_depth: state.redo_stack.len(),
+            state,
+        }
+    }
     
     pub fn clear(&self) {
         if let Ok(mut state) = self.0.lock() {
@@ -4,15 +4,19 @@ pub mod session;
 pub mod progress;
 pub mod transaction;
 pub mod history;
+pub mod activity;
+pub mod favorites;
 
 pub use error::CommandError;
 pub type Result<T> = std::result::Result<T, CommandError>;
 
-pub use file_entry::{FileEntry, FileProperties, ShortcutInfo, FileSummary, FolderSizeResult, DriveInfo, WinMenuItem, QuickAccessItem, ConflictEntry, ConflictResponse, TrashEntry, SidebarNode, SnapRect, NetResource, get_file_entry_from_path};
+pub use file_entry::{FileEntry, GroupKey, FileProperties, FileAttributes, FileAttributeChanges, FileTimestamps, FileTimestampChanges, ShortcutInfo, ShortcutOptions, FileSummary, TypeSummary, FolderSizeResult, DriveInfo, WinMenuItem, QuickAccessItem, BreadcrumbSegment, ConflictEntry, ConflictPolicy, ConflictResponse, SizeEstimate, TrashEntry, SidebarNode, SnapRect, MailSendResult, LockingProcess, ShadowVersion, WallpaperStyle, ImageTransform, ImageSize, ImageOutputFormat, ImageConvertOptions, NetResource, get_file_entry_from_path};
 pub use session::{SessionState, SessionManager, Tab};
-pub use config::{AppConfig, ConfigManager};
+pub use config::{AppConfig, Bookmark, ConfigManager, ConfigImportReport, ConfigKeySchema, Favorite, SavedSearch, SearchPreset, Workspace, WorkspacePanel};
 pub use progress::ProgressEvent;
-pub use transaction::{Transaction, TransactionType, TransactionDetails};
+pub use transaction::{Transaction, TransactionType, TransactionDetails, TransactionSummary};
 pub use history::HistoryManager;
+pub use activity::{ActivityEntry, ActivityKind, ActivityLog, ActivityOutcome};
+pub use favorites::FavoriteFiles;
 
 pub mod config;
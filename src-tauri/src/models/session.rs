@@ -35,10 +35,23 @@ pub enum SortDirection {
     Desc,
 }
 
+/// Column used to bucket a listing into groups (see `list_dir`'s `group_by`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupField {
+    Extension,
+    Date,
+    Size,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SortConfig {
+    /// Primary key, kept for callers that only know about a single sort column
     pub field: SortField,
     pub direction: SortDirection,
+    /// Ordered sort keys (primary first). When empty, `field`/`direction` is the only key.
+    #[serde(default)]
+    pub keys: Vec<(SortField, SortDirection)>,
 }
 
 impl Default for SortConfig {
@@ -46,7 +59,28 @@ impl Default for SortConfig {
         Self {
             field: SortField::Name,
             direction: SortDirection::Asc,
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl SortConfig {
+    /// The keys to sort by, in order of precedence.
+    pub fn sort_keys(&self) -> Vec<(SortField, SortDirection)> {
+        if self.keys.is_empty() {
+            vec![(self.field.clone(), self.direction.clone())]
+        } else {
+            self.keys.clone()
+        }
+    }
+
+    /// Keeps the single-field shim in sync with the first of `keys`.
+    pub fn normalized(mut self) -> Self {
+        if let Some((field, direction)) = self.keys.first() {
+            self.field = field.clone();
+            self.direction = direction.clone();
         }
+        self
     }
 }
 
@@ -56,6 +90,12 @@ pub struct Tab {
     pub path: PathBuf,
     #[serde(default)]
     pub version: u64,
+    /// Accent color chosen by the user (any CSS color), shown on the tab
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Pinned tabs stay at the front and need `force` to be closed
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +133,9 @@ pub struct PanelState {
     pub sort_config: SortConfig,
     #[serde(default)]
     pub cached_results: Option<CachedResults>,
+    /// Quick name filter over the current listing, cleared when the panel changes folder
+    #[serde(default)]
+    pub filter: Option<String>,
 }
 
 impl PanelState {
@@ -148,14 +191,38 @@ impl PanelState {
     }
 }
 
+/// Recently closed tabs kept for `reopen_closed_tab`; older ones are dropped.
+pub const MAX_CLOSED_TABS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedTab {
+    pub panel_id: String,
+    pub tab: Tab,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionState {
     pub left_panel: PanelState,
     pub right_panel: PanelState,
     pub active_panel: String, // "left" or "right"
+    /// Most recently closed last
+    #[serde(default)]
+    pub closed_tabs: Vec<ClosedTab>,
+    /// Mirror navigation in the active tab of one panel onto the other (see `active_tab_navigate`)
+    #[serde(default)]
+    pub sync_navigation: bool,
 }
 
 impl SessionState {
+    /// Records a closed tab so it can be reopened, dropping the oldest past `MAX_CLOSED_TABS`.
+    pub fn remember_closed_tab(&mut self, panel_id: &str, tab: Tab) {
+        self.closed_tabs.push(ClosedTab { panel_id: panel_id.to_string(), tab });
+        if self.closed_tabs.len() > MAX_CLOSED_TABS {
+            let excess = self.closed_tabs.len() - MAX_CLOSED_TABS;
+            self.closed_tabs.drain(..excess);
+        }
+    }
+
     /// Get a mutable reference to the panel identified by `id` ("left" or "right").
     pub fn get_panel_mut(&mut self, id: &str) -> &mut PanelState {
         if id == "left" { &mut self.left_panel } else { &mut self.right_panel }
@@ -170,6 +237,8 @@ impl Default for SessionState {
                     id: "default-left".to_string(),
                     path: PathBuf::from("C:\\"),
                     version: 0,
+                    color: None,
+                    pinned: false,
                 }],
                 active_tab_id: "default-left".to_string(),
                 watcher: None,
@@ -177,12 +246,15 @@ impl Default for SessionState {
                 search_context: None,
                 sort_config: SortConfig::default(),
                 cached_results: None,
+                filter: None,
             },
             right_panel: PanelState {
                 tabs: vec![Tab {
                     id: "default-right".to_string(),
                     path: PathBuf::from("C:\\"),
                     version: 0,
+                    color: None,
+                    pinned: false,
                 }],
                 active_tab_id: "default-right".to_string(),
                 watcher: None,
@@ -190,8 +262,11 @@ impl Default for SessionState {
                 search_context: None,
                 sort_config: SortConfig::default(),
                 cached_results: None,
+                filter: None,
             },
             active_panel: "left".to_string(),
+            closed_tabs: Vec::new(),
+            sync_navigation: false,
         }
     }
 }
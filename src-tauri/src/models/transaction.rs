@@ -9,6 +9,10 @@ pub enum TransactionType {
     Delete,
     NewFolder,
     Restore,
+    /// A single new file, e.g. a shortcut
+    NewFile,
+    /// File content changed in place; `old_path` holds a backup of the previous content
+    Edit,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,22 +24,86 @@ pub struct TransactionDetails {
     pub created_files: Option<Vec<String>>,
 }
 
+/// What a transaction did, for the history list. The UI translates `key` (e.g.
+/// `history_moved`: "Moved {items} to {target}") and fills it in with the other fields.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionSummary {
+    pub key: String,
+    /// Name of the item involved, when there is only one
+    pub name: Option<String>,
+    pub count: usize,
+    /// Destination folder, group folder or new name, depending on the operation
+    pub target: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: String,
     pub timestamp: i64,
     pub op_type: TransactionType,
     pub details: TransactionDetails,
+    /// Description for the history list, e.g. "Moved 3 items to Downloads"
+    #[serde(default)]
+    pub summary: TransactionSummary,
     // Future: backup references for safe undo?
 }
 
 impl Transaction {
     pub fn new(op_type: TransactionType, details: TransactionDetails) -> Self {
+        let summary = Self::describe(&op_type, &details);
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             op_type,
             details,
+            summary,
         }
     }
+
+    pub fn describe(op_type: &TransactionType, details: &TransactionDetails) -> TransactionSummary {
+        let target_dir = details.target_dir.as_deref().map(display_name);
+        // Duplicates and "group into folder" record what they created
+        let created = details.created_files.as_ref().filter(|c| !c.is_empty());
+
+        let (key, target) = match op_type {
+            TransactionType::Copy if created.is_some() => ("history_duplicated", None),
+            TransactionType::Copy => ("history_copied", target_dir),
+            TransactionType::Move => match created.and_then(|c| c.first()) {
+                Some(folder) => ("history_grouped", Some(display_name(folder))),
+                None => ("history_moved", target_dir),
+            },
+            TransactionType::Rename => ("history_renamed", details.new_path.as_deref().map(display_name)),
+            TransactionType::Delete => ("history_deleted", None),
+            TransactionType::NewFolder => ("history_new_folder", None),
+            TransactionType::NewFile => ("history_new_file", None),
+            TransactionType::Edit => ("history_edited", None),
+            TransactionType::Restore if target_dir.is_none() => ("history_restored", None),
+            TransactionType::Restore => ("history_restored_to", target_dir),
+        };
+
+        // A rename describes the item by its old name
+        let name = match op_type {
+            TransactionType::Rename => details.old_path.as_deref().map(display_name),
+            _ => match details.paths.as_slice() {
+                [single] => Some(display_name(single)),
+                _ => None,
+            },
+        };
+
+        TransactionSummary {
+            key: key.to_string(),
+            name,
+            count: details.paths.len(),
+            target,
+        }
+    }
+}
+
+/// Last path component, or the path itself for drive roots.
+fn display_name(path: &str) -> String {
+    let trimmed = path.trim_end_matches(['\\', '/']);
+    match trimmed.rsplit(['\\', '/']).next() {
+        Some(name) if !name.is_empty() && !name.ends_with(':') => name.to_string(),
+        _ => path.to_string(),
+    }
 }
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use crate::models::SessionManager;
+use crate::utils::hardware::{get_disk_space, volume_root};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DriveSpaceEvent {
+    pub drive: String,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Emits `drive_space_changed` once for each distinct volume among `paths`.
+pub fn emit_for_paths(app: &AppHandle, paths: &[PathBuf]) {
+    let mut seen = Vec::new();
+    for path in paths {
+        if let Some(root) = volume_root(path) {
+            if seen.contains(&root) { continue; }
+            if let Some((free_bytes, total_bytes)) = get_disk_space(path) {
+                let _ = app.emit("drive_space_changed", DriveSpaceEvent { drive: root.clone(), free_bytes, total_bytes });
+            }
+            seen.push(root);
+        }
+    }
+}
+
+/// Background poller for the drives shown in the active tabs. Only emits when free space
+/// moved by at least `threshold` bytes since the last value sent for that drive.
+#[derive(Default)]
+pub struct DriveSpaceMonitor {
+    stop_flag: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl DriveSpaceMonitor {
+    pub fn start(&self, app: AppHandle, interval: Duration, threshold: u64) {
+        self.stop();
+        let stop = Arc::new(AtomicBool::new(false));
+        *self.stop_flag.lock().unwrap() = Some(stop.clone());
+
+        std::thread::spawn(move || {
+            let mut last_free: HashMap<String, u64> = HashMap::new();
+            while !stop.load(Ordering::Relaxed) {
+                for path in Self::active_tab_paths(&app) {
+                    let (root, (free_bytes, total_bytes)) = match (volume_root(&path), get_disk_space(&path)) {
+                        (Some(root), Some(space)) => (root, space),
+                        _ => continue,
+                    };
+
+                    let changed = match last_free.get(&root) {
+                        Some(&prev) => prev.abs_diff(free_bytes) >= threshold,
+                        None => true,
+                    };
+                    if changed {
+                        last_free.insert(root.clone(), free_bytes);
+                        let _ = app.emit("drive_space_changed", DriveSpaceEvent { drive: root, free_bytes, total_bytes });
+                    }
+                }
+                std::thread::sleep(interval);
+            }
+        });
+    }
+
+    pub fn stop(&self) {
+        if let Some(flag) = self.stop_flag.lock().unwrap().take() {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+
+    fn active_tab_paths(app: &AppHandle) -> Vec<PathBuf> {
+        let state = match app.try_state::<SessionManager>() {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+        let session = match state.0.lock() {
+            Ok(s) => s,
+            Err(_) => return Vec::new(),
+        };
+        [&session.left_panel, &session.right_panel]
+            .iter()
+            .filter_map(|panel| panel.tabs.iter().find(|t| t.id == panel.active_tab_id))
+            .map(|t| t.path.clone())
+            .collect()
+    }
+}
@@ -4,9 +4,10 @@ use std::path::PathBuf;
 use serde::{Serialize, Deserialize};
 use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
-use crate::models::{HistoryManager, Transaction, TransactionType, TransactionDetails};
+use crate::models::{ActivityEntry, ActivityKind, ActivityLog, ActivityOutcome, ConfigManager, HistoryManager, Transaction, TransactionType, TransactionDetails};
+use crate::utils::path_security::{extended_path, long_path, strip_extended_length};
 use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use log::info;
 
 #[cfg(target_os = "windows")]
@@ -16,6 +17,137 @@ use windows::Win32::System::Threading::{
 };
 
 
+/// Alignment required by unbuffered reads; 4096 covers both 512e and 4Kn disks.
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+/// Below this size bypassing the cache costs more than it saves.
+const DIRECT_IO_MIN_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Heap buffer whose start and length are multiples of `DIRECT_IO_ALIGNMENT`.
+struct AlignedBuffer {
+    storage: Vec<u8>,
+    offset: usize,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    fn new(len: usize) -> Self {
+        let len = len.div_ceil(DIRECT_IO_ALIGNMENT).max(1) * DIRECT_IO_ALIGNMENT;
+        let storage = vec![0u8; len + DIRECT_IO_ALIGNMENT];
+        let offset = storage.as_ptr().align_offset(DIRECT_IO_ALIGNMENT);
+        Self { storage, offset, len }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.storage[self.offset..self.offset + self.len]
+    }
+}
+
+/// Opens `path` for reading, bypassing the system cache when `unbuffered` is set (Windows only).
+/// Falls back to a regular open if the unbuffered open fails.
+fn open_copy_source(path: &std::path::Path, unbuffered: bool) -> std::io::Result<std::fs::File> {
+    #[cfg(target_os = "windows")]
+    if unbuffered {
+        use std::os::windows::fs::OpenOptionsExt;
+        use windows::Win32::Storage::FileSystem::{FILE_FLAG_NO_BUFFERING, FILE_FLAG_SEQUENTIAL_SCAN};
+        if let Ok(f) = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(FILE_FLAG_NO_BUFFERING.0 | FILE_FLAG_SEQUENTIAL_SCAN.0)
+            .open(path)
+        {
+            return Ok(f);
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = unbuffered;
+    std::fs::File::open(path)
+}
+
+/// Retries for per-file I/O failing because something else (antivirus, indexer) briefly holds the file.
+#[derive(Clone, Copy)]
+struct RetryPolicy {
+    attempts: u32,
+    delay: std::time::Duration,
+}
+
+impl RetryPolicy {
+    fn from_config(app: &AppHandle) -> Self {
+        app.try_state::<ConfigManager>()
+            .and_then(|c| c.0.lock().ok().map(|cfg| Self {
+                attempts: cfg.io_retry_attempts.min(10),
+                delay: std::time::Duration::from_millis(cfg.io_retry_delay_ms.min(10_000)),
+            }))
+            .unwrap_or(Self { attempts: 3, delay: std::time::Duration::from_millis(100) })
+    }
+
+    /// Runs `f` until it succeeds, fails for another reason, runs out of attempts or the
+    /// operation is cancelled, waiting twice as long after each failure (up to `MAX_RETRY_DELAY`).
+    fn run<T>(&self, cancel: &AtomicBool, mut f: impl FnMut() -> std::io::Result<T>) -> std::io::Result<T> {
+        let mut delay = self.delay.min(MAX_RETRY_DELAY);
+        let mut attempt = 0;
+        loop {
+            match f() {
+                Err(e) if attempt < self.attempts && is_transient_io_error(&e) && !cancel.load(Ordering::Relaxed) => {
+                    info!("Retrying after transient error: {}", e);
+                    sleep_unless_cancelled(delay, cancel);
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Longest wait between two attempts of `RetryPolicy::run`, whatever the configured delay.
+const MAX_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Sleeps for `duration` in short slices, returning early once `cancel` is set.
+fn sleep_unless_cancelled(duration: std::time::Duration, cancel: &AtomicBool) {
+    let slice = std::time::Duration::from_millis(50);
+    let deadline = std::time::Instant::now() + duration;
+    while !cancel.load(Ordering::Relaxed) {
+        let now = std::time::Instant::now();
+        if now >= deadline {
+            break;
+        }
+        std::thread::sleep(slice.min(deadline - now));
+    }
+}
+
+fn is_transient_io_error(e: &std::io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION, plus access denied: antivirus scanners and
+    // indexers briefly hold new files that way. A lasting denial only costs the policy's attempts
+    e.kind() == std::io::ErrorKind::PermissionDenied
+        || (cfg!(target_os = "windows") && matches!(e.raw_os_error(), Some(32) | Some(33)))
+}
+
+/// Bytes compared at the end of a partial destination before resuming into it.
+const RESUME_VERIFY_BYTES: u64 = 64 * 1024;
+
+fn read_range(path: &std::path::Path, start: u64, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = vec![0u8; len];
+    file.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Offset at which an interrupted copy of `src` into `dest` can continue, or None if it has to
+/// start over: `dest` must be a non-empty prefix of `src`, checked on its last bytes.
+fn resume_offset(src: &std::path::Path, dest: &std::path::Path) -> Option<u64> {
+    let src_len = std::fs::metadata(src).ok()?.len();
+    let dest_len = std::fs::metadata(dest).ok()?.len();
+    if dest_len == 0 || dest_len >= src_len {
+        return None;
+    }
+
+    let tail = RESUME_VERIFY_BYTES.min(dest_len);
+    let start = dest_len - tail;
+    let src_tail = read_range(src, start, tail as usize).ok()?;
+    let dest_tail = read_range(dest, start, tail as usize).ok()?;
+    if src_tail == dest_tail { Some(dest_len) } else { None }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum OpStatus {
     Queued,
@@ -51,6 +183,17 @@ pub struct FileOperation {
     pub bytes_per_second: u64,
     pub turbo: bool,
     pub is_cross_volume: bool,
+    /// Continue partially copied destination files instead of overwriting them
+    pub resume: bool,
+    /// Explicit destination for each source (same order), e.g. "name - Copy.ext" for duplicates.
+    /// When unset, sources keep their name inside `destination`.
+    pub dest_paths: Option<Vec<PathBuf>>,
+    /// Folder created to receive the items (group into new folder). Removed again if the
+    /// operation doesn't complete, and by undo once emptied.
+    pub created_dir: Option<PathBuf>,
+    /// Password of the encrypted archives entries are deleted from; never sent to the UI
+    #[serde(skip)]
+    pub archive_password: Option<String>,
     // Private/Internal state, not serialized by default unless needed
     #[serde(skip)]
     pub cancel_flag: Arc<AtomicBool>,
@@ -76,6 +219,10 @@ impl FileOperation {
             bytes_per_second: 0,
             turbo: false,
             is_cross_volume: false,
+            resume: false,
+            dest_paths: None,
+            created_dir: None,
+            archive_password: None,
             cancel_flag: Arc::new(AtomicBool::new(false)),
             pause_flag: Arc::new(AtomicBool::new(false)),
             turbo_flag: Arc::new(AtomicBool::new(false)),
@@ -213,7 +360,10 @@ impl FileOperationManager {
                         target_dir: target_str,
                         old_path: None,
                         new_path: None,
-                        created_files: None, // Could populate this if we tracked exact output paths
+                        // Copies under a new name: undo must remove exactly these, not same-named originals
+                        created_files: locked.dest_paths.as_ref()
+                            .map(|d| d.iter().map(|p| p.to_string_lossy().to_string()).collect())
+                            .or_else(|| locked.created_dir.as_ref().map(|d| vec![d.to_string_lossy().to_string()])),
                     };
 
                     let tx = Transaction::new(t_type, details);
@@ -222,11 +372,52 @@ impl FileOperationManager {
                 }
             }
         }
+        // Drop a folder made for this operation if it stopped before anything landed in it
+        if locked.status != OpStatus::Completed {
+            if let Some(dir) = &locked.created_dir {
+                let _ = std::fs::remove_dir(dir);
+            }
+        }
         let _ = app.emit("file_op_event", locked.clone());
+
+        let outcome = match &locked.status {
+            OpStatus::Completed => ActivityOutcome::Completed,
+            OpStatus::Cancelled => ActivityOutcome::Cancelled,
+            OpStatus::Error(e) => ActivityOutcome::Failed(e.clone()),
+            _ => ActivityOutcome::Failed("Operation did not finish".to_string()),
+        };
+        let kind = match locked.op_type {
+            FileOpType::Copy => ActivityKind::Copy,
+            FileOpType::Move => ActivityKind::Move,
+            FileOpType::Delete => ActivityKind::Delete,
+            FileOpType::Trash => ActivityKind::Trash,
+        };
+        app.state::<ActivityLog>().record(&app, ActivityEntry::new(
+            kind,
+            locked.sources.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+            locked.destination.as_ref().map(|p| p.to_string_lossy().to_string()),
+            locked.processed_bytes,
+            outcome,
+        ));
+
+        // Free space changed on the volumes touched by the operation (even if it stopped midway)
+        let mut touched = locked.sources.clone();
+        touched.extend(locked.destination.clone());
+        drop(locked);
+        crate::systems::drive_space::emit_for_paths(&app, &touched);
     }
 
     fn perform_copy(app: &AppHandle, op: &Arc<Mutex<FileOperation>>, sources: Vec<PathBuf>, destination: Option<PathBuf>, is_move: bool) -> Result<(), String> {
         let target_dir = destination.ok_or("No destination provided for copy/move".to_string())?;
+        let dest_paths = op.lock().unwrap().dest_paths.clone();
+        let dest_of = |src: &PathBuf| -> Result<PathBuf, String> {
+            let explicit = dest_paths.as_ref()
+                .and_then(|d| sources.iter().position(|s| s == src).and_then(|i| d.get(i).cloned()));
+            match explicit {
+                Some(dest) => Ok(dest),
+                None => Ok(target_dir.join(src.file_name().ok_or("Invalid source name")?)),
+            }
+        };
         
         let mut sources_to_copy = Vec::new();
         let mut total_bytes = 0;
@@ -236,8 +427,7 @@ impl FileOperationManager {
         if is_move {
             for src in &sources {
                 if !src.exists() { continue; }
-                let file_name = src.file_name().ok_or("Invalid source name")?;
-                let dest = target_dir.join(file_name);
+                let dest = dest_of(src)?;
 
                 // Try atomic rename
                 match std::fs::rename(src, &dest) {
@@ -265,15 +455,15 @@ impl FileOperationManager {
 
         for src in &sources_to_copy {
             if !src.exists() { continue; }
-            let file_name = src.file_name().ok_or("Invalid source name")?;
-            let dest_root = target_dir.join(file_name);
+            let dest_root = dest_of(src)?;
             
             if src.is_dir() {
-                for entry in walkdir::WalkDir::new(src) {
+                let root = extended_path(src);
+                for entry in walkdir::WalkDir::new(&root) {
                     let entry = entry.map_err(|e| e.to_string())?;
                     if entry.path().is_dir() { continue; } 
                     
-                    let relative = entry.path().strip_prefix(src).map_err(|e| e.to_string())?;
+                    let relative = entry.path().strip_prefix(&root).map_err(|e| e.to_string())?;
                     let dest_path = dest_root.join(relative);
                     let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
                     
@@ -306,11 +496,18 @@ impl FileOperationManager {
         let mut speed_samples: std::collections::VecDeque<u64> = std::collections::VecDeque::with_capacity(4);
         
         // Cache flags to avoid locking in tight loops
-        let (cancel_flag, pause_flag, turbo_flag) = {
+        let (cancel_flag, pause_flag, turbo_flag, resume) = {
             let locked = op.lock().unwrap();
-            (locked.cancel_flag.clone(), locked.pause_flag.clone(), locked.turbo_flag.clone())
+            (locked.cancel_flag.clone(), locked.pause_flag.clone(), locked.turbo_flag.clone(), locked.resume)
         };
         
+        // Buffer sizes and the direct-I/O switch come from the user config
+        let (turbo_buffer_size, normal_buffer_size, unbuffered_io) = app.try_state::<ConfigManager>()
+            .and_then(|c| c.0.lock().ok().map(|cfg| (cfg.copy_buffer_bytes(true), cfg.copy_buffer_bytes(false), cfg.copy_unbuffered_io)))
+            .unwrap_or((1024 * 1024, 512 * 1024, false));
+
+        let retry = RetryPolicy::from_config(app);
+
         let last_turbo_state = turbo_flag.load(Ordering::Relaxed);
         // Ensure starting priority matches initial mode
         #[cfg(target_os = "windows")]
@@ -380,29 +577,53 @@ impl FileOperationManager {
                         if cancel.load(Ordering::Relaxed) { return Ok(()); }
                     }
 
-                    let (src, dest): &(PathBuf, PathBuf) = &files[i];
+                    let (src, dest) = (long_path(&files[i].0), long_path(&files[i].1));
+                    let (src, dest) = (&src, &dest);
                     
                     if let Some(parent) = dest.parent() {
                         let _ = std::fs::create_dir_all(parent);
                     }
 
-                    let mut file_in = match std::fs::File::open(src) {
+                    let resume_from = if resume { resume_offset(src, dest) } else { None };
+
+                    // Unbuffered reads only pay off for large files staying on the same volume,
+                    // and need a sector-aligned starting offset
+                    let use_direct_io = unbuffered_io
+                        && resume_from.unwrap_or(0) % DIRECT_IO_ALIGNMENT as u64 == 0
+                        && std::fs::metadata(src).map(|m| m.len() >= DIRECT_IO_MIN_FILE_SIZE).unwrap_or(false)
+                        && matches!(
+                            (crate::utils::hardware::volume_root(src), crate::utils::hardware::volume_root(dest)),
+                            (Some(a), Some(b)) if a.eq_ignore_ascii_case(&b)
+                        );
+
+                    let mut file_in = match retry.run(&cancel, || open_copy_source(src, use_direct_io)) {
                         Ok(f) => f,
                         Err(_) => {
                             processed_files.fetch_add(1, Ordering::Relaxed);
                             continue;
                         }
                     };
-                    let mut file_out = match std::fs::File::create(dest) {
+                    let opened_out = match resume_from {
+                        Some(offset) => file_in.seek(SeekFrom::Start(offset))
+                            .and_then(|_| retry.run(&cancel, || std::fs::OpenOptions::new().write(true).open(dest)))
+                            .and_then(|mut f| f.seek(SeekFrom::Start(offset)).map(|_| f)),
+                        None => retry.run(&cancel, || std::fs::File::create(dest)),
+                    };
+                    let mut file_out = match opened_out {
                         Ok(f) => f,
                         Err(_) => {
                             processed_files.fetch_add(1, Ordering::Relaxed);
                             continue;
                         }
                     };
+                    if let Some(offset) = resume_from {
+                        info!("Resuming {} at byte {}", dest.display(), offset);
+                        processed_bytes.fetch_add(offset, Ordering::Relaxed);
+                    }
                     
-                    let buffer_size = if is_turbo { 1024 * 1024 } else { 512 * 1024 };
-                    let mut buffer = vec![0u8; buffer_size];
+                    let buffer_size = if is_turbo { turbo_buffer_size } else { normal_buffer_size };
+                    let mut aligned_buffer = AlignedBuffer::new(buffer_size);
+                    let buffer = aligned_buffer.as_mut_slice();
                     
                     loop {
                         if cancel.load(Ordering::Relaxed) { break; }
@@ -411,7 +632,7 @@ impl FileOperationManager {
                             if cancel.load(Ordering::Relaxed) { return Ok(()); }
                         }
 
-                        let n = match file_in.read(&mut buffer) {
+                        let n = match file_in.read(buffer) {
                             Ok(0) => break,
                             Ok(n) => n,
                             Err(_) => break,
@@ -427,7 +648,7 @@ impl FileOperationManager {
 
                     processed_files.fetch_add(1, Ordering::Relaxed);
                     if is_move {
-                        let _ = std::fs::remove_file(src);
+                        let _ = retry.run(&cancel, || std::fs::remove_file(src));
                     }
                 }
                 
@@ -473,7 +694,7 @@ impl FileOperationManager {
             // Clean up source directories (naive approach: try to remove them, silence errors if not empty)
              for src in &sources {
                  if src.is_dir() {
-                     let _ = std::fs::remove_dir_all(src);
+                     let _ = std::fs::remove_dir_all(long_path(src));
                  }
              }
         }
@@ -524,9 +745,9 @@ impl FileOperationManager {
     }
 
     fn perform_delete(app: &AppHandle, op: &Arc<Mutex<FileOperation>>, sources: Vec<PathBuf>) -> Result<(), String> {
-        let (turbo, cancel_flag, turbo_flag) = {
+        let (turbo, cancel_flag, turbo_flag, archive_password) = {
             let locked = op.lock().unwrap();
-            (locked.turbo, locked.cancel_flag.clone(), locked.turbo_flag.clone())
+            (locked.turbo, locked.cancel_flag.clone(), locked.turbo_flag.clone(), locked.archive_password.clone())
         };
 
         let mut real_sources = Vec::new();
@@ -545,9 +766,13 @@ impl FileOperationManager {
         }
 
         // 1. Handle Virtual Sources (Archive Deletion)
+        // Refuse the whole batch up front if one archive can't be modified (ISO, RAR)
+        for archive_path in virtual_sources.keys() {
+            crate::commands::archive::check_removable(archive_path).map_err(|e| e.to_string())?;
+        }
         for (archive_path, internal_paths) in virtual_sources {
             if cancel_flag.load(Ordering::Relaxed) { return Ok(()); }
-            crate::commands::archive::remove_items_from_archive(archive_path, internal_paths).map_err(|e| e.to_string())?;
+            crate::commands::archive::remove_items_from_archive(archive_path, internal_paths, archive_password.as_deref()).map_err(|e| e.to_string())?;
         }
 
         if real_sources.is_empty() {
@@ -566,7 +791,7 @@ impl FileOperationManager {
             // Prepare double-null terminated string
             let mut buffer: Vec<u16> = Vec::new();
             for src in &real_sources {
-                let path_str = src.to_string_lossy().replace("/", "\\");
+                let path_str = strip_extended_length(&src.to_string_lossy()).replace("/", "\\");
                 buffer.extend(path_str.encode_utf16());
                 buffer.push(0);
             }
@@ -601,6 +826,7 @@ impl FileOperationManager {
         
         let mut handles = Vec::with_capacity(concurrency);
         let real_sources_arc = Arc::new(real_sources);
+        let retry = RetryPolicy::from_config(app);
         let processed_files_atomic = Arc::new(AtomicUsize::new(0));
 
         {
@@ -647,13 +873,13 @@ impl FileOperationManager {
                     let i = idx.fetch_add(1, Ordering::Relaxed);
                     if i >= total_items { break; }
 
-                    let src = &sources[i];
+                    let src = &long_path(&sources[i]);
                     
-                    let res = if src.is_dir() {
+                    let res = retry.run(&cancel, || if src.is_dir() {
                         std::fs::remove_dir_all(src)
                     } else {
                         std::fs::remove_file(src)
-                    };
+                    });
 
                     if let Err(e) = res {
                         if e.kind() != std::io::ErrorKind::NotFound {
@@ -701,9 +927,9 @@ impl FileOperationManager {
     }
 
     fn perform_trash(app: &AppHandle, op: &Arc<Mutex<FileOperation>>, sources: Vec<PathBuf>) -> Result<(), String> {
-        let cancel_flag = {
+        let (cancel_flag, archive_password) = {
             let locked = op.lock().unwrap();
-            locked.cancel_flag.clone()
+            (locked.cancel_flag.clone(), locked.archive_password.clone())
         };
 
         let mut real_sources = Vec::new();
@@ -722,9 +948,12 @@ impl FileOperationManager {
         }
 
         // 1. Handle Virtual Sources (Delete from Archive, no Trash support)
+        for archive_path in virtual_sources.keys() {
+            crate::commands::archive::check_removable(archive_path).map_err(|e| e.to_string())?;
+        }
         for (archive_path, internal_paths) in virtual_sources {
             if cancel_flag.load(Ordering::Relaxed) { return Ok(()); }
-            crate::commands::archive::remove_items_from_archive(archive_path, internal_paths).map_err(|e| e.to_string())?;
+            crate::commands::archive::remove_items_from_archive(archive_path, internal_paths, archive_password.as_deref()).map_err(|e| e.to_string())?;
         }
 
         if real_sources.is_empty() {
@@ -746,7 +975,7 @@ impl FileOperationManager {
 
             let mut buffer: Vec<u16> = Vec::new();
             for src in real_sources {
-                let path_str = src.to_string_lossy().replace("/", "\\");
+                let path_str = strip_extended_length(&src.to_string_lossy()).replace("/", "\\");
                 buffer.extend(path_str.encode_utf16());
                 buffer.push(0);
             }
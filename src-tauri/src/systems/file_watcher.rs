@@ -0,0 +1,102 @@
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// Quiet time required after the last write before `file_changed` is sent, so a log being
+/// appended to line by line produces one event per burst.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a burst can hold `file_changed` back: a file written continuously is still reported
+/// at this pace.
+const MAX_WAIT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChangedEvent {
+    pub path: String,
+    /// False once the file was deleted or renamed away
+    pub exists: bool,
+    pub size: u64,
+    pub modified: u64,
+}
+
+/// Watchers for single files (live preview refresh), keyed by path. Dropping a watcher closes its
+/// channel, which ends the debounce thread.
+#[derive(Default)]
+pub struct FileWatchManager(Mutex<HashMap<PathBuf, RecommendedWatcher>>);
+
+impl FileWatchManager {
+    pub fn watch(&self, app: AppHandle, path: PathBuf) -> notify::Result<()> {
+        let mut watchers = self.0.lock().unwrap();
+        if watchers.contains_key(&path) {
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::channel::<()>();
+        let target = path.clone();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| {
+                let Ok(event) = res else { return; };
+                if matches!(event.kind, EventKind::Access(_)) { return; }
+                if event.paths.iter().any(|p| p == &target) {
+                    let _ = tx.send(());
+                }
+            },
+            Config::default(),
+        )?;
+        // The parent is watched rather than the file itself so that editors replacing the file
+        // (write to temp + rename) keep being followed
+        let parent = path.parent().unwrap_or(&path);
+        watcher.watch(parent, RecursiveMode::NonRecursive)?;
+
+        let watched = path.clone();
+        std::thread::spawn(move || {
+            while rx.recv().is_ok() {
+                // Wait for the writes to settle, or for MAX_WAIT since the first of them; a
+                // disconnect here means the watch was removed
+                let first = Instant::now();
+                loop {
+                    let remaining = MAX_WAIT.saturating_sub(first.elapsed());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    match rx.recv_timeout(DEBOUNCE.min(remaining)) {
+                        Ok(()) => continue,
+                        Err(mpsc::RecvTimeoutError::Timeout) => break,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                let _ = app.emit("file_changed", Self::snapshot(&watched));
+            }
+        });
+
+        watchers.insert(path, watcher);
+        Ok(())
+    }
+
+    pub fn unwatch(&self, path: &Path) {
+        self.0.lock().unwrap().remove(path);
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    fn snapshot(path: &Path) -> FileChangedEvent {
+        let metadata = std::fs::metadata(path).ok();
+        FileChangedEvent {
+            path: path.to_string_lossy().to_string(),
+            exists: metadata.is_some(),
+            size: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+            modified: metadata
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
@@ -1,2 +1,5 @@
+pub mod drive_space;
 pub mod file_ops;
+pub mod file_watcher;
 pub mod quick_access_watcher;
+pub mod search_index;
@@ -0,0 +1,349 @@
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+use walkdir::WalkDir;
+use crate::models::{CommandError, ProgressEvent};
+
+const INDEX_HEADER: &str = "OXYIDX1";
+/// Indexes loaded from a previous session missed any change made while the app was closed
+const MAX_INDEX_AGE_MS: u64 = 6 * 60 * 60 * 1000;
+/// Past this many live changes the index is considered stale until rebuilt
+const MAX_OVERLAY_ENTRIES: usize = 100_000;
+/// Cap on entries picked up when a whole folder is created/moved under an indexed root
+const MAX_NEW_DIR_ENTRIES: usize = 10_000;
+
+/// One file or folder recorded in a filename index.
+#[derive(Debug, Clone)]
+pub struct IndexRow {
+    pub path: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: u64,
+    pub is_hidden: bool,
+    pub is_system: bool,
+    /// Set when an ancestor folder is hidden (the live walker prunes those)
+    pub under_hidden: bool,
+}
+
+impl IndexRow {
+    fn from_metadata(path: &Path, metadata: &fs::Metadata, under_hidden: bool) -> Self {
+        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let (is_hidden, is_system, _) = crate::utils::get_file_attributes(metadata, &name);
+        let is_dir = metadata.is_dir();
+        Self {
+            path: path.to_string_lossy().to_string(),
+            is_dir,
+            size: if is_dir { 0 } else { metadata.len() },
+            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH)
+                .duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            is_hidden,
+            is_system,
+            under_hidden,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.path.rsplit(['\\', '/']).next().unwrap_or(&self.path)
+    }
+
+    /// `flags \t size \t modified \t path` (tabs and newlines can't appear in Windows paths)
+    fn to_line(&self) -> String {
+        let mut flags = String::with_capacity(4);
+        if self.is_dir { flags.push('d'); }
+        if self.is_hidden { flags.push('h'); }
+        if self.is_system { flags.push('s'); }
+        if self.under_hidden { flags.push('u'); }
+        if flags.is_empty() { flags.push('-'); }
+        format!("{}\t{}\t{}\t{}\n", flags, self.size, self.modified, self.path)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(4, '\t');
+        let flags = parts.next()?;
+        let size = parts.next()?.parse().ok()?;
+        let modified = parts.next()?.parse().ok()?;
+        let path = parts.next()?.to_string();
+        Some(Self {
+            path,
+            is_dir: flags.contains('d'),
+            size,
+            modified,
+            is_hidden: flags.contains('h'),
+            is_system: flags.contains('s'),
+            under_hidden: flags.contains('u'),
+        })
+    }
+}
+
+fn path_key(path: &str) -> String {
+    path.to_lowercase()
+}
+
+fn prefix_key(root: &Path) -> String {
+    let root = path_key(&root.to_string_lossy());
+    format!("{}{}", root.trim_end_matches(['\\', '/']), MAIN_SEPARATOR)
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// Changes seen by the watcher since the index file was written.
+#[derive(Default, Clone)]
+struct IndexOverlay {
+    /// Lowercased path -> current row, or None when the path was removed
+    changed: HashMap<String, Option<IndexRow>>,
+    removed_prefixes: Vec<String>,
+    overflowed: bool,
+}
+
+impl IndexOverlay {
+    fn record(&mut self, path: &Path, walk_new_dirs: bool) {
+        let key = path_key(&path.to_string_lossy());
+        match fs::symlink_metadata(path) {
+            Ok(metadata) => {
+                let is_dir = metadata.is_dir();
+                self.changed.insert(key, Some(IndexRow::from_metadata(path, &metadata, false)));
+
+                // A folder moved in from elsewhere only reports itself: pick up its content too
+                if is_dir && walk_new_dirs {
+                    for entry in WalkDir::new(path).min_depth(1).into_iter().filter_map(|e| e.ok()).take(MAX_NEW_DIR_ENTRIES) {
+                        if let Ok(m) = entry.metadata() {
+                            let k = path_key(&entry.path().to_string_lossy());
+                            self.changed.insert(k, Some(IndexRow::from_metadata(entry.path(), &m, false)));
+                        }
+                    }
+                }
+            }
+            Err(_) => {
+                self.removed_prefixes.push(format!("{}{}", key, MAIN_SEPARATOR));
+                self.changed.insert(key, None);
+            }
+        }
+
+        if self.changed.len() > MAX_OVERLAY_ENTRIES {
+            self.overflowed = true;
+            self.changed.clear();
+            self.removed_prefixes.clear();
+        }
+    }
+
+    fn is_removed(&self, key: &str) -> bool {
+        self.removed_prefixes.iter().any(|p| key.starts_with(p))
+    }
+}
+
+/// A filename index for one root folder, kept current by a recursive watcher.
+pub struct SearchIndex {
+    pub root: PathBuf,
+    pub file: PathBuf,
+    pub built_at: u64,
+    /// Built during this session, so the watcher has seen every change since
+    live: bool,
+    overlay: Arc<Mutex<IndexOverlay>>,
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl SearchIndex {
+    fn new(root: PathBuf, file: PathBuf, built_at: u64, live: bool) -> Self {
+        let overlay = Arc::new(Mutex::new(IndexOverlay::default()));
+        let watcher = Self::start_watcher(&root, overlay.clone());
+        Self { root, file, built_at, live, overlay, _watcher: watcher }
+    }
+
+    fn start_watcher(root: &Path, overlay: Arc<Mutex<IndexOverlay>>) -> Option<RecommendedWatcher> {
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| {
+                let Ok(event) = res else { return; };
+                let walk_new_dirs = match event.kind {
+                    EventKind::Access(_) => return,
+                    EventKind::Create(_) | EventKind::Modify(notify::event::ModifyKind::Name(_)) => true,
+                    _ => false,
+                };
+                if let Ok(mut overlay) = overlay.lock() {
+                    for path in &event.paths {
+                        overlay.record(path, walk_new_dirs);
+                    }
+                }
+            },
+            Config::default(),
+        ).ok()?;
+
+        if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+            log::warn!("Could not watch indexed root {:?}: {}", root, e);
+            return None;
+        }
+        Some(watcher)
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        let overflowed = self.overlay.lock().map(|o| o.overflowed).unwrap_or(true);
+        !overflowed && (self.live || now_ms().saturating_sub(self.built_at) < MAX_INDEX_AGE_MS)
+    }
+
+    pub fn covers(&self, path: &Path) -> bool {
+        let key = path_key(&path.to_string_lossy());
+        let root = prefix_key(&self.root);
+        format!("{}{}", key.trim_end_matches(['\\', '/']), MAIN_SEPARATOR).starts_with(&root)
+    }
+
+    /// Calls `f` for every indexed entry below `root`, with live changes applied.
+    /// Iteration stops as soon as `f` returns false.
+    pub fn for_each_row(&self, root: &Path, mut f: impl FnMut(IndexRow) -> bool) -> io::Result<()> {
+        let prefix = prefix_key(root);
+        // Snapshot so the watcher isn't blocked for the duration of the search
+        let overlay = self.overlay.lock().map(|o| o.clone()).unwrap_or_default();
+
+        let reader = BufReader::with_capacity(256 * 1024, File::open(&self.file)?);
+        for line in reader.lines().skip(1) {
+            let line = line?;
+            let Some(row) = IndexRow::parse(&line) else { continue; };
+            let key = path_key(&row.path);
+            if !key.starts_with(&prefix) || overlay.changed.contains_key(&key) || overlay.is_removed(&key) {
+                continue;
+            }
+            if !f(row) {
+                return Ok(());
+            }
+        }
+
+        for (key, row) in overlay.changed {
+            if let Some(row) = row {
+                if key.starts_with(&prefix) && !f(row) {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Registry of the filename indexes available on disk, keyed by root folder.
+#[derive(Default)]
+pub struct SearchIndexManager(pub Mutex<HashMap<PathBuf, Arc<SearchIndex>>>);
+
+impl SearchIndexManager {
+    fn index_dir(app: &AppHandle) -> Result<PathBuf, CommandError> {
+        let dir = app.path().app_cache_dir().map_err(|e| CommandError::IoError(e.to_string()))?.join("search_index");
+        if !dir.exists() {
+            fs::create_dir_all(&dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+        }
+        Ok(dir)
+    }
+
+    fn index_file(app: &AppHandle, root: &Path) -> Result<PathBuf, CommandError> {
+        let hash = blake3::hash(path_key(&root.to_string_lossy()).as_bytes());
+        Ok(Self::index_dir(app)?.join(format!("{}.idx", &hash.to_hex()[..16])))
+    }
+
+    /// Registers the indexes written in previous sessions.
+    pub fn load(&self, app: &AppHandle) -> Result<(), CommandError> {
+        let dir = Self::index_dir(app)?;
+        let mut indexes = self.0.lock().map_err(|_| CommandError::SystemError("Failed to lock search index".to_string()))?;
+
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let file = entry.path();
+            if file.extension().and_then(|e| e.to_str()) != Some("idx") { continue; }
+
+            let Ok(handle) = File::open(&file) else { continue; };
+            let mut header = String::new();
+            if BufReader::new(handle).read_line(&mut header).is_err() { continue; }
+            let mut parts = header.trim_end().splitn(3, '\t');
+            if parts.next() != Some(INDEX_HEADER) { continue; }
+            let (Some(built_at), Some(root)) = (parts.next().and_then(|b| b.parse().ok()), parts.next()) else { continue; };
+
+            let root = PathBuf::from(root);
+            if root.exists() {
+                indexes.insert(root.clone(), Arc::new(SearchIndex::new(root, file, built_at, false)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a fresh index covering `path`, preferring the most specific root.
+    pub fn find(&self, path: &Path) -> Option<Arc<SearchIndex>> {
+        let indexes = self.0.lock().ok()?;
+        indexes.values()
+            .filter(|idx| idx.covers(path) && idx.is_fresh())
+            .max_by_key(|idx| idx.root.as_os_str().len())
+            .cloned()
+    }
+
+    /// Walks `root` once and writes its filename index. Returns the number of entries.
+    pub fn build(&self, app: &AppHandle, root: &Path) -> Result<usize, CommandError> {
+        let file = Self::index_file(app, root)?;
+        let temp_file = file.with_extension("idx.tmp");
+        let built_at = now_ms();
+        let op_id = "index_op".to_string();
+
+        // Watch before walking so changes made during the build aren't lost
+        let overlay = Arc::new(Mutex::new(IndexOverlay::default()));
+        let watcher = SearchIndex::start_watcher(root, overlay.clone());
+
+        let mut writer = BufWriter::with_capacity(256 * 1024, File::create(&temp_file)?);
+        writeln!(writer, "{}\t{}\t{}", INDEX_HEADER, built_at, root.to_string_lossy())?;
+
+        let mut count = 0usize;
+        let mut last_emit = Instant::now();
+        // hidden_stack[d]: the entry at depth d is hidden or lies under a hidden folder
+        let mut hidden_stack: Vec<bool> = Vec::new();
+
+        for entry in WalkDir::new(root).sort_by_file_name().into_iter().filter_map(|e| e.ok()) {
+            let depth = entry.depth();
+            let Ok(metadata) = entry.metadata() else { continue; };
+            let under_hidden = depth > 0 && hidden_stack.get(depth - 1).copied().unwrap_or(false);
+            let row = IndexRow::from_metadata(entry.path(), &metadata, under_hidden);
+
+            hidden_stack.truncate(depth);
+            hidden_stack.push(depth > 0 && (under_hidden || row.is_hidden));
+            if depth == 0 { continue; }
+
+            writer.write_all(row.to_line().as_bytes())?;
+            count += 1;
+
+            if last_emit.elapsed().as_millis() > 250 {
+                let _ = app.emit("progress", ProgressEvent {
+                    id: op_id.clone(),
+                    task: "index".to_string(),
+                    current: count as u64,
+                    total: 0,
+                    status: "running".to_string(),
+                    filename: Some(row.path),
+                });
+                last_emit = Instant::now();
+            }
+        }
+
+        writer.flush()?;
+        drop(writer);
+        fs::rename(&temp_file, &file)?;
+
+        let index = Arc::new(SearchIndex {
+            root: root.to_path_buf(),
+            file,
+            built_at,
+            live: watcher.is_some(),
+            overlay,
+            _watcher: watcher,
+        });
+        self.0.lock()
+            .map_err(|_| CommandError::SystemError("Failed to lock search index".to_string()))?
+            .insert(root.to_path_buf(), index);
+
+        let _ = app.emit("progress", ProgressEvent {
+            id: op_id,
+            task: "index".to_string(),
+            current: count as u64,
+            total: count as u64,
+            status: "completed".to_string(),
+            filename: None,
+        });
+
+        Ok(count)
+    }
+}
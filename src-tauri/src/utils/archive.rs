@@ -1,4 +1,8 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ArchiveFormat {
@@ -42,14 +46,89 @@ impl ArchiveFormat {
             }
         }
     }
+
+    /// Format from the file's leading bytes, regardless of its name.
+    pub fn from_content(path: &Path) -> Option<Self> {
+        let signature = crate::utils::file_type::detect_file(path).ok()??;
+        match signature.kind {
+            "zip" => Some(ArchiveFormat::Zip),
+            "7z" => Some(ArchiveFormat::SevenZip),
+            "tar" => Some(ArchiveFormat::Tar),
+            "gzip" => Self::compressed_tar(path, ArchiveFormat::TarGz),
+            "xz" => Self::compressed_tar(path, ArchiveFormat::TarXz),
+            "zstd" => Self::compressed_tar(path, ArchiveFormat::TarZst),
+            "bzip2" => Self::compressed_tar(path, ArchiveFormat::TarBz2),
+            "rar" => Some(ArchiveFormat::Rar),
+            "iso" => Some(ArchiveFormat::Iso),
+            _ => None,
+        }
+    }
+
+    /// `format` if the compressed stream in `path` holds a tarball, None for a single compressed
+    /// file such as `app.log.gz`.
+    fn compressed_tar(path: &Path, format: ArchiveFormat) -> Option<Self> {
+        let file = File::open(path).ok()?;
+        let is_tar = match format {
+            ArchiveFormat::TarGz => has_tar_header(flate2::read::GzDecoder::new(file)),
+            ArchiveFormat::TarXz => has_tar_header(xz2::read::XzDecoder::new(file)),
+            ArchiveFormat::TarBz2 => has_tar_header(bzip2::read::BzDecoder::new(file)),
+            ArchiveFormat::TarZst => zstd::stream::read::Decoder::new(file).is_ok_and(|d| has_tar_header(d)),
+            _ => false,
+        };
+        is_tar.then_some(format)
+    }
+}
+
+/// Whether `reader` starts with a tar header, i.e. the `ustar` magic at offset 257.
+fn has_tar_header(reader: impl Read) -> bool {
+    let mut header = Vec::with_capacity(512);
+    let _ = reader.take(512).read_to_end(&mut header);
+    header.get(257..262) == Some(b"ustar".as_slice())
+}
+
+/// Format strings accepted by `compress_to_archive`.
+pub const COMPRESSION_FORMATS: &[&str] = &["zip", "7z", "tar", "zst", "tar.zst", "gz", "tar.gz", "bz2", "tar.bz2", "xz", "tar.xz"];
+
+/// Quality presets accepted by `compress_to_archive`.
+pub const COMPRESSION_LEVELS: &[&str] = &["fast", "normal", "best"];
+
+pub fn is_compression_format_supported(format: &str) -> bool {
+    COMPRESSION_FORMATS.contains(&format.to_lowercase().as_str())
+}
+
+/// Extensions that say nothing about the content: such files are sniffed like extensionless ones.
+/// Other unknown extensions aren't, so listing or searching a folder doesn't open every file.
+const GENERIC_EXTENSIONS: &[&str] = &["bin", "dat", "tmp", "raw", "part", "download", "crdownload"];
+
+/// Archive format by extension, falling back to the file's magic bytes when the extension is
+/// missing or generic (`.bin`, `.dat`, ...).
+pub fn detect_format(path: &Path) -> Option<ArchiveFormat> {
+    ArchiveFormat::from_path(path).or_else(|| {
+        let generic = match path.extension() {
+            None => true,
+            Some(ext) => GENERIC_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()),
+        };
+        if !generic || !path.is_file() {
+            return None;
+        }
+        ArchiveFormat::from_content(path)
+    })
 }
 
 pub fn is_archive(path: &Path) -> bool {
-    ArchiveFormat::from_path(path).is_some()
+    detect_format(path).is_some()
 }
 
 /// Splits a virtual path like C:\path\to\archive.zip\folder into (archive_path, internal_path)
 pub fn split_virtual_path(path: &str) -> Option<(PathBuf, String)> {
+    // Behind the `\\?\` prefix '/' isn't a separator, so the archive wouldn't be found
+    let normalized;
+    let path = if path.starts_with("\\\\?\\") {
+        normalized = path.replace('/', "\\");
+        normalized.as_str()
+    } else {
+        path
+    };
     let path_buf = PathBuf::from(path);
     let mut current = path_buf.as_path();
 
@@ -65,3 +144,288 @@ pub fn split_virtual_path(path: &str) -> Option<(PathBuf, String)> {
     }
     None
 }
+
+/// Returns the path of the `index`-th (0-based) volume of a split archive: `archive.zip.001`, ...
+pub fn volume_path(base: &Path, index: usize) -> PathBuf {
+    let mut name = base.as_os_str().to_os_string();
+    name.push(format!(".{:03}", index + 1));
+    PathBuf::from(name)
+}
+
+/// For a first volume `name.001`, the original path `name` and every consecutive volume on disk.
+pub fn find_volumes(first_volume: &Path) -> Option<(PathBuf, Vec<PathBuf>)> {
+    let name = first_volume.as_os_str().to_str()?;
+    let base = PathBuf::from(name.strip_suffix(".001")?);
+    let volumes = (0..).map(|i| volume_path(&base, i)).take_while(|p| p.is_file()).collect();
+    Some((base, volumes))
+}
+
+/// Writer that spreads its output over fixed-size volume files.
+/// Seeking is supported across volumes, since ZIP and 7z writers patch headers after the fact.
+pub struct SplittingWriter {
+    base: PathBuf,
+    volume_size: u64,
+    files: Vec<File>,
+    created: Arc<Mutex<Vec<PathBuf>>>,
+    position: u64,
+    len: u64,
+}
+
+impl SplittingWriter {
+    /// `created` receives the path of every volume as it is opened.
+    pub fn new(base: &Path, volume_size: u64, created: Arc<Mutex<Vec<PathBuf>>>) -> Self {
+        Self {
+            base: base.to_path_buf(),
+            volume_size: volume_size.max(1),
+            files: Vec::new(),
+            created,
+            position: 0,
+            len: 0,
+        }
+    }
+
+    fn volume(&mut self, index: usize) -> io::Result<&mut File> {
+        while self.files.len() <= index {
+            let path = volume_path(&self.base, self.files.len());
+            let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path)?;
+            self.files.push(file);
+            if let Ok(mut created) = self.created.lock() {
+                created.push(path);
+            }
+        }
+        Ok(&mut self.files[index])
+    }
+}
+
+impl Write for SplittingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let index = (self.position / self.volume_size) as usize;
+        let offset = self.position % self.volume_size;
+        let n = std::cmp::min(buf.len() as u64, self.volume_size - offset) as usize;
+
+        let file = self.volume(index)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(&buf[..n])?;
+
+        self.position += n as u64;
+        self.len = self.len.max(self.position);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for file in &mut self.files {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl Seek for SplittingWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(p) => Some(p),
+            SeekFrom::Current(d) => self.position.checked_add_signed(d),
+            SeekFrom::End(d) => self.len.checked_add_signed(d),
+        };
+        match new_pos {
+            Some(p) => {
+                self.position = p;
+                Ok(p)
+            }
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "invalid seek to a negative position")),
+        }
+    }
+}
+
+/// ISO 9660 logical sector size.
+const ISO_SECTOR: u64 = 2048;
+/// Largest directory extent read by `iso_modified_times`; real ones are a few sectors.
+const ISO_MAX_DIR_BYTES: u32 = 16 * 1024 * 1024;
+
+/// Recording dates (ms since epoch) of the files and folders of an ISO image, keyed by their
+/// lowercase path from the root ("/dir/file.txt") without the ";1" version suffix. Both the
+/// ISO 9660 and the Joliet trees are read, so either naming finds its entry. Unreadable parts
+/// are left out.
+pub fn iso_modified_times<R: Read + Seek>(reader: &mut R) -> HashMap<String, u64> {
+    let mut times = HashMap::new();
+    let mut descriptor = [0u8; ISO_SECTOR as usize];
+    for sector in 16..64 {
+        if reader.seek(SeekFrom::Start(sector * ISO_SECTOR)).is_err() || reader.read_exact(&mut descriptor).is_err() {
+            break;
+        }
+        if &descriptor[1..6] != b"CD001" || descriptor[0] == 255 {
+            break;
+        }
+        // Primary descriptor, or a supplementary one with a Joliet escape sequence (UCS-2 names)
+        let joliet = descriptor[0] == 2 && descriptor[88..90] == *b"%/" && matches!(descriptor[90], b'@' | b'C' | b'E');
+        if descriptor[0] == 1 || joliet {
+            let root = &descriptor[156..190];
+            let mut visited = HashSet::new();
+            read_iso_dir(reader, le_u32(&root[2..6]), le_u32(&root[10..14]), "", joliet, &mut visited, &mut times);
+        }
+    }
+    times
+}
+
+fn read_iso_dir<R: Read + Seek>(
+    reader: &mut R,
+    extent: u32,
+    size: u32,
+    prefix: &str,
+    joliet: bool,
+    visited: &mut HashSet<u32>,
+    times: &mut HashMap<String, u64>,
+) {
+    if size > ISO_MAX_DIR_BYTES || !visited.insert(extent) {
+        return;
+    }
+    let mut data = vec![0u8; size as usize];
+    if reader.seek(SeekFrom::Start(extent as u64 * ISO_SECTOR)).is_err() || reader.read_exact(&mut data).is_err() {
+        return;
+    }
+
+    let mut subdirs = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let len = data[pos] as usize;
+        // Records never cross a sector boundary: a zero length pads to the next one
+        if len == 0 {
+            pos = (pos / ISO_SECTOR as usize + 1) * ISO_SECTOR as usize;
+            continue;
+        }
+        let Some(record) = data.get(pos..pos + len).filter(|r| r.len() >= 34) else { break };
+        pos += len;
+
+        let name_len = record[32] as usize;
+        let Some(raw_name) = record.get(33..33 + name_len) else { continue };
+        // "." and ".."
+        if name_len == 1 && raw_name[0] <= 1 {
+            continue;
+        }
+        let name = if joliet {
+            let units: Vec<u16> = raw_name.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        } else {
+            raw_name.iter().map(|&b| b as char).collect()
+        };
+        let path = format!("{}/{}", prefix, name.split(';').next().unwrap_or(&name).to_lowercase());
+
+        if let Some(time) = iso_record_time(&record[18..25]) {
+            times.insert(path.clone(), time);
+        }
+        if record[25] & 0x02 != 0 {
+            subdirs.push((le_u32(&record[2..6]), le_u32(&record[10..14]), path));
+        }
+    }
+
+    for (extent, size, path) in subdirs {
+        read_iso_dir(reader, extent, size, &path, joliet, visited, times);
+    }
+}
+
+/// A directory record's 7-byte recording date: years since 1900, month, day, hour, minute,
+/// second, and the offset from GMT in 15-minute steps.
+fn iso_record_time(field: &[u8]) -> Option<u64> {
+    let date = chrono::NaiveDate::from_ymd_opt(1900 + field[0] as i32, field[1] as u32, field[2] as u32)?;
+    let local = date.and_hms_opt(field[3] as u32, field[4] as u32, field[5] as u32)?;
+    let offset_ms = (field[6] as i8) as i64 * 15 * 60 * 1000;
+    u64::try_from(local.and_utc().timestamp_millis() - offset_ms).ok()
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_splitting_writer_rolls_over_and_seeks_back() {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("out.zip");
+        let created = Arc::new(Mutex::new(Vec::new()));
+
+        let mut writer = SplittingWriter::new(&base, 4, created.clone());
+        writer.write_all(b"abcdefghij").unwrap();
+        // Patch bytes spanning the first volume boundary
+        writer.seek(SeekFrom::Start(3)).unwrap();
+        writer.write_all(b"XY").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(created.lock().unwrap().len(), 3);
+        assert_eq!(fs::read(volume_path(&base, 0)).unwrap(), b"abcX");
+        assert_eq!(fs::read(volume_path(&base, 1)).unwrap(), b"Yfgh");
+        assert_eq!(fs::read(volume_path(&base, 2)).unwrap(), b"ij");
+
+        let (original, volumes) = find_volumes(&volume_path(&base, 0)).unwrap();
+        assert_eq!(original, base);
+        assert_eq!(volumes.len(), 3);
+        assert!(find_volumes(&base).is_none());
+    }
+
+    #[test]
+    fn test_detect_format_sniffs_generic_names() {
+        let dir = tempdir().unwrap();
+        let gz = b"\x1f\x8b\x08\x00\x00\x00\x00\x00";
+
+        fs::write(dir.path().join("backup"), gz).unwrap();
+        fs::write(dir.path().join("payload.bin"), b"PK\x03\x04rest").unwrap();
+        fs::write(dir.path().join("notes.txt"), gz).unwrap();
+
+        assert_eq!(detect_format(&dir.path().join("backup")), Some(ArchiveFormat::TarGz));
+        assert_eq!(detect_format(&dir.path().join("payload.bin")), Some(ArchiveFormat::Zip));
+        // Known, non-generic extensions are trusted without opening the file
+        assert_eq!(detect_format(&dir.path().join("notes.txt")), None);
+        assert_eq!(detect_format(&dir.path().join("missing.zip")), Some(ArchiveFormat::Zip));
+    }
+
+    /// Directory record as found in an ISO 9660 directory extent.
+    fn iso_record(name: &[u8], extent: u32, size: u32, is_dir: bool, date: [u8; 7]) -> Vec<u8> {
+        let mut record = vec![0u8; 33];
+        record[2..6].copy_from_slice(&extent.to_le_bytes());
+        record[10..14].copy_from_slice(&size.to_le_bytes());
+        record[18..25].copy_from_slice(&date);
+        record[25] = if is_dir { 0x02 } else { 0 };
+        record[32] = name.len() as u8;
+        record.extend_from_slice(name);
+        if record.len() % 2 == 1 {
+            record.push(0);
+        }
+        record[0] = record.len() as u8;
+        record
+    }
+
+    #[test]
+    fn test_iso_modified_times() {
+        let sector = ISO_SECTOR as usize;
+        let mut image = vec![0u8; 20 * sector];
+        let date = [124, 3, 15, 12, 30, 0, 4]; // 2024-03-15 12:30:00 GMT+1
+
+        let pvd = 16 * sector;
+        image[pvd] = 1;
+        image[pvd + 1..pvd + 6].copy_from_slice(b"CD001");
+        let root = iso_record(&[0], 18, ISO_SECTOR as u32, true, date);
+        image[pvd + 156..pvd + 156 + root.len()].copy_from_slice(&root);
+        image[17 * sector] = 255;
+        image[17 * sector + 1..17 * sector + 6].copy_from_slice(b"CD001");
+
+        let mut entries = [iso_record(&[0], 18, 2048, true, date), iso_record(&[1], 18, 2048, true, date)].concat();
+        entries.extend(iso_record(b"README.TXT;1", 30, 10, false, date));
+        entries.extend(iso_record(b"DOCS", 19, 2048, true, date));
+        image[18 * sector..18 * sector + entries.len()].copy_from_slice(&entries);
+        let nested = iso_record(b"A.TXT;1", 31, 10, false, [124, 3, 16, 0, 0, 0, 0]);
+        image[19 * sector..19 * sector + nested.len()].copy_from_slice(&nested);
+
+        let times = iso_modified_times(&mut io::Cursor::new(image));
+        assert_eq!(times.get("/readme.txt"), Some(&1_710_502_200_000));
+        assert!(times.contains_key("/docs"));
+        assert_eq!(times.get("/docs/a.txt"), Some(&1_710_547_200_000));
+        assert_eq!(times.len(), 3);
+    }
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+/// Broad kinds of files, recognised by extension, for search filters and grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileCategory {
+    Image,
+    Document,
+    Video,
+    Audio,
+    Archive,
+    Code,
+}
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff", "ico", "svg", "heic", "heif", "avif",
+    "raw", "cr2", "nef", "arw", "dng", "psd",
+];
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "pdf", "doc", "docx", "docm", "odt", "rtf", "txt", "md", "xls", "xlsx", "xlsm", "ods", "csv",
+    "ppt", "pptx", "pptm", "odp", "epub",
+];
+const VIDEO_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "avi", "mov", "wmv", "webm", "flv", "m4v", "mpg", "mpeg", "m2ts", "3gp",
+];
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "wav", "flac", "aac", "ogg", "opus", "m4a", "wma", "aiff", "mid", "midi",
+];
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "7z", "rar", "tar", "gz", "tgz", "bz2", "xz", "zst", "iso", "cab",
+];
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "c", "h", "cpp", "hpp", "cs", "java", "kt", "go", "py", "rb", "php", "js", "jsx", "ts",
+    "tsx", "html", "css", "scss", "json", "toml", "yaml", "yml", "xml", "sql", "sh", "ps1", "bat",
+];
+
+impl FileCategory {
+    pub const ALL: [FileCategory; 6] = [
+        FileCategory::Image,
+        FileCategory::Document,
+        FileCategory::Video,
+        FileCategory::Audio,
+        FileCategory::Archive,
+        FileCategory::Code,
+    ];
+
+    /// Parses a category name as the UI sends it ("image", "document", ...), in any case.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FileCategory::Image => "image",
+            FileCategory::Document => "document",
+            FileCategory::Video => "video",
+            FileCategory::Audio => "audio",
+            FileCategory::Archive => "archive",
+            FileCategory::Code => "code",
+        }
+    }
+
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            FileCategory::Image => IMAGE_EXTENSIONS,
+            FileCategory::Document => DOCUMENT_EXTENSIONS,
+            FileCategory::Video => VIDEO_EXTENSIONS,
+            FileCategory::Audio => AUDIO_EXTENSIONS,
+            FileCategory::Archive => ARCHIVE_EXTENSIONS,
+            FileCategory::Code => CODE_EXTENSIONS,
+        }
+    }
+
+    /// Whether a file named `file_name` belongs to this category.
+    pub fn contains(self, file_name: &str) -> bool {
+        Path::new(file_name)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .is_some_and(|e| self.extensions().contains(&e.as_str()))
+    }
+
+    /// The category of a file named `file_name`, if any.
+    pub fn of(file_name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.contains(file_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(FileCategory::parse("image"), Some(FileCategory::Image));
+        assert_eq!(FileCategory::parse(" Audio "), Some(FileCategory::Audio));
+        assert_eq!(FileCategory::parse("spreadsheet"), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        assert!(FileCategory::Image.contains("Holiday.JPG"));
+        assert!(FileCategory::Document.contains("report.docx"));
+        assert!(FileCategory::Archive.contains("backup.tar.gz"));
+        assert!(!FileCategory::Image.contains("notes.txt"));
+        assert!(!FileCategory::Code.contains("Makefile"));
+    }
+
+    #[test]
+    fn test_of() {
+        assert_eq!(FileCategory::of("song.flac"), Some(FileCategory::Audio));
+        assert_eq!(FileCategory::of("clip.mkv"), Some(FileCategory::Video));
+        assert_eq!(FileCategory::of("unknown.xyz"), None);
+    }
+}
@@ -0,0 +1,125 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Bytes read from the start of a file to recognise it.
+pub const HEADER_LEN: usize = 4096;
+
+/// ISO 9660 volume descriptors start at sector 16, far past `HEADER_LEN`.
+const ISO_MAGIC_OFFSET: u64 = 0x8001;
+
+pub struct FileSignature {
+    pub kind: &'static str,
+    pub mime: &'static str,
+    /// Extensions that are consistent with this content
+    pub extensions: &'static [&'static str],
+    offset: usize,
+    magic: &'static [u8],
+}
+
+const fn sig(kind: &'static str, mime: &'static str, extensions: &'static [&'static str], offset: usize, magic: &'static [u8]) -> FileSignature {
+    FileSignature { kind, mime, extensions, offset, magic }
+}
+
+/// Checked in order, so more specific signatures come before shorter ones sharing a prefix.
+static SIGNATURES: &[FileSignature] = &[
+    sig("png", "image/png", &["png"], 0, b"\x89PNG\r\n\x1a\n"),
+    sig("jpeg", "image/jpeg", &["jpg", "jpeg", "jfif"], 0, b"\xff\xd8\xff"),
+    sig("gif", "image/gif", &["gif"], 0, b"GIF8"),
+    sig("bmp", "image/bmp", &["bmp", "dib"], 0, b"BM"),
+    sig("tiff", "image/tiff", &["tif", "tiff"], 0, b"II*\x00"),
+    sig("tiff", "image/tiff", &["tif", "tiff"], 0, b"MM\x00*"),
+    sig("ico", "image/x-icon", &["ico"], 0, b"\x00\x00\x01\x00"),
+    sig("webp", "image/webp", &["webp"], 8, b"WEBP"),
+    sig("wav", "audio/wav", &["wav"], 8, b"WAVE"),
+    sig("avi", "video/x-msvideo", &["avi"], 8, b"AVI "),
+    sig("mp4", "video/mp4", &["mp4", "m4v", "m4a", "mov", "heic", "avif", "3gp"], 4, b"ftyp"),
+    sig("mkv", "video/x-matroska", &["mkv", "webm"], 0, b"\x1a\x45\xdf\xa3"),
+    sig("mp3", "audio/mpeg", &["mp3"], 0, b"ID3"),
+    sig("flac", "audio/flac", &["flac"], 0, b"fLaC"),
+    sig("ogg", "audio/ogg", &["ogg", "oga", "ogv", "opus"], 0, b"OggS"),
+    sig("pdf", "application/pdf", &["pdf"], 0, b"%PDF"),
+    sig("zip", "application/zip", &["zip", "docx", "xlsx", "pptx", "odt", "ods", "odp", "jar", "apk", "epub", "nupkg", "vsix"], 0, b"PK\x03\x04"),
+    sig("zip", "application/zip", &["zip"], 0, b"PK\x05\x06"),
+    sig("7z", "application/x-7z-compressed", &["7z"], 0, b"7z\xbc\xaf\x27\x1c"),
+    sig("rar", "application/vnd.rar", &["rar"], 0, b"Rar!\x1a\x07"),
+    sig("gzip", "application/gzip", &["gz", "tgz"], 0, b"\x1f\x8b"),
+    sig("xz", "application/x-xz", &["xz", "txz"], 0, b"\xfd7zXZ\x00"),
+    sig("bzip2", "application/x-bzip2", &["bz2", "tbz2"], 0, b"BZh"),
+    sig("zstd", "application/zstd", &["zst", "tzst"], 0, b"\x28\xb5\x2f\xfd"),
+    sig("tar", "application/x-tar", &["tar"], 257, b"ustar"),
+    sig("cab", "application/vnd.ms-cab-compressed", &["cab"], 0, b"MSCF"),
+    // OLE compound file, shared by installers, legacy Office documents and Outlook messages
+    sig("cfb", "application/x-cfb", &["msi", "msp", "doc", "xls", "ppt", "msg", "pub", "vsd"], 0, CFB_MAGIC),
+    sig("exe", "application/vnd.microsoft.portable-executable", &["exe", "dll", "sys", "scr", "cpl", "ocx", "efi", "com"], 0, b"MZ"),
+    sig("elf", "application/x-elf", &["", "so", "o", "elf"], 0, b"\x7fELF"),
+    sig("sqlite", "application/vnd.sqlite3", &["db", "sqlite", "sqlite3"], 0, b"SQLite format 3\x00"),
+    sig("woff", "font/woff", &["woff"], 0, b"wOFF"),
+    sig("woff2", "font/woff2", &["woff2"], 0, b"wOF2"),
+    sig("ttf", "font/ttf", &["ttf"], 0, b"\x00\x01\x00\x00\x00"),
+    sig("otf", "font/otf", &["otf"], 0, b"OTTO"),
+    sig("lnk", "application/x-ms-shortcut", &["lnk"], 0, b"\x4c\x00\x00\x00\x01\x14\x02\x00"),
+];
+
+static ISO: FileSignature = sig("iso", "application/x-iso9660-image", &["iso", "img"], 0, b"CD001");
+
+const CFB_MAGIC: &[u8] = b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1";
+
+/// A compound file named like an installer; the content alone can't tell it from a document.
+static MSI: FileSignature = sig("msi", "application/x-msi", &["msi", "msp"], 0, CFB_MAGIC);
+
+/// Identifies content from its leading bytes (`HEADER_LEN` is enough for every entry).
+pub fn detect_bytes(header: &[u8]) -> Option<&'static FileSignature> {
+    SIGNATURES.iter().find(|s| header.get(s.offset..s.offset + s.magic.len()) == Some(s.magic))
+}
+
+/// Reads the start of `path` (and the ISO descriptor if needed) and identifies it.
+pub fn detect_file(path: &Path) -> io::Result<Option<&'static FileSignature>> {
+    let mut file = File::open(path)?;
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    (&mut file).take(HEADER_LEN as u64).read_to_end(&mut header)?;
+    if let Some(found) = detect_bytes(&header) {
+        let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        if found.kind == "cfb" && MSI.matches_extension(&extension) {
+            return Ok(Some(&MSI));
+        }
+        return Ok(Some(found));
+    }
+
+    let mut iso_magic = [0u8; 5];
+    if file.seek(SeekFrom::Start(ISO_MAGIC_OFFSET)).is_ok() && file.read_exact(&mut iso_magic).is_ok() && iso_magic.as_slice() == ISO.magic {
+        return Ok(Some(&ISO));
+    }
+    Ok(None)
+}
+
+impl FileSignature {
+    /// True when `extension` (without dot, any case) is a usual one for this content.
+    pub fn matches_extension(&self, extension: &str) -> bool {
+        let extension = extension.to_lowercase();
+        self.extensions.iter().any(|e| *e == extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_bytes() {
+        assert_eq!(detect_bytes(b"\x89PNG\r\n\x1a\n\x00\x00").map(|s| s.kind), Some("png"));
+        assert_eq!(detect_bytes(b"MZ\x90\x00").map(|s| s.kind), Some("exe"));
+        assert_eq!(detect_bytes(b"RIFF\x00\x00\x00\x00WEBPVP8 ").map(|s| s.kind), Some("webp"));
+        assert!(detect_bytes(b"plain text").is_none());
+
+        let mut tar = vec![0u8; 512];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(detect_bytes(&tar).map(|s| s.kind), Some("tar"));
+
+        assert_eq!(detect_bytes(b"\xd0\xcf\x11\xe0\xa1\xb1\x1a\xe1\x00").map(|s| s.kind), Some("cfb"));
+
+        let jpeg = detect_bytes(b"\xff\xd8\xff\xe0").unwrap();
+        assert!(jpeg.matches_extension("JPG"));
+        assert!(!jpeg.matches_extension("exe"));
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 #[cfg(target_os = "windows")]
-use windows::Win32::Storage::FileSystem::{CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS, GetDriveTypeW};
+use windows::Win32::Storage::FileSystem::{CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING, FILE_FLAG_BACKUP_SEMANTICS, GetDriveTypeW, GetDiskFreeSpaceExW};
 #[cfg(target_os = "windows")]
 use windows::Win32::System::Ioctl::{IOCTL_STORAGE_GET_DEVICE_NUMBER, STORAGE_DEVICE_NUMBER, IOCTL_STORAGE_QUERY_PROPERTY, STORAGE_PROPERTY_QUERY, StorageDeviceSeekPenaltyProperty, DEVICE_SEEK_PENALTY_DESCRIPTOR, PropertyStandardQuery};
 #[cfg(target_os = "windows")]
@@ -124,3 +124,166 @@ pub fn is_ssd(path: &Path) -> bool {
     }
     false
 }
+
+/// Root of the volume holding `path`: "C:\\" for drive paths, "\\\\server\\share\\" for UNC paths.
+pub fn volume_root(path: &Path) -> Option<String> {
+    let path_str = crate::utils::path_security::strip_extended_length(&path.to_string_lossy());
+    if let Some(rest) = path_str.strip_prefix("\\\\") {
+        let mut parts = rest.split('\\').filter(|p| !p.is_empty());
+        let server = parts.next()?;
+        let share = parts.next()?;
+        return Some(format!("\\\\{}\\{}\\", server, share));
+    }
+    let mut chars = path_str.chars();
+    if let (Some(letter), Some(':')) = (chars.next(), chars.next()) {
+        return Some(format!("{}:\\", letter.to_ascii_uppercase()));
+    }
+    if path_str.starts_with('/') {
+        return Some("/".to_string());
+    }
+    None
+}
+
+/// Free bytes available to the current user and total size of the volume holding `path`.
+pub fn get_disk_space(path: &Path) -> Option<(u64, u64)> {
+    #[cfg(target_os = "windows")]
+    {
+        let root = volume_root(path)?;
+        let wide_root: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut free_bytes_available = 0u64;
+        let mut total_bytes = 0u64;
+        unsafe {
+            GetDiskFreeSpaceExW(
+                PCWSTR(wide_root.as_ptr()),
+                Some(&mut free_bytes_available),
+                Some(&mut total_bytes),
+                None,
+            ).ok()?;
+        }
+        Some((free_bytes_available, total_bytes))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// Allocation unit of the volume holding `path`, in bytes.
+pub fn cluster_size(path: &Path) -> Option<u64> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceW;
+
+        let root = volume_root(path)?;
+        let wide_root: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut sectors_per_cluster = 0u32;
+        let mut bytes_per_sector = 0u32;
+        unsafe {
+            GetDiskFreeSpaceW(
+                PCWSTR(wide_root.as_ptr()),
+                Some(&mut sectors_per_cluster),
+                Some(&mut bytes_per_sector),
+                None,
+                None,
+            ).ok()?;
+        }
+        Some(sectors_per_cluster as u64 * bytes_per_sector as u64).filter(|&c| c > 0)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// Whether the volume holding `path` supports transparent per-file compression (NTFS).
+pub fn supports_compression(path: &Path) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+        let Some(root) = volume_root(path) else { return false; };
+        let wide_root: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut flags = 0u32;
+        unsafe {
+            if GetVolumeInformationW(PCWSTR(wide_root.as_ptr()), None, None, None, Some(&mut flags), None).is_err() {
+                return false;
+            }
+        }
+        // FILE_FILE_COMPRESSION
+        flags & 0x10 != 0
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Space a file actually takes, as Explorer's "Size on disk": the compressed (or sparse) size
+/// when NTFS reports one, rounded up to whole clusters. `logical` is used when that fails.
+pub fn size_on_disk(path: &Path, logical: u64, cluster: Option<u64>) -> u64 {
+    #[cfg(target_os = "windows")]
+    let allocated = {
+        use windows::Win32::Foundation::{GetLastError, SetLastError, NO_ERROR};
+        use windows::Win32::Storage::FileSystem::{GetCompressedFileSizeW, INVALID_FILE_SIZE};
+
+        let wide_path: Vec<u16> = path.to_string_lossy().encode_utf16().chain(std::iter::once(0)).collect();
+        let mut high = 0u32;
+        unsafe {
+            // INVALID_FILE_SIZE is also a valid low part, only the last error tells them apart
+            SetLastError(NO_ERROR);
+            let low = GetCompressedFileSizeW(PCWSTR(wide_path.as_ptr()), Some(&mut high));
+            if low == INVALID_FILE_SIZE && GetLastError() != NO_ERROR {
+                logical
+            } else {
+                ((high as u64) << 32) | low as u64
+            }
+        }
+    };
+    #[cfg(not(target_os = "windows"))]
+    let allocated = {
+        let _ = path;
+        logical
+    };
+
+    match cluster {
+        Some(cluster) => allocated.div_ceil(cluster) * cluster,
+        None => allocated,
+    }
+}
+
+/// Whether deleting under `path` can go to the Recycle Bin. Network, removable and optical
+/// drives have none, so the shell would delete permanently there.
+pub fn supports_recycle_bin(path: &Path) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::UI::Shell::{SHQueryRecycleBinW, SHQUERYRBINFO};
+
+        let root = match volume_root(path) {
+            Some(r) => r,
+            None => return false,
+        };
+        if root.starts_with("\\\\") {
+            return false;
+        }
+        let wide_root: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            // Only fixed drives (DRIVE_FIXED) get a Recycle Bin
+            if GetDriveTypeW(PCWSTR(wide_root.as_ptr())) != 3 {
+                return false;
+            }
+            let mut info = SHQUERYRBINFO {
+                cbSize: std::mem::size_of::<SHQUERYRBINFO>() as u32,
+                ..Default::default()
+            };
+            SHQueryRecycleBinW(PCWSTR(wide_root.as_ptr()), &mut info).is_ok()
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = path;
+        true
+    }
+}
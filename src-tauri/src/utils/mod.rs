@@ -7,6 +7,9 @@ pub mod path_security;
 pub mod archive;
 pub mod thumbnails;
 pub mod hardware;
+pub mod file_type;
+pub mod file_category;
+pub mod text_encoding;
 
 use unicode_normalization::UnicodeNormalization;
 
@@ -110,6 +113,205 @@ pub fn get_file_attributes(metadata: &Metadata, _file_name: &str) -> (bool, bool
     }
 }
 
+/// Walks everything below `root` (root excluded) for size totals. Unless `follow_links`, symlinks
+/// and junctions are not entered or counted (see `is_reparse_point`). Either way, a folder reached a second
+/// time (junction into the tree, self-referencing link) is skipped, so the walk always ends.
+pub fn walk_for_size(root: &std::path::Path, follow_links: bool) -> impl Iterator<Item = walkdir::DirEntry> {
+    let mut visited = std::collections::HashSet::new();
+    walkdir::WalkDir::new(root)
+        .follow_links(follow_links)
+        .into_iter()
+        .filter_entry(move |entry| {
+            if entry.depth() > 0 && !follow_links && is_reparse_point(entry) {
+                return false;
+            }
+            if entry.file_type().is_dir() {
+                if let Some(id) = file_id(entry.path()) {
+                    return visited.insert(id);
+                }
+            }
+            true
+        })
+        .skip(1)
+        .filter_map(|e| e.ok())
+}
+
+/// Symlink or junction, the reparse points that lead somewhere else. Other reparse points
+/// (OneDrive placeholders, deduplicated files) are ordinary files and folders to walks and sizes.
+pub fn is_reparse_point(entry: &walkdir::DirEntry) -> bool {
+    if entry.path_is_symlink() {
+        return true;
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use windows::Win32::System::SystemServices::{IO_REPARSE_TAG_MOUNT_POINT, IO_REPARSE_TAG_SYMLINK};
+        // The tag is only read for entries carrying the attribute, which are rare
+        entry.metadata().is_ok_and(|m| get_file_attributes(&m, "").2)
+            && reparse_tag(entry.path()).is_some_and(|tag| tag == IO_REPARSE_TAG_MOUNT_POINT || tag == IO_REPARSE_TAG_SYMLINK)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        false
+    }
+}
+
+/// Reparse tag of `path` itself (not of what it points to).
+#[cfg(target_os = "windows")]
+fn reparse_tag(path: &std::path::Path) -> Option<u32> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Storage::FileSystem::{
+        FileAttributeTagInfo, GetFileInformationByHandleEx, FILE_ATTRIBUTE_TAG_INFO, FILE_FLAG_BACKUP_SEMANTICS,
+        FILE_FLAG_OPEN_REPARSE_POINT,
+    };
+
+    let file = std::fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags((FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OPEN_REPARSE_POINT).0)
+        .open(path)
+        .ok()?;
+    let mut info = FILE_ATTRIBUTE_TAG_INFO::default();
+    unsafe {
+        GetFileInformationByHandleEx(
+            HANDLE(file.as_raw_handle()),
+            FileAttributeTagInfo,
+            &mut info as *mut _ as *mut _,
+            std::mem::size_of::<FILE_ATTRIBUTE_TAG_INFO>() as u32,
+        )
+    }.ok()?;
+    Some(info.ReparseTag)
+}
+
+/// Volume + file index on Windows, device + inode elsewhere: identifies a folder whatever path
+/// it was reached through.
+#[cfg(target_os = "windows")]
+fn file_id(path: &std::path::Path) -> Option<(u64, u64)> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::Storage::FileSystem::{GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS};
+
+    // Backup semantics are required to open a directory handle; no access rights are needed
+    let file = std::fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS.0)
+        .open(path)
+        .ok()?;
+    let mut info = BY_HANDLE_FILE_INFORMATION::default();
+    unsafe { GetFileInformationByHandle(HANDLE(file.as_raw_handle()), &mut info) }.ok()?;
+    Some((info.dwVolumeSerialNumber as u64, ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64))
+}
+
+#[cfg(unix)]
+fn file_id(path: &std::path::Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::metadata(path).ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+fn file_id(_path: &std::path::Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// Globs from `AppConfig::ignore_patterns` or a compression exclude list, matched case-insensitively.
+pub struct IgnoreList(Vec<glob::Pattern>);
+
+impl IgnoreList {
+    /// Compiles the patterns, silently dropping invalid ones.
+    pub fn new(patterns: &[String]) -> Self {
+        Self(patterns.iter().filter_map(|p| glob::Pattern::new(p.trim()).ok()).collect())
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let options = glob::MatchOptions { case_sensitive: false, ..Default::default() };
+        self.0.iter().any(|p| p.matches_with(name, options))
+    }
+
+    /// True when `relative` as a whole (with `/` separators) or just its last component matches,
+    /// so both `*.tmp` and `src/generated/*` work.
+    pub fn matches_path(&self, relative: &std::path::Path) -> bool {
+        relative.file_name().is_some_and(|n| self.matches(&n.to_string_lossy()))
+            || self.matches(&relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// True when any folder between `root` and `path` (or `path` itself, if a folder) is ignored.
+    pub fn excludes(&self, root: &std::path::Path, path: &std::path::Path, is_dir: bool) -> bool {
+        if self.0.is_empty() { return false; }
+        let relative = match path.strip_prefix(root) {
+            Ok(r) => r,
+            Err(_) => return false,
+        };
+        let folders = if is_dir { Some(relative) } else { relative.parent() };
+        folders.is_some_and(|f| f.components().any(|c| self.matches(&c.as_os_str().to_string_lossy())))
+    }
+}
+
+/// Free path for a copy of `path` in the same folder, Explorer style:
+/// `name - Copy.ext`, then `name - Copy (2).ext`, ... Folders keep any dots in their name.
+pub fn duplicate_path(path: &std::path::Path) -> std::path::PathBuf {
+    let parent = path.parent().unwrap_or(std::path::Path::new(""));
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let (stem, ext) = match name.rfind('.') {
+        Some(i) if i > 0 && !path.is_dir() => (name[..i].to_string(), name[i..].to_string()),
+        _ => (name.clone(), String::new()),
+    };
+
+    (1..)
+        .map(|n| match n {
+            1 => parent.join(format!("{} - Copy{}", stem, ext)),
+            n => parent.join(format!("{} - Copy ({}){}", stem, n, ext)),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Draws an ASCII tree like `tree /F`. `entries` are `(depth, name)` in walk order, depth 1
+/// being the direct children of `root_name`.
+pub fn render_tree(root_name: &str, entries: &[(usize, String)]) -> String {
+    // An entry is the last of its siblings when no later entry at its depth comes before
+    // the walk climbs back above it
+    let mut is_last = vec![false; entries.len()];
+    let mut seen_at_depth: Vec<bool> = Vec::new();
+    for (i, (depth, _)) in entries.iter().enumerate().rev() {
+        seen_at_depth.resize(seen_at_depth.len().max(depth + 1), false);
+        is_last[i] = !seen_at_depth[*depth];
+        seen_at_depth[*depth] = true;
+        seen_at_depth.truncate(depth + 1);
+    }
+
+    let mut out = String::from(root_name);
+    let mut open_levels: Vec<bool> = Vec::new();
+    for ((depth, name), last) in entries.iter().zip(is_last) {
+        open_levels.truncate(depth.saturating_sub(1));
+        out.push('\n');
+        for open in &open_levels {
+            out.push_str(if *open { "│   " } else { "    " });
+        }
+        out.push_str(if last { "└── " } else { "├── " });
+        out.push_str(name);
+        open_levels.push(!last);
+    }
+    out
+}
+
+/// Target of an Internet shortcut (`.url`): the `URL=` key of its `[InternetShortcut]` section.
+pub fn parse_url_shortcut(content: &str) -> Option<String> {
+    let mut in_section = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_section = line.eq_ignore_ascii_case("[InternetShortcut]");
+        } else if in_section {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim().eq_ignore_ascii_case("URL") && !value.trim().is_empty() {
+                    return Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,4 +331,55 @@ mod tests {
         assert!(!hidden);
         assert!(!system);
     }
+
+    #[test]
+    fn test_ignore_list() {
+        let ignore = IgnoreList::new(&["node_modules".to_string(), "$Recycle.Bin".to_string(), "*.tmp".to_string(), "[".to_string()]);
+        assert!(ignore.matches("node_modules"));
+        assert!(ignore.matches("$RECYCLE.BIN"));
+        assert!(ignore.matches("build.tmp"));
+        assert!(!ignore.matches("src"));
+
+        let root = std::path::Path::new("/data");
+        assert!(ignore.excludes(root, std::path::Path::new("/data/app/node_modules/pkg/index.js"), false));
+        assert!(ignore.excludes(root, std::path::Path::new("/data/app/node_modules"), true));
+        assert!(!ignore.excludes(root, std::path::Path::new("/data/app/cache.tmp"), false));
+        assert!(!ignore.excludes(root, std::path::Path::new("/data/app/src/main.rs"), false));
+
+        let exclude = IgnoreList::new(&["*.tmp".to_string(), ".git".to_string(), "app/dist/*".to_string()]);
+        assert!(exclude.matches_path(std::path::Path::new("app/cache.TMP")));
+        assert!(exclude.matches_path(std::path::Path::new("app/.git")));
+        assert!(exclude.matches_path(std::path::Path::new("app/dist/bundle.js")));
+        assert!(!exclude.matches_path(std::path::Path::new("app/src/main.rs")));
+    }
+
+    #[test]
+    fn test_duplicate_path() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("report.final.txt");
+        fs::write(&file_path, "x").unwrap();
+        assert_eq!(duplicate_path(&file_path), dir.path().join("report.final - Copy.txt"));
+
+        fs::write(dir.path().join("report.final - Copy.txt"), "x").unwrap();
+        assert_eq!(duplicate_path(&file_path), dir.path().join("report.final - Copy (2).txt"));
+
+        let folder = dir.path().join("v1.2");
+        fs::create_dir(&folder).unwrap();
+        assert_eq!(duplicate_path(&folder), dir.path().join("v1.2 - Copy"));
+    }
+
+    #[test]
+    fn test_parse_url_shortcut() {
+        let content = "\u{feff}[{000214A0-0000-0000-C000-000000000046}]\r\nProp3=19,11\r\n[InternetShortcut]\r\nIDList=\r\nurl=https://example.com/?a=b\r\nIconIndex=0\r\n";
+        assert_eq!(parse_url_shortcut(content).as_deref(), Some("https://example.com/?a=b"));
+        assert_eq!(parse_url_shortcut("[Other]\nURL=https://example.com"), None);
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let entries = [(1, "src"), (2, "main.rs"), (2, "utils"), (3, "mod.rs"), (1, "Cargo.toml")]
+            .map(|(d, n)| (d, n.to_string()));
+        let expected = "crate\n├── src\n│   ├── main.rs\n│   └── utils\n│       └── mod.rs\n└── Cargo.toml";
+        assert_eq!(render_tree("crate", &entries), expected);
+    }
 }
@@ -10,8 +10,9 @@ pub fn validate_path(path_str: &str) -> Result<PathBuf, CommandError> {
         return Err(CommandError::PathError(format!("Path must be absolute: {}", path_str)));
     }
 
+    // Trailing dots and spaces are real name characters behind the extended-length prefix
     #[cfg(target_os = "windows")]
-    {
+    if !path_str.starts_with(EXTENDED_PREFIX) {
         let mut needs_update = None;
         if let Some(file_name) = path.file_name() {
             let name_str = file_name.to_string_lossy();
@@ -28,6 +29,79 @@ pub fn validate_path(path_str: &str) -> Result<PathBuf, CommandError> {
     Ok(path)
 }
 
+/// Turns a bare drive ("C:") into its root ("C:\\"); "C:" alone means the drive's current
+/// directory on Windows. Other paths are returned unchanged.
+pub fn normalize_drive_root(path_str: &str) -> String {
+    let mut chars = path_str.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(letter), Some(':'), None) if letter.is_ascii_alphabetic() => format!("{}\\", path_str),
+        _ => path_str.to_string(),
+    }
+}
+
+const EXTENDED_PREFIX: &str = "\\\\?\\";
+const EXTENDED_UNC_PREFIX: &str = "\\\\?\\UNC\\";
+
+/// Length (in UTF-16 units) from which paths get the extended-length prefix: MAX_PATH minus
+/// the room CreateDirectoryW keeps for an 8.3 file name.
+pub const LONG_PATH_THRESHOLD: usize = 248;
+
+/// `path_str` with the extended-length prefix: `\\?\C:\...`, or `\\?\UNC\server\share\...` for
+/// network paths. Windows skips all normalization on such paths, so separators are converted
+/// here and paths that are relative, already prefixed or contain `.`/`..` give `None`.
+pub fn to_extended_length(path_str: &str) -> Option<String> {
+    if path_str.starts_with(EXTENDED_PREFIX) {
+        return None;
+    }
+    let normalized = path_str.replace('/', "\\");
+    if normalized.split('\\').any(|part| part == "." || part == "..") {
+        return None;
+    }
+    if let Some(rest) = normalized.strip_prefix("\\\\") {
+        return (!rest.is_empty()).then(|| format!("{}{}", EXTENDED_UNC_PREFIX, rest));
+    }
+    let bytes = normalized.as_bytes();
+    if bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\' {
+        Some(format!("{}{}", EXTENDED_PREFIX, normalized))
+    } else {
+        None
+    }
+}
+
+/// Inverse of `to_extended_length`, for display and for APIs that don't accept the prefix.
+pub fn strip_extended_length(path_str: &str) -> String {
+    if let Some(rest) = path_str.strip_prefix(EXTENDED_UNC_PREFIX) {
+        format!("\\\\{}", rest)
+    } else {
+        path_str.strip_prefix(EXTENDED_PREFIX).unwrap_or(path_str).to_string()
+    }
+}
+
+/// `path` prefixed for Win32 file APIs once it is too long for them (see
+/// `LONG_PATH_THRESHOLD`); unchanged on other platforms. Only for std::fs and Win32 file
+/// calls: shell APIs such as `SHFileOperationW` reject prefixed paths.
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    if let Some(path_str) = path.to_str() {
+        if path_str.encode_utf16().count() >= LONG_PATH_THRESHOLD {
+            if let Some(extended) = to_extended_length(path_str) {
+                return PathBuf::from(extended);
+            }
+        }
+    }
+    path.to_path_buf()
+}
+
+/// `path` prefixed regardless of its length, for walk roots whose descendants may grow past
+/// the limit: entries yielded under a prefixed root keep the prefix.
+pub fn extended_path(path: &Path) -> PathBuf {
+    #[cfg(target_os = "windows")]
+    if let Some(extended) = path.to_str().and_then(to_extended_length) {
+        return PathBuf::from(extended);
+    }
+    path.to_path_buf()
+}
+
 /// Safe join that prevents directory traversal attacks when joining a user input to a base directory.
 /// Useful if we ever restrict operations to a specific sandbox (not currently the case for full FM).
 pub fn safe_join(base: &Path, input: &str) -> Result<PathBuf, CommandError> {
@@ -73,8 +147,34 @@ mod tests {
         assert!(validate_path("C:\\foo\\..\\bar").is_ok());
     }
 
+    #[test]
+    fn test_normalize_drive_root() {
+        assert_eq!(normalize_drive_root("C:"), "C:\\");
+        assert_eq!(normalize_drive_root("d:"), "d:\\");
+        assert_eq!(normalize_drive_root("C:\\"), "C:\\");
+        assert_eq!(normalize_drive_root("C:\\Users"), "C:\\Users");
+        assert_eq!(normalize_drive_root("1:"), "1:");
+    }
+
     #[test]
     fn test_validate_path_unix_absolute() {
         assert!(validate_path("C:/Users").is_ok());
     }
+
+    #[test]
+    fn test_extended_length_prefix() {
+        assert_eq!(to_extended_length("C:\\deep\\file.txt").as_deref(), Some("\\\\?\\C:\\deep\\file.txt"));
+        assert_eq!(to_extended_length("D:/Data/Files").as_deref(), Some("\\\\?\\D:\\Data\\Files"));
+        assert_eq!(to_extended_length("\\\\server\\share\\dir").as_deref(), Some("\\\\?\\UNC\\server\\share\\dir"));
+        assert_eq!(to_extended_length("\\\\?\\C:\\already"), None);
+        assert_eq!(to_extended_length("C:\\dir\\..\\file"), None);
+        assert_eq!(to_extended_length("relative\\path"), None);
+    }
+
+    #[test]
+    fn test_strip_extended_length() {
+        assert_eq!(strip_extended_length("\\\\?\\C:\\deep"), "C:\\deep");
+        assert_eq!(strip_extended_length("\\\\?\\UNC\\server\\share"), "\\\\server\\share");
+        assert_eq!(strip_extended_length("C:\\plain"), "C:\\plain");
+    }
 }
@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+/// Bytes from the start of a file looked at to guess its encoding.
+pub const SNIFF_LEN: usize = 1024;
+
+/// Encoding of a text from its first bytes, with the length of its BOM. A BOM wins; otherwise
+/// mostly-ASCII UTF-16 (as written by Notepad or regedit) shows as NUL in every other byte.
+/// `None` means no BOM and nothing UTF-16-like: treat it as UTF-8.
+pub fn detect(head: &[u8]) -> Option<(&'static Encoding, usize)> {
+    if let Some(found) = Encoding::for_bom(head) {
+        return Some(found);
+    }
+    let pairs = head.len() / 2;
+    if pairs < 2 {
+        return None;
+    }
+    let even_nuls = head.iter().step_by(2).take(pairs).filter(|&&b| b == 0).count();
+    let odd_nuls = head.iter().skip(1).step_by(2).take(pairs).filter(|&&b| b == 0).count();
+    // Real UTF-16 text has NUL high bytes for ASCII and (almost) none in the low bytes
+    if odd_nuls * 10 >= pairs * 9 && even_nuls * 10 <= pairs {
+        Some((UTF_16LE, 0))
+    } else if even_nuls * 10 >= pairs * 9 && odd_nuls * 10 <= pairs {
+        Some((UTF_16BE, 0))
+    } else {
+        None
+    }
+}
+
+/// Whether `head` starts a UTF-16 text, whose NUL bytes don't make it binary.
+pub fn is_utf16(head: &[u8]) -> bool {
+    detect(head).is_some_and(|(encoding, _)| encoding == UTF_16LE || encoding == UTF_16BE)
+}
+
+/// Decodes a whole text file, dropping its BOM. Malformed sequences become U+FFFD.
+pub fn decode(bytes: &[u8]) -> Cow<'_, str> {
+    let (encoding, bom_len) = detect(bytes).unwrap_or((UTF_8, 0));
+    encoding.decode_without_bom_handling(&bytes[bom_len..]).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str, bom: bool) -> Vec<u8> {
+        let mut bytes = if bom { vec![0xFF, 0xFE] } else { Vec::new() };
+        bytes.extend(text.encode_utf16().flat_map(|u| u.to_le_bytes()));
+        bytes
+    }
+
+    #[test]
+    fn test_detect_utf16le() {
+        let with_bom = utf16le("Windows Registry Editor Version 5.00\r\n", true);
+        assert_eq!(detect(&with_bom), Some((UTF_16LE, 2)));
+        assert!(is_utf16(&with_bom));
+
+        let without_bom = utf16le("plain notepad text", false);
+        assert_eq!(detect(&without_bom), Some((UTF_16LE, 0)));
+        assert!(is_utf16(&without_bom));
+    }
+
+    #[test]
+    fn test_detect_utf8() {
+        assert_eq!(detect(b"\xEF\xBB\xBFhello"), Some((UTF_8, 3)));
+        assert_eq!(detect(b"hello world"), None);
+        assert!(!is_utf16(b"hello world"));
+        assert!(!is_utf16(b"\x00\x01\x02\x03\x00\x00\x00\x00"));
+    }
+
+    #[test]
+    fn test_decode() {
+        let text = "Clé=\"Valeur\"\r\nsecond line";
+        assert_eq!(decode(&utf16le(text, true)), text);
+        assert_eq!(decode(&utf16le(text, false)), text);
+        assert_eq!(decode(b"\xEF\xBB\xBFcaf\xC3\xA9"), "café");
+    }
+}
@@ -180,6 +180,253 @@ pub fn get_office_thumbnail_cached(
     Ok(cache_file.to_string_lossy().to_string())
 }
 
+/// Image extensions considered for a folder preview; anything else in the folder is ignored.
+const PREVIEW_IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff"];
+
+/// Upper bound on the images composited into a folder preview, whatever the caller asks for.
+pub const MAX_PREVIEW_IMAGES: usize = 9;
+
+/// Edge length of the square folder preview montage.
+const PREVIEW_SIZE: u32 = 256;
+
+/// JPEG montage of the first `max_images` images of a folder (by name), laid out on a square grid.
+/// Tiles come from the per-image thumbnail cache; the montage itself is cached on the folder path and
+/// modification time, so adding or removing files rebuilds it.
+pub fn get_folder_preview_cached(
+    path: String,
+    max_images: usize,
+    cache_dir: PathBuf,
+) -> Result<Vec<u8>, CommandError> {
+    let folder = Path::new(&path);
+    if !folder.is_dir() {
+        return Err(CommandError::PathError(path));
+    }
+    let max_images = max_images.clamp(1, MAX_PREVIEW_IMAGES);
+
+    let metadata = fs::metadata(folder).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let duration = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+
+    let hash_input = format!("{}_{}_{}_folder", path, duration.as_secs(), max_images);
+    let hash = hex::encode(hash_input);
+    let cache_file = cache_dir.join(format!("{}.jpg", hash));
+
+    if !cache_dir.exists() {
+        fs::create_dir_all(&cache_dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+    }
+
+    if let Ok(bytes) = fs::read(&cache_file) {
+        return Ok(bytes);
+    }
+
+    // Only names are read here, so huge folders stay cheap; decoding is limited to what is kept
+    let mut images: Vec<PathBuf> = fs::read_dir(folder)
+        .map_err(|e| CommandError::IoError(e.to_string()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| is_preview_image(p))
+        .collect();
+    images.sort_by_key(|p| p.file_name().map(|n| n.to_string_lossy().to_lowercase()));
+
+    let mut tiles = Vec::with_capacity(max_images);
+    for image_path in images {
+        if tiles.len() == max_images {
+            break;
+        }
+        // Files that don't decode are skipped rather than failing the whole preview
+        let thumbnail = get_thumbnail_cached(image_path.to_string_lossy().to_string(), cache_dir.clone())
+            .and_then(|thumb| image::open(thumb).map_err(|e| CommandError::Other(e.to_string())));
+        match thumbnail {
+            Ok(img) => tiles.push(img),
+            Err(e) => log::debug!("Skipping {:?} in folder preview: {}", image_path, e),
+        }
+    }
+    if tiles.is_empty() {
+        return Err(CommandError::Other("No images in folder".to_string()));
+    }
+
+    let columns = (tiles.len() as f64).sqrt().ceil() as u32;
+    let tile_size = PREVIEW_SIZE / columns;
+    let mut montage = image::RgbImage::new(PREVIEW_SIZE, PREVIEW_SIZE);
+    for (i, tile) in tiles.iter().enumerate() {
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        let cell = tile.resize_to_fill(tile_size, tile_size, FilterType::Triangle).to_rgb8();
+        image::imageops::replace(&mut montage, &cell, (column * tile_size) as i64, (row * tile_size) as i64);
+    }
+
+    let mut bytes = Vec::new();
+    JpegEncoder::new_with_quality(&mut bytes, 80)
+        .encode_image(&montage)
+        .map_err(|e| CommandError::Other(format!("Failed to encode folder preview: {}", e)))?;
+    fs::write(&cache_file, &bytes).map_err(|e| CommandError::IoError(e.to_string()))?;
+
+    Ok(bytes)
+}
+
+/// Whether `path` is an existing file in one of the formats the image crate decodes.
+pub fn is_preview_image(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .is_some_and(|e| PREVIEW_IMAGE_EXTENSIONS.contains(&e.as_str()))
+        && path.is_file()
+}
+
+/// Thumbnail from the shell's own thumbnail handlers (PDF, video, fonts, 3D models...), as Explorer
+/// shows it. Falls back to the file's icon when no handler produces a thumbnail.
+pub fn get_shell_thumbnail_cached(
+    path: String,
+    size: u32,
+    cache_dir: PathBuf,
+) -> Result<String, CommandError> {
+    let source_path = Path::new(&path);
+    if !source_path.exists() {
+        return Err(CommandError::PathError(path.clone()));
+    }
+    let size = size.clamp(16, 1024);
+
+    let metadata = fs::metadata(source_path).map_err(|e| CommandError::IoError(e.to_string()))?;
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let duration = modified.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default();
+
+    let hash_input = format!("{}_{}_{}_{}_shell", path, metadata.len(), duration.as_secs(), size);
+    let hash = hex::encode(hash_input);
+
+    // PNG rather than JPEG: shell thumbnails and icons carry transparency
+    let cache_file = cache_dir.join(format!("{}.png", hash));
+
+    if !cache_dir.exists() {
+        fs::create_dir_all(&cache_dir).map_err(|e| CommandError::IoError(e.to_string()))?;
+    }
+
+    if cache_file.exists() {
+        return Ok(cache_file.to_string_lossy().to_string());
+    }
+
+    THUMB_LIMITER.acquire();
+    let result = (|| -> Result<(), CommandError> {
+        match shell_thumbnail(source_path, size) {
+            Ok(thumbnail) => thumbnail
+                .save_with_format(&cache_file, image::ImageFormat::Png)
+                .map_err(|e| CommandError::Other(format!("Failed to save thumbnail: {}", e))),
+            Err(e) => {
+                log::debug!("No shell thumbnail for {}: {}", path, e);
+                let png = shell_icon_fallback(&path, size).map_err(CommandError::Other)?;
+                fs::write(&cache_file, png).map_err(|e| CommandError::IoError(e.to_string()))
+            }
+        }
+    })();
+    THUMB_LIMITER.release();
+    result?;
+
+    Ok(cache_file.to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn shell_thumbnail(path: &Path, size: u32) -> Result<image::RgbaImage, String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::SIZE;
+    use windows::Win32::Graphics::Gdi::{
+        DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO, BITMAPINFOHEADER,
+        BI_RGB, DIB_RGB_COLORS, HGDIOBJ,
+    };
+    use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, IBindCtx, COINIT_APARTMENTTHREADED};
+    use windows::Win32::UI::Shell::{
+        IShellItemImageFactory, SHCreateItemFromParsingName, SIIGBF, SIIGBF_BIGGERSIZEOK, SIIGBF_THUMBNAILONLY,
+    };
+
+    let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        let com_initialized = CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_ok();
+
+        let result = (|| -> Result<image::RgbaImage, String> {
+            let factory: IShellItemImageFactory = SHCreateItemFromParsingName(PCWSTR(wide_path.as_ptr()), None::<&IBindCtx>)
+                .map_err(|e| e.to_string())?;
+            // THUMBNAILONLY: fail instead of handing back the icon, so the caller can pick its own
+            let flags = SIIGBF(SIIGBF_THUMBNAILONLY.0 | SIIGBF_BIGGERSIZEOK.0);
+            let hbitmap = factory
+                .GetImage(SIZE { cx: size as i32, cy: size as i32 }, flags)
+                .map_err(|e| e.to_string())?;
+
+            let mut bitmap: BITMAP = std::mem::zeroed();
+            if GetObjectW(HGDIOBJ(hbitmap.0), std::mem::size_of::<BITMAP>() as i32, Some(&mut bitmap as *mut _ as *mut _)) == 0
+                || bitmap.bmWidth <= 0
+                || bitmap.bmHeight == 0
+            {
+                let _ = DeleteObject(HGDIOBJ(hbitmap.0));
+                return Err("Could not read thumbnail size".to_string());
+            }
+            let (width, height) = (bitmap.bmWidth, bitmap.bmHeight.abs());
+
+            let mut bi = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    biHeight: -height, // Top-down
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let mut pixels = vec![0u32; (width * height) as usize];
+
+            let dc = GetDC(None);
+            let lines = GetDIBits(
+                dc,
+                hbitmap,
+                0,
+                height as u32,
+                Some(pixels.as_mut_ptr() as *mut _),
+                &mut bi,
+                DIB_RGB_COLORS,
+            );
+            let _ = ReleaseDC(None, dc);
+            let _ = DeleteObject(HGDIOBJ(hbitmap.0));
+            if lines == 0 {
+                return Err("GetDIBits failed".to_string());
+            }
+
+            // Opaque thumbnails (photos, video frames) often come back with a zero alpha channel
+            let has_alpha = pixels.iter().any(|p| (p >> 24) != 0);
+            let mut rgba = Vec::with_capacity(pixels.len() * 4);
+            for pixel in pixels {
+                rgba.push(((pixel >> 16) & 0xFF) as u8); // R
+                rgba.push(((pixel >> 8) & 0xFF) as u8);  // G
+                rgba.push((pixel & 0xFF) as u8);         // B
+                rgba.push(if has_alpha { ((pixel >> 24) & 0xFF) as u8 } else { 0xFF }); // A
+            }
+
+            image::RgbaImage::from_raw(width as u32, height as u32, rgba)
+                .ok_or_else(|| "Failed to create RgbaImage".to_string())
+        })();
+
+        if com_initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_thumbnail(_path: &Path, _size: u32) -> Result<image::RgbaImage, String> {
+    Err("Shell thumbnails are only available on Windows".to_string())
+}
+
+/// PNG of the file's icon, used when the shell has no thumbnail for it.
+#[cfg(target_os = "windows")]
+fn shell_icon_fallback(path: &str, size: u32) -> Result<Vec<u8>, String> {
+    use crate::commands::icons::{file_icon_png, IconSize};
+    file_icon_png(path, IconSize::for_pixels(size), false)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_icon_fallback(_path: &str, _size: u32) -> Result<Vec<u8>, String> {
+    Err("File icons are only available on Windows".to_string())
+}
+
 pub fn get_office_text_preview(
     path: String,
 ) -> Result<String, CommandError> {
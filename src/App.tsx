@@ -68,7 +68,7 @@ function App() {
   const { tabs, activeTabId, setActiveTab, updateTabPath, addTab, closeTab } = useTabs();
   const dialogs = useDialogs();
   const { left, right, activePanelId, setActivePanelId } = usePanelContext();
-  const fileOps = useFileOperations(notify, t as any);
+  const fileOps = useFileOperations(notify, t as any, dialogs.confirm);
   const { favorites } = useFavorites();
 
   const [contextMenu, setContextMenu] = useState<{
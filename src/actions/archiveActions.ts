@@ -50,9 +50,7 @@ export const EXTRACT_TO_FOLDER_ACTION: ActionDefinition = {
         if (!target) return;
 
         const archivePath = target;
-        let targetDir = getParent(archivePath);
-        const folderName = archivePath.split('\\').pop()?.split('/').pop()?.split('.').shift() || "extracted";
-        targetDir = `${targetDir}\\${folderName}`;
+        const targetDir = getParent(archivePath);
 
         if (ctx.setProgress) {
             ctx.setProgress({ visible: true, message: ctx.t('calculating'), cancellable: true });
@@ -61,7 +59,8 @@ export const EXTRACT_TO_FOLDER_ACTION: ActionDefinition = {
         }
 
         try {
-            await invoke('extract_archive', { archivePath, targetDir });
+            // The backend names the folder after the archive (e.g. "backup" for backup.tar.gz)
+            await invoke('extract_archive', { archivePath, targetDir, extractMode: 'subfolder' });
             ctx.notify(ctx.t('item_restored'), 'success');
             if (typeof ctx.activePanel.refresh === 'function') ctx.activePanel.refresh();
             if (ctx.otherPanel && typeof ctx.otherPanel.refresh === 'function') ctx.otherPanel.refresh();
@@ -122,7 +121,7 @@ const compress = async (ctx: ActionContext, format: 'zip' | '7z' | 'tar' | 'zst'
     }
 
     try {
-        await invoke('compress_to_archive', { paths: selection, archivePath, format, quality });
+        await invoke('compress_to_archive', { paths: selection, archivePath, format, quality, exclude: [] });
         ctx.notify(ctx.t('item_created') || "Archive created", 'success');
         if (typeof ctx.activePanel.refresh === 'function') ctx.activePanel.refresh();
         if (ctx.otherPanel && typeof ctx.otherPanel.refresh === 'function') ctx.otherPanel.refresh();
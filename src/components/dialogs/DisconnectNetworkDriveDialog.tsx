@@ -53,7 +53,7 @@ export const DisconnectNetworkDriveDialog: React.FC<DisconnectNetworkDriveDialog
             for (const path of selectedLetters) {
                 // path is usually "Z:\" or "\\server\share". The WNet API uses letter like "Z:" or full path.
                 const cleanLetter = path.replace(/[\\/]+$/, '');
-                await invoke('disconnect_network_drive', { letter: cleanLetter, force: true });
+                await invoke('disconnect_network_drive', { driveLetter: cleanLetter, force: true });
             }
             notify(t('disconnect_network_drive_success' as any), 'success');
             onClose();
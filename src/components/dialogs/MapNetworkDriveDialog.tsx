@@ -31,7 +31,7 @@ export const MapNetworkDriveDialog: React.FC<MapNetworkDriveDialogProps> = ({ on
 
         setLoading(true);
         try {
-            await invoke('map_network_drive', { letter, path, reconnect });
+            await invoke('map_network_drive', { uncPath: path, driveLetter: letter, persistent: reconnect });
             notify(t('map_network_drive_success' as any), 'success');
             onClose();
         } catch (e: any) {
@@ -5,6 +5,7 @@ import { AppContextValue } from '../context/AppContext';
 import { actionService } from '../services/ActionService';
 import { formatCommandError } from '../utils/error';
 import { getParent } from '../utils/path';
+import { splitArchivePath } from '../utils/archive';
 import { DirectoryTreeHandle } from '../components/ui/DirectoryTree';
 import { invoke } from '@tauri-apps/api/core';
 
@@ -269,7 +270,12 @@ export const useAppHandlers = ({
         }
 
         try {
-            await invoke('open_item', { path });
+            const inArchive = splitArchivePath(path);
+            if (inArchive) {
+                await invoke('open_archive_entry', inArchive);
+            } else {
+                await invoke('open_item', { path });
+            }
         } catch (e) {
             notify(`${t('error')}: ${formatCommandError(e)}`, 'error');
         }
@@ -423,7 +429,7 @@ export const useAppHandlers = ({
     const handleDisconnectDrive = useCallback(async (path: string) => {
         const cleanLetter = path.replace(/[\\/]+$/, '');
         try {
-            await invoke('disconnect_network_drive', { letter: cleanLetter, force: false });
+            await invoke('disconnect_network_drive', { driveLetter: cleanLetter, force: false });
             notify(t('disconnect_network_drive_success' as any), 'success');
             refreshDrives();
         } catch (e: any) {
@@ -438,7 +444,7 @@ export const useAppHandlers = ({
 
                 if (confirmed) {
                     try {
-                        await invoke('disconnect_network_drive', { letter: cleanLetter, force: true });
+                        await invoke('disconnect_network_drive', { driveLetter: cleanLetter, force: true });
                         notify(t('disconnect_network_drive_success' as any), 'success');
                         refreshDrives();
                     } catch (err) {
@@ -2,8 +2,9 @@ import { useState, useCallback, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { ConflictEntry, ConflictAction, HistoryState, FileOperation, Transaction, ConflictResponse } from '../types';
+import { CommandError } from '../utils/error';
 
-export const useFileOperations = (notify?: (message: string, type: 'error' | 'success' | 'info' | 'warning', duration?: number) => void, t?: any) => {
+export const useFileOperations = (notify?: (message: string, type: 'error' | 'success' | 'info' | 'warning', duration?: number) => void, t?: any, confirm?: (message: string, title?: string, isDanger?: boolean, confirmLabel?: string, sources?: string[]) => Promise<boolean>) => {
     // Stable refs for notify/t to avoid re-subscribing the event listener on every render
     const notifyRef = useRef(notify);
     const tRef = useRef(t);
@@ -134,8 +135,19 @@ export const useFileOperations = (notify?: (message: string, type: 'error' | 'su
                     total_files: initialEstimates?.total_files,
                     is_cross_volume: initialEstimates?.is_cross_volume
                 });
+            } else if (action === 'trash') {
+                try {
+                    opId = await invoke<string>('delete_items', { paths, turbo });
+                } catch (e) {
+                    // Removable and network drives have no Recycle Bin: offer a permanent delete instead
+                    const noTrash = (e as CommandError | null)?.RecycleBinUnavailable;
+                    if (!noTrash || !confirm || !t) throw e;
+                    const confirmed = await confirm(t('no_recycle_bin_confirm'), t('confirm_delete_title'), true, undefined, noTrash.split('\n'));
+                    if (!confirmed) return;
+                    opId = await invoke<string>('delete_items', { paths, turbo, allowPermanent: true });
+                }
             } else {
-                opId = await invoke<string>(action === 'trash' ? 'delete_items' : 'purge_items', { paths, turbo });
+                opId = await invoke<string>('purge_items', { paths, turbo });
             }
 
             // Add to active ops immediately with initial state (queued)
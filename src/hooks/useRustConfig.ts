@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 
 export interface AppConfig {
     theme: string;
@@ -40,6 +41,16 @@ export const useRustConfig = () => {
         refreshConfig();
     }, [refreshConfig]);
 
+    // Backend emits after every saved change (other windows, imports, resets)
+    useEffect(() => {
+        const unlistenPromise = listen<AppConfig>('config_changed', (event) => {
+            setConfig(event.payload);
+        });
+        return () => {
+            unlistenPromise.then(unlisten => unlisten());
+        };
+    }, []);
+
     const setConfigValue = useCallback(async (key: keyof AppConfig, value: any) => {
         try {
             // Frontend keeps the correct type (boolean/number) for immediate UI update
@@ -7,6 +7,8 @@ export interface Tab {
     id: string;
     path: string;
     version: number;
+    color?: string | null;
+    pinned?: boolean;
 }
 
 export interface PanelState {
@@ -18,6 +20,7 @@ export interface SessionState {
     left_panel: PanelState;
     right_panel: PanelState;
     active_panel: string;
+    sync_navigation?: boolean;
 }
 
 export const useRustSession = () => {
@@ -28,6 +28,24 @@ export const translations = {
         sort_asc: 'Ascending',
         sort_desc: 'Descending',
         extension: 'Extension',
+        // Group headers (list_dir groupBy)
+        group_folders: 'Folders',
+        group_no_extension: 'No extension',
+        group_extension: '{ext} files',
+        group_today: 'Today',
+        group_yesterday: 'Yesterday',
+        group_this_week: 'This week',
+        group_last_week: 'Last week',
+        group_this_month: 'This month',
+        group_this_year: 'This year',
+        group_older: 'Older',
+        group_size_empty: 'Empty',
+        group_size_tiny: 'Tiny',
+        group_size_small: 'Small',
+        group_size_medium: 'Medium',
+        group_size_large: 'Large',
+        group_size_huge: 'Huge',
+        group_size_gigantic: 'Gigantic',
         view: 'View',
         grid: 'Grid',
         list: 'List',
@@ -297,6 +315,7 @@ export const translations = {
         confirm_delete_title: 'Confirm Delete',
         perm_delete_confirm_single: 'Permanently delete this item?',
         perm_delete_confirm_multiple: 'Permanently delete {count} items?',
+        no_recycle_bin_confirm: 'These items can\'t be moved to the Recycle Bin. Delete them permanently?',
         file_operation: 'File Operation',
         time_remaining: 'Time remaining',
         completed: 'Completed',
@@ -324,6 +343,19 @@ export const translations = {
         moved_to_recycle_bin_plural: 'moved to Recycle Bin',
         undo_action: 'Undo:',
         redo_action: 'Redo:',
+        // History summaries (Transaction.summary)
+        history_items: '{count} items',
+        history_duplicated: 'Duplicated {items}',
+        history_copied: 'Copied {items} to {target}',
+        history_moved: 'Moved {items} to {target}',
+        history_grouped: 'Grouped {items} into {target}',
+        history_renamed: 'Renamed {items} to {target}',
+        history_deleted: 'Moved {items} to the Recycle Bin',
+        history_new_folder: 'Created folder {items}',
+        history_new_file: 'Created {items}',
+        history_edited: 'Edited {items}',
+        history_restored: 'Restored {items}',
+        history_restored_to: 'Restored {items} to {target}',
         calculate_size: 'Calculate folders size',
         calculating: 'Calculating...',
         empty_dir: 'Empty',
@@ -450,6 +482,24 @@ export const translations = {
         sort_asc: 'Croissant',
         sort_desc: 'Décroissant',
         extension: 'Extension',
+        // Group headers (list_dir groupBy)
+        group_folders: 'Dossiers',
+        group_no_extension: 'Sans extension',
+        group_extension: 'Fichiers {ext}',
+        group_today: 'Aujourd\'hui',
+        group_yesterday: 'Hier',
+        group_this_week: 'Cette semaine',
+        group_last_week: 'La semaine dernière',
+        group_this_month: 'Ce mois-ci',
+        group_this_year: 'Cette année',
+        group_older: 'Plus ancien',
+        group_size_empty: 'Vide',
+        group_size_tiny: 'Minuscule',
+        group_size_small: 'Petit',
+        group_size_medium: 'Moyen',
+        group_size_large: 'Grand',
+        group_size_huge: 'Très grand',
+        group_size_gigantic: 'Gigantesque',
         view: 'Affichage',
         grid: 'Grille',
         list: 'Liste',
@@ -682,6 +732,7 @@ export const translations = {
         confirm_delete_title: 'Confirmer la suppression',
         perm_delete_confirm_single: 'Supprimer définitivement cet élément ?',
         perm_delete_confirm_multiple: 'Supprimer définitivement {count} éléments ?',
+        no_recycle_bin_confirm: 'Ces éléments ne peuvent pas être placés dans la corbeille. Les supprimer définitivement ?',
         file_operation: 'Opération de fichier',
         time_remaining: 'Temps restant',
         completed: 'Terminé',
@@ -709,6 +760,19 @@ export const translations = {
         moved_to_recycle_bin_plural: 'déplacés vers la corbeille',
         undo_action: 'Annulation :',
         redo_action: 'Rétablissement :',
+        // History summaries (Transaction.summary)
+        history_items: '{count} éléments',
+        history_duplicated: '{items} dupliqué',
+        history_copied: '{items} copié vers {target}',
+        history_moved: '{items} déplacé vers {target}',
+        history_grouped: '{items} regroupé dans {target}',
+        history_renamed: '{items} renommé en {target}',
+        history_deleted: '{items} placé dans la corbeille',
+        history_new_folder: 'Dossier {items} créé',
+        history_new_file: '{items} créé',
+        history_edited: '{items} modifié',
+        history_restored: '{items} restauré',
+        history_restored_to: '{items} restauré vers {target}',
         calculate_size: 'Calculer la taille des dossiers',
         calculating: 'Calcul en cours...',
         empty_dir: 'Vide',
@@ -1,3 +1,6 @@
+// Group header from list_dir: a translation key for fixed buckets, or the extension itself
+export type GroupKey = { label: string } | { extension: string };
+
 export type FileEntry = {
     name: string;
     path: string;
@@ -15,6 +18,12 @@ export type FileEntry = {
     // Trash-specific fields (populated when viewing Recycle Bin)
     original_path?: string;
     deleted_time?: number;
+    // Set when the listing was requested with groupBy
+    group_key?: GroupKey;
+    // Starred with toggle_favorite
+    is_favorite?: boolean;
+    match_context?: string;
+    match_line?: number;
     // Network-specific fields
     is_media_device?: boolean;
     has_web_page?: boolean;
@@ -70,9 +79,12 @@ export interface ClipboardState {
 export type SortField = 'name' | 'size' | 'date' | 'type' | 'location' | 'deletedDate';
 export type SortDirection = 'asc' | 'desc';
 
+export type GroupField = 'extension' | 'date' | 'size';
+
 export interface SortConfig {
     field: SortField;
     direction: SortDirection;
+    keys?: [SortField, SortDirection][]; // Ordered sort keys, primary first
 }
 
 export interface ColumnWidths {
@@ -107,6 +119,7 @@ export interface FileProperties {
     parent: string;
     is_dir: boolean;
     size: number;
+    size_on_disk: number | null;
     is_calculated: boolean;
     created: number;
     modified: number;
@@ -136,6 +149,7 @@ export interface FileProperties {
 
 export interface FolderSizeResult {
     size: number;
+    size_on_disk: number;
     folders_count: number;
     files_count: number;
 }
@@ -156,6 +170,7 @@ export interface DirResponse {
     entries: FileEntry[];
     summary: FileSummary;
     is_complete: boolean;
+    groups?: GroupKey[]; // Group headers in display order (when listed with groupBy)
 }
 
 export interface DirBatchEvent {
@@ -186,6 +201,7 @@ export interface NetResource {
     provider?: string;
     is_media_device?: boolean;
     has_web_page?: boolean;
+    requires_auth?: boolean;
 }
 
 export interface QuickAccessItem {
@@ -216,7 +232,21 @@ export interface ConflictResponse {
     likely_large: boolean;
 }
 
+export interface SizeEstimate {
+    op_id: string;
+    total_size: number;
+    total_files: number;
+    cancelled: boolean;
+}
+
 export type ConflictAction = 'replace' | 'skip';
+export type ConflictPolicy = 'overwrite' | 'skip' | 'rename' | 'keepNewer';
+
+export interface ExtractSummary {
+    destination: string;
+    skipped: string[];
+    renamed: { from: string; to: string }[];
+}
 
 export type TransactionType = 'copy' | 'move' | 'rename' | 'new_folder' | 'delete';
 
@@ -260,16 +290,40 @@ export interface TransactionDetails {
     new_path?: string;
 }
 
+// Translated in the UI: t(key) with {items} (name, or count) and {target}
+export interface TransactionSummary {
+    key: string;
+    name?: string;
+    count: number;
+    target?: string;
+}
+
 export interface Transaction {
     id: string;
     timestamp: number;
-    op_type: 'Copy' | 'Move' | 'Rename' | 'Delete' | 'NewFolder' | 'Restore';
+    op_type: 'Copy' | 'Move' | 'Rename' | 'Delete' | 'NewFolder' | 'Restore' | 'NewFile' | 'Edit';
     details: TransactionDetails;
+    summary?: TransactionSummary;
 }
 
 export interface HistoryState {
     undo_stack: Transaction[];
     redo_stack: Transaction[];
+    can_undo?: boolean;
+    can_redo?: boolean;
+    undo_depth?: number;
+    redo_depth?: number;
+}
+
+export interface ActivityEntry {
+    id: string;
+    timestamp: number;
+    kind: 'copy' | 'move' | 'delete' | 'trash' | 'extract' | 'compress';
+    sources: string[];
+    destination?: string;
+    item_count: number;
+    total_bytes: number;
+    outcome: { status: 'completed' | 'cancelled' } | { status: 'failed'; message: string };
 }
 
 export type OpStatus =
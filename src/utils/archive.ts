@@ -25,3 +25,18 @@ export const isSupportedArchiveForAdding = (path: string): boolean => {
     const ext = path.split('.').pop()?.toLowerCase();
     return ext === 'zip';
 };
+
+/**
+ * Splits a path pointing inside an archive ("C:\\a.zip\\dir\\file.txt") into the archive
+ * path and the internal path. Returns null for regular paths.
+ */
+export const splitArchivePath = (path: string): { archivePath: string; internalPath: string } | null => {
+    const parts = path.split('\\');
+    for (let i = 1; i < parts.length - 1; i++) {
+        const candidate = parts.slice(0, i + 1).join('\\');
+        if (isArchivePath(parts[i]) || /\.iso$/i.test(parts[i])) {
+            return { archivePath: candidate, internalPath: parts.slice(i + 1).join('/') };
+        }
+    }
+    return null;
+};
@@ -5,6 +5,9 @@ export interface CommandError {
     SystemError?: string;
     ArchiveError?: string;
     TrashError?: string;
+    InsufficientSpace?: string;
+    RecycleBinUnavailable?: string;
+    InvalidConfig?: string;
     Other?: string;
     [key: string]: string | undefined;
 }
@@ -37,6 +40,9 @@ export function formatCommandError(error: unknown): string {
         if (err.SystemError) return `System Error: ${err.SystemError}`;
         if (err.ArchiveError) return `Archive Error: ${err.ArchiveError}`;
         if (err.TrashError) return `Trash Error: ${err.TrashError}`;
+        if (err.InsufficientSpace) return `Insufficient Space: ${err.InsufficientSpace}`;
+        if (err.RecycleBinUnavailable) return `Recycle Bin Unavailable: ${err.RecycleBinUnavailable}`;
+        if (err.InvalidConfig) return `Invalid Config: ${err.InvalidConfig}`;
         if (err.Other) return `Error: ${err.Other}`;
 
         // Standard JS Error or Tauri Error with 'message'
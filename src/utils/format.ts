@@ -1,7 +1,7 @@
 /**
  * Formatting utilities for the file manager
  */
-import { FileEntry, DateFormat } from '../types';
+import { FileEntry, DateFormat, GroupKey, TransactionSummary } from '../types';
 
 /**
  * Formats a byte size into a human-readable string.
@@ -98,3 +98,27 @@ export const getFileTypeString = (entry: FileEntry, t: any): string => {
 
     return '';
 };
+
+/**
+ * Gets the localized header of a group from a grouped listing.
+ * @param key - Group key returned by list_dir
+ * @param t - Translation function
+ * @returns Localized header (e.g. "Yesterday", "PNG files")
+ */
+export const formatGroupKey = (key: GroupKey, t: any): string => {
+    if ('extension' in key) {
+        return t('group_extension', { ext: key.extension.toUpperCase() });
+    }
+    return t(key.label);
+};
+
+/**
+ * Gets the localized description of a history transaction.
+ * @param summary - Summary returned with the transaction
+ * @param t - Translation function
+ * @returns Localized description (e.g. "Moved 3 items to Downloads")
+ */
+export const formatTransactionSummary = (summary: TransactionSummary, t: any): string => {
+    const items = summary.name ?? t('history_items', { count: summary.count });
+    return t(summary.key, { items, target: summary.target ?? '' });
+};